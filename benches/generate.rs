@@ -0,0 +1,58 @@
+//! Generating the readmes of many crates, as `--recursive` does
+//!
+//! `per_crate` sets everything up again for every readme, like `generate_readme_with_options`
+//! does, while `shared` reuses one `Generator`, with its compiled regexes and parsed template,
+//! for all of them.
+
+#[macro_use]
+extern crate criterion;
+extern crate cargo_readme;
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use cargo_readme::{Generator, Options};
+use criterion::Criterion;
+
+const PROJECT: &str = "tests/test-project";
+const CRATES: usize = 50;
+
+fn generate_crates(c: &mut Criterion) {
+    let project_root = Path::new(PROJECT);
+    let source = fs::read_to_string(project_root.join("src/lib.rs")).unwrap();
+    let template = fs::read_to_string(project_root.join("README.tpl")).unwrap();
+    let options = Options::default();
+
+    let generate = |generator: &mut Generator| {
+        generator
+            .generate(
+                project_root,
+                &mut Cursor::new(source.as_bytes()),
+                Some(&mut Cursor::new(template.as_bytes())),
+                &options,
+            )
+            .unwrap()
+    };
+
+    let mut group = c.benchmark_group("generate");
+    group.bench_function("per_crate", |b| {
+        b.iter(|| {
+            for _ in 0..CRATES {
+                generate(&mut Generator::new());
+            }
+        })
+    });
+    group.bench_function("shared", |b| {
+        b.iter(|| {
+            let mut generator = Generator::new();
+            for _ in 0..CRATES {
+                generate(&mut generator);
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, generate_crates);
+criterion_main!(benches);
@@ -0,0 +1,31 @@
+//! Transform arbitrary docs, which must never panic nor loop
+//!
+//!     cargo +nightly fuzz run transform
+
+#![no_main]
+
+use cargo_readme::{transform_str, Options};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // the first byte picks the options, the rest is the docs
+    let (flags, docs) = match data.split_first() {
+        Some((flags, docs)) => (*flags, docs),
+        None => return,
+    };
+    let docs = match std::str::from_utf8(docs) {
+        Ok(docs) => docs,
+        Err(_) => return,
+    };
+
+    let mut options = Options {
+        indent_headings: flags & 1 != 0,
+        ..Options::default()
+    };
+    options.config.feature_notes = flags & 2 != 0;
+    if flags & 4 != 0 {
+        options.config.section_order = vec!["Usage".to_owned(), "Examples".to_owned()];
+    }
+
+    transform_str(docs, &options);
+});
@@ -0,0 +1,170 @@
+//! Record how a readme was generated, and check that it still matches
+//!
+//! With `--attest attestation.json`, a json file is written next to the readme with the version
+//! of cargo-readme, the arguments it was given, and the sha256 hashes of its inputs and of the
+//! readme written:
+//!
+//!     {
+//!       "tool": "cargo-readme",
+//!       "version": "2.0.1",
+//!       "arguments": ["--no-license", "--output", "README.md"],
+//!       "inputs": [
+//!         { "path": "Cargo.toml", "sha256": "..." },
+//!         { "path": "src/lib.rs", "sha256": "..." }
+//!       ],
+//!       "output": { "path": "README.md", "sha256": "..." }
+//!     }
+//!
+//! The inputs are `Cargo.toml`, the source file of the docs and the template file, if there is
+//! one. Paths are relative to the project root. `cargo readme verify-attestation` hashes the
+//! files again and fails if one of them changed.
+
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use clap::ArgMatches;
+use serde_json::{self, Value};
+use sha2::{Digest, Sha256};
+
+use cargo_readme::{builtin, workspace};
+
+const TOOL: &str = "cargo-readme";
+const DEFAULT_TEMPLATE: &str = "README.tpl";
+
+/// Write the attestation of the readme written to `output` to the file given with `--attest`,
+/// both relative to `project_root`
+pub fn write_attestation(
+    m: &ArgMatches,
+    project_root: &Path,
+    output: Option<&str>,
+) -> Result<(), String> {
+    let (file, output) = match (m.value_of("ATTEST"), output) {
+        (Some(file), Some(output)) => (file, output),
+        _ => return Ok(()),
+    };
+
+    let mut inputs = Vec::new();
+    for path in input_files(m, project_root)? {
+        inputs.push(json!({ "path": path, "sha256": hash_file(project_root, &path)? }));
+    }
+    let attestation = json!({
+        "tool": TOOL,
+        "version": crate_version!(),
+        "arguments": arguments(),
+        "inputs": inputs,
+        "output": { "path": output, "sha256": hash_file(project_root, output)? },
+    });
+
+    let path = project_root.join(file);
+    let mut json = serde_json::to_string_pretty(&attestation).map_err(|e| format!("{}", e))?;
+    json.push('\n');
+    File::create(&path)
+        .and_then(|mut file| file.write_all(json.as_bytes()))
+        .map_err(|e| format!("Could not write file '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Check that the inputs and the readme of the attestation at `path` are the ones it was written
+/// for, returning the path of the readme
+pub fn verify_attestation(path: &Path, project_root: &Path) -> Result<String, String> {
+    let mut content = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+    let invalid = || format!("'{}' is not an attestation of cargo-readme", path.to_string_lossy());
+    let attestation: Value = serde_json::from_str(&content).map_err(|_| invalid())?;
+    if attestation["tool"] != TOOL {
+        return Err(invalid());
+    }
+
+    let file = |value: &Value| -> Option<(String, String)> {
+        Some((value["path"].as_str()?.to_owned(), value["sha256"].as_str()?.to_owned()))
+    };
+    let inputs = attestation["inputs"].as_array().ok_or_else(invalid)?;
+    let mut files = Vec::new();
+    for input in inputs {
+        files.push(file(input).ok_or_else(invalid)?);
+    }
+    let (output, output_hash) = file(&attestation["output"]).ok_or_else(invalid)?;
+
+    for (path, hash) in &files {
+        if hash_file(project_root, path)? != *hash {
+            return Err(format!("'{}' changed since the readme was generated", path));
+        }
+    }
+    if hash_file(project_root, &output)? != output_hash {
+        return Err(format!("'{}' changed since it was generated", output));
+    }
+
+    Ok(output)
+}
+
+/// Files the readme is generated from, relative to the project root
+fn input_files(m: &ArgMatches, project_root: &Path) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    // a cargo script has its manifest in its source
+    if project_root.join("Cargo.toml").is_file() {
+        files.push("Cargo.toml".to_owned());
+    }
+
+    let root = project_root.canonicalize().unwrap_or_else(|_| project_root.to_path_buf());
+    let (source, _) = super::get_source(m, project_root)?;
+    let source = source.canonicalize().unwrap_or(source);
+    files.push(workspace::relative_path(&root, &source));
+
+    if m.is_present("NO_TEMPLATE") || m.is_present("RAW") {
+        return Ok(files);
+    }
+    let config = super::get_cargo_info(m, project_root)?.config(m.value_of("CONFIG_PROFILE"))?;
+    match m.value_of("TEMPLATE").or(config.template.as_deref()) {
+        Some(template) if template.starts_with(builtin::PREFIX) => {}
+        Some(template) => files.push(template.to_owned()),
+        None if project_root.join(DEFAULT_TEMPLATE).is_file() => {
+            files.push(DEFAULT_TEMPLATE.to_owned())
+        }
+        None => {}
+    }
+
+    Ok(files)
+}
+
+/// Arguments of the command line after `readme`, without `--attest` and its file
+fn arguments() -> Vec<String> {
+    let mut args = env::args().skip_while(|arg| arg != "readme").skip(1);
+    let mut arguments = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg == "--attest" {
+            args.next();
+        } else if !arg.starts_with("--attest=") {
+            arguments.push(arg);
+        }
+    }
+    arguments
+}
+
+/// Sha256 hash of the file at `path`, relative to `project_root`, in hexadecimal
+fn hash_file(project_root: &Path, path: &str) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    File::open(project_root.join(path))
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|e| format!("Could not read file '{}': {}", path, e))?;
+    Ok(hex(&Sha256::digest(&bytes)))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_in_hexadecimal() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            hex(&Sha256::digest(b""))
+        );
+    }
+}
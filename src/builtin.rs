@@ -0,0 +1,78 @@
+//! Templates embedded in the binary, used with `--template builtin:<name>`
+//!
+//! - `minimal`: title, docs and license
+//! - `badges`: same as minimal, with crates.io and docs.rs badges
+//! - `full`: badges, installation instructions, docs and a license section
+//! - `workspace-index`: docs followed by the list of the other crates of the workspace
+
+/// Prefix of the template names that refer to a built-in template
+pub const PREFIX: &str = "builtin:";
+
+/// Names of the built-in templates
+pub const NAMES: &[&str] = &["minimal", "badges", "full", "workspace-index"];
+
+const BADGES: &str = concat!(
+    "[![crates.io](https://img.shields.io/crates/v/{{crate}}.svg)]",
+    "(https://crates.io/crates/{{crate}})\n",
+    "[![docs.rs](https://docs.rs/{{crate}}/badge.svg)](https://docs.rs/{{crate}})\n",
+);
+
+const INSTALLATION: &str = concat!(
+    "## Installation\n",
+    "\n",
+    "```toml\n",
+    "[dependencies]\n",
+    "{{crate}} = \"{{version | major-minor}}\"\n",
+    "```\n",
+);
+
+/// Content of the built-in template with the given name
+///
+/// The title and license are left out if they should not be rendered, so the template can be
+/// used with `--no-title` and `--no-license`.
+pub fn template(name: &str, title: bool, license: bool) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    if title {
+        parts.push("# {{crate}}\n");
+    }
+    match name {
+        "minimal" => parts.push("{{readme}}\n"),
+        "badges" => parts.extend(&[BADGES, "{{readme}}\n"]),
+        "full" => parts.extend(&[BADGES, INSTALLATION, "{{readme}}\n"]),
+        "workspace-index" => parts.extend(&["{{readme}}\n", "## Crates\n\n{{workspace-crates}}\n"]),
+        _ => return None,
+    }
+    if license {
+        parts.push(match name {
+            "full" => "## License\n\n{{license}}\n",
+            _ => "License: {{license}}\n",
+        });
+    }
+
+    Some(parts.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_template() {
+        let expected = "# {{crate}}\n\n{{readme}}\n\nLicense: {{license}}\n";
+        assert_eq!(Some(expected.to_owned()), template("minimal", true, true));
+        assert_eq!(Some("{{readme}}\n".to_owned()), template("minimal", false, false));
+    }
+
+    #[test]
+    fn full_template() {
+        let template = template("full", true, true).unwrap();
+        assert!(template.contains("https://docs.rs/{{crate}}/badge.svg"));
+        assert!(template.contains("{{crate}} = \"{{version | major-minor}}\""));
+        assert!(template.ends_with("{{readme}}\n\n## License\n\n{{license}}\n"));
+    }
+
+    #[test]
+    fn unknown_template() {
+        assert_eq!(None, template("fancy", true, true));
+    }
+}
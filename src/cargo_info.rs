@@ -1,30 +1,167 @@
 //! Read crate information from `Cargo.toml`
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
 use toml;
 
+use config::Config;
+
 /// Cargo.toml crate information
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 pub struct Cargo {
     pub package: CargoPackage,
     pub lib: Option<CargoLib>,
     pub bin: Option<Vec<CargoLib>>,
+    /// Features of the crate and what they enable
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Dependencies of the crate, by the name they are used with in the code
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, toml::Value>,
 }
 
 /// Cargo.toml crate package information
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 pub struct CargoPackage {
     pub name: String,
+    /// Either a version or `{ workspace = true }`
+    pub version: Option<toml::Value>,
+    /// Either an edition or `{ workspace = true }`
+    pub edition: Option<toml::Value>,
+    pub description: Option<String>,
+    /// Arrays, or `{ workspace = true }`
+    pub authors: Option<toml::Value>,
+    pub keywords: Option<toml::Value>,
+    pub categories: Option<toml::Value>,
     pub license: Option<String>,
+    /// Either a url or `{ workspace = true }`
+    pub repository: Option<toml::Value>,
+    /// Either the path of the readme, or whether there is one
+    pub readme: Option<toml::Value>,
+    pub publish: Option<toml::Value>,
+    pub metadata: Option<CargoMetadata>,
+}
+
+/// Cargo.toml crate package metadata, where tools keep their settings
+#[derive(Clone, Default, Deserialize)]
+pub struct CargoMetadata {
+    pub readme: Option<toml::Value>,
+    /// Settings of other tools, and anything else crates keep there
+    #[serde(flatten)]
+    pub other: BTreeMap<String, toml::Value>,
 }
 
 /// Cargo.toml crate lib information
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Default, Deserialize)]
 pub struct CargoLib {
-    pub path: String,
+    pub name: Option<String>,
+    /// Entry file, when it is not where cargo expects it
+    pub path: Option<String>,
+}
+
+impl Cargo {
+    /// Whether the crate can be published, according to the `publish` field
+    pub fn is_publishable(&self) -> bool {
+        match self.package.publish {
+            Some(toml::Value::Boolean(publish)) => publish,
+            Some(toml::Value::Array(ref registries)) => !registries.is_empty(),
+            _ => true,
+        }
+    }
+
+    /// Name of the library target, as used in code
+    ///
+    /// This is the `name` of the `[lib]` section, or the package name with dashes replaced by
+    /// underscores, like cargo does.
+    pub fn lib_name(&self) -> String {
+        match self.lib.as_ref().and_then(|lib| lib.name.as_ref()) {
+            Some(name) => name.clone(),
+            None => self.package.name.replace('-', "_"),
+        }
+    }
+
+    /// Features enabled by default, including the ones enabled by other default features
+    pub fn default_features(&self) -> Vec<String> {
+        let mut enabled = Vec::new();
+        let mut pending = vec!["default".to_owned()];
+        while let Some(feature) = pending.pop() {
+            for item in self.features.get(&feature).into_iter().flatten() {
+                // `dep:name` and `name/feature` enable dependencies, not features of the crate
+                if !item.contains(':') && !item.contains('/') && !enabled.contains(item) {
+                    enabled.push(item.clone());
+                    pending.push(item.clone());
+                }
+            }
+        }
+        enabled
+    }
+
+    /// Version of the crate, unless it is inherited from the workspace
+    pub fn version(&self) -> Option<&str> {
+        self.package.version.as_ref().and_then(|version| version.as_str())
+    }
+
+    /// Url of the repository, if it is set in the package itself
+    pub fn repository(&self) -> Option<&str> {
+        self.package.repository.as_ref().and_then(|repository| repository.as_str())
+    }
+
+    /// Path of the readme set with the `readme` field, relative to the root of the crate
+    pub fn readme_path(&self) -> Option<&str> {
+        self.package.readme.as_ref().and_then(|readme| readme.as_str())
+    }
+
+    /// Path of the readme shipped with the crate, relative to its root, if there is one
+    ///
+    /// Like cargo, `README.md` is used when the `readme` field is missing or `true`.
+    pub fn readme_file(&self) -> Option<&str> {
+        match self.package.readme {
+            Some(toml::Value::String(ref path)) => Some(path),
+            Some(toml::Value::Boolean(false)) => None,
+            _ => Some("README.md"),
+        }
+    }
+
+    /// Rust edition of the crate, unless it is inherited from the workspace
+    ///
+    /// Crates that do not set one use the 2015 edition.
+    pub fn edition(&self) -> Option<&str> {
+        match self.package.edition {
+            Some(ref edition) => edition.as_str(),
+            None => Some("2015"),
+        }
+    }
+
+    /// Settings from `[package.metadata.readme]`, or the defaults if there are none
+    ///
+    /// If a profile is given, the keys of `[package.metadata.readme.profiles.<profile>]` replace
+    /// the ones with the same name in `[package.metadata.readme]`.
+    pub fn config(&self, profile: Option<&str>) -> Result<Config, String> {
+        let mut settings = match self.package.metadata.as_ref().and_then(|m| m.readme.as_ref()) {
+            Some(toml::Value::Table(table)) => table.clone(),
+            Some(_) => return Err("`package.metadata.readme` must be a table".to_owned()),
+            None => toml::value::Table::new(),
+        };
+
+        let profiles = settings.remove("profiles");
+        if let Some(profile) = profile {
+            let overrides = match profiles.as_ref().and_then(|profiles| profiles.get(profile)) {
+                Some(toml::Value::Table(overrides)) => overrides,
+                Some(_) => return Err(format!("Config profile '{}' must be a table", profile)),
+                None => return Err(format!("Config profile '{}' not found", profile)),
+            };
+            for (key, value) in overrides {
+                settings.insert(key.clone(), value.clone());
+            }
+        }
+
+        toml::Value::Table(settings)
+            .try_into()
+            .map_err(|e| format!("Invalid `package.metadata.readme`: {}", e))
+    }
 }
 
 /// Try to get crate name and license from Cargo.toml
@@ -35,13 +172,12 @@ pub fn get_cargo_info(project_root: &Path) -> Result<Cargo, String> {
     };
 
     let mut buf = String::new();
-    match cargo_toml.read_to_string(&mut buf) {
-        Err(e) => return Err(format!("{}", e)),
-        Ok(_) => {}
+    if let Err(e) = cargo_toml.read_to_string(&mut buf) {
+        return Err(format!("{}", e));
     }
 
     match toml::from_str(&buf) {
-        Err(e) => return Err(format!("{}", e)),
+        Err(e) => Err(format!("{}", e)),
         Ok(cargo) => Ok(cargo),
     }
 }
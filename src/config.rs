@@ -0,0 +1,234 @@
+//! Settings read from the `[package.metadata.readme]` section of `Cargo.toml`
+//!
+//! ```toml
+//! [package.metadata.readme]
+//! demo = [
+//!     { path = "assets/demo.gif", alt = "Demo" },
+//!     { path = "https://asciinema.org/a/123456", alt = "Recording" },
+//! ]
+//! rename-headings = { "Examples" = "Quick start" }
+//! heading-prefixes = { "Quick start" = "🚀" }
+//! section-order = ["Installation", "Quick start"]
+//! license-text = "## License\n\nLicensed under {{license}}."
+//! prepend = "docs/header.md"
+//! append = ["docs/footer.md", "SECURITY-NOTE.md"]
+//! feature-notes = true
+//! funding-footer = true
+//! warn-unreleased = true
+//! contributors-ignore = ["renovate*", "Release Automation"]
+//! forge = "gitlab"
+//! default-branch = "master"
+//! absolute-links = true
+//! ci-badge = { image = "https://ci.example.com/my-crate.svg", link = "https://ci.example.com" }
+//! number-headings = true
+//! code-fence-label = "rs"
+//! keep-fence-attributes = true
+//! title-from-h1 = true
+//! dedupe-title = false
+//! item-links = "target/doc/my_crate.json"
+//! split = { file = "DOCUMENTATION.md", sections = 2 }
+//! combine-headings = { lib = "Library", bin = "Command line tool" }
+//!
+//! # Selected with `--config-profile oss`
+//! [package.metadata.readme.profiles.oss]
+//! template = "README.oss.tpl"
+//! template-delimiters = ["<%", "%>"]
+//!
+//! # Selected with `--config-profile internal`
+//! [package.metadata.readme.profiles.internal]
+//! license = false
+//! strip-external-links = true
+//! reference-links = "section"
+//! max-line-length = 100
+//! ```
+//!
+//! A profile only needs the keys that differ from the main section. Command line flags take
+//! precedence over both.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer};
+
+/// Settings for generating the readme
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    /// Template file, relative to the project root
+    pub template: Option<String>,
+    /// Strings opening and closing the template tags, instead of `{{` and `}}`
+    pub template_delimiters: Option<(String, String)>,
+    /// Whether to render the crate name as title
+    pub title: Option<bool>,
+    /// Whether to render the license
+    pub license: Option<bool>,
+    /// Text of the license added after the docs when there is no template, where `{{license}}`
+    /// is the license of the crate
+    pub license_text: Option<String>,
+    /// Whether to add a level to every heading
+    pub indent_headings: Option<bool>,
+    /// Whether to use the `# Title` the docs start with as the title, instead of the crate name
+    #[serde(default)]
+    pub title_from_h1: bool,
+    /// Whether to leave out the `# Title` the docs start with when it is the crate name, true
+    /// if not set
+    pub dedupe_title: Option<bool>,
+    /// New names of headings of the docs, by their current name
+    #[serde(default)]
+    pub rename_headings: BTreeMap<String, String>,
+    /// Text added before headings of the docs, by their name once renamed, like an emoji
+    #[serde(default)]
+    pub heading_prefixes: BTreeMap<String, String>,
+    /// Titles of the top level sections of the docs to put first, in this order
+    #[serde(default)]
+    pub section_order: Vec<String>,
+    /// Files added before the docs, relative to the project root
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub prepend: Vec<String>,
+    /// Files added after the docs, relative to the project root
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub append: Vec<String>,
+    /// Language label of the rust code blocks, `rust` if not set
+    pub code_fence_label: Option<String>,
+    /// Whether to keep the attributes of the rust code blocks after their label, like
+    /// `rust,no_run`
+    #[serde(default)]
+    pub keep_fence_attributes: bool,
+    /// Whether to replace links to other sites by their text
+    #[serde(default)]
+    pub strip_external_links: bool,
+    /// Whether to make the relative links and images absolute, pointing to the repository
+    #[serde(default)]
+    pub absolute_links: bool,
+    /// Turn the inline links into reference links, defined at the end of the readme or of each
+    /// section
+    pub reference_links: Option<ReferenceLinks>,
+    /// Maximum length of the lines of prose, longer ones being broken between their sentences
+    pub max_line_length: Option<usize>,
+    /// Whether to put every sentence of the prose on its own line, ignoring `max-line-length`
+    #[serde(default)]
+    pub sembr: bool,
+    /// Whether to add a note under examples that need features that are not enabled by default
+    #[serde(default)]
+    pub feature_notes: bool,
+    /// Whether to number the headings of the docs, like `1.` and `1.2.`
+    #[serde(default)]
+    pub number_headings: bool,
+    /// Whether to end the readme with a "Support this project" section listing the sponsor
+    /// links of `.github/FUNDING.yml`
+    #[serde(default)]
+    pub funding_footer: bool,
+    /// Whether to warn when the readme mentions the version of the package and it is newer than
+    /// the latest release, found in `CHANGELOG.md` or the git tags
+    #[serde(default)]
+    pub warn_unreleased: bool,
+    /// Authors left out of the `{{contributors}}` tag, where `*` matches any text
+    #[serde(default)]
+    pub contributors_ignore: Vec<String>,
+    /// Forge hosting the repository, when the host of the `repository` url does not tell it,
+    /// like a self-hosted GitLab
+    pub forge: Option<Forge>,
+    /// Branch the links to the repository point to, `main` if not set
+    pub default_branch: Option<String>,
+    /// Badge rendered by the `{{ci-badge}}` tag, instead of the one of the forge
+    pub ci_badge: Option<Badge>,
+    /// Media rendered by the `{{demo}}` tag
+    #[serde(default)]
+    pub demo: Vec<Media>,
+    /// File listing the public items of the crate, to link their mentions in inline code to
+    /// docs.rs, either rustdoc JSON or a list of kinds and paths, relative to the project root
+    pub item_links: Option<String>,
+    /// rustdoc JSON of the crate to read the docs from instead of the source, relative to the
+    /// project root
+    pub rustdoc_json: Option<String>,
+    /// Whether to read the docs of `src/main.rs` or of `src/lib.rs` first, in crates with both
+    #[serde(default)]
+    pub prefer: Entrypoint,
+    /// Keep only the first sections of the docs in the readme, moving the others to another file
+    pub split: Option<Split>,
+    /// Headings of the docs of the targets combined with `--combine`, by target
+    #[serde(default)]
+    pub combine_headings: BTreeMap<String, String>,
+}
+
+/// A single value or a list of values
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Accept either a string or a list of strings
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// Which sections of the docs stay in the readme, and where the others go
+///
+/// The readme keeps the text before the first heading and the first `sections` top level
+/// sections, one if not set, or the sections titled as in `summary` if there are any, and links
+/// to `file` for the rest.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Split {
+    /// File getting the sections left out of the readme, relative to the project root
+    pub file: String,
+    /// Number of top level sections kept in the readme
+    pub sections: Option<usize>,
+    /// Titles of the top level sections kept in the readme, instead of the first ones
+    #[serde(default)]
+    pub summary: Vec<String>,
+}
+
+/// Where the definitions of reference links go
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReferenceLinks {
+    /// At the end of the readme
+    Document,
+    /// At the end of each section, before the next heading
+    Section,
+}
+
+/// Target whose docs are read first when the crate has both a binary and a library
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Entrypoint {
+    /// `src/main.rs`, then the library
+    #[default]
+    Bin,
+    /// `src/lib.rs`, or the `[lib]` of `Cargo.toml`, then `src/main.rs`
+    Lib,
+}
+
+/// Forges whose badges and links are known
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Forge {
+    Github,
+    Gitlab,
+    Sourcehut,
+    Codeberg,
+}
+
+/// A badge, linking to the page it summarizes
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Badge {
+    /// Url of the image
+    pub image: String,
+    pub link: String,
+}
+
+/// An image, animation or recording showing the crate in action
+#[derive(Clone, Deserialize, Serialize)]
+pub struct Media {
+    /// Path relative to the project root, or url
+    pub path: String,
+    /// Alternative text
+    pub alt: Option<String>,
+    /// Where the media links to, if anywhere
+    pub link: Option<String>,
+}
@@ -0,0 +1,77 @@
+//! Find the crates inside a directory, for generating all their readmes at once
+//!
+//! Directories ignored by `.gitignore` or `.readmeignore` files are skipped, as well as hidden
+//! directories and `target`. `.readmeignore` uses the same syntax as `.gitignore` and is meant for
+//! crates that are in version control but should not get a readme, like test fixtures.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use ignore::Ignore;
+
+const IGNORE_FILES: &[&str] = &[".gitignore", ".readmeignore"];
+
+/// A manifest that may or may not describe a package
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<toml::Value>,
+}
+
+/// Find the directories under `root`, including itself, containing a crate, sorted by path
+pub fn find_crates(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut crates = Vec::new();
+    visit(root, &mut Ignore::default(), &mut crates)?;
+    Ok(crates)
+}
+
+fn visit(dir: &Path, ignore: &mut Ignore, crates: &mut Vec<PathBuf>) -> Result<(), String> {
+    let rules_before = ignore.len();
+    for name in IGNORE_FILES {
+        ignore.add_file(dir, name);
+    }
+
+    if is_package(&dir.join("Cargo.toml")) {
+        crates.push(dir.to_path_buf());
+    }
+
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Could not read directory '{}': {}", dir.to_string_lossy(), e))?;
+    let mut subdirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    subdirs.sort();
+
+    for subdir in subdirs {
+        let name = subdir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if name.starts_with('.') || name == "target" || ignore.is_ignored(&subdir, true) {
+            continue;
+        }
+        visit(&subdir, ignore, crates)?;
+    }
+
+    // rules only apply to the directory of the ignore file
+    ignore.truncate(rules_before);
+    Ok(())
+}
+
+fn is_package(manifest: &Path) -> bool {
+    let mut buf = String::new();
+    if File::open(manifest)
+        .and_then(|mut file| file.read_to_string(&mut buf))
+        .is_err()
+    {
+        return false;
+    }
+
+    toml::from_str::<Manifest>(&buf)
+        .map(|manifest| manifest.package.is_some())
+        .unwrap_or(false)
+}
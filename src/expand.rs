@@ -0,0 +1,258 @@
+//! Read the crate docs from the macro-expanded source
+//!
+//! Docs produced by macros, like `#![doc = include_str!("../intro.md")]` or a procedural macro,
+//! are not written as doc comments in the source. Once expanded, they are plain
+//! `#![doc = "..."]` attributes that can be read back. Expansion uses `cargo expand` if it is
+//! installed, otherwise `cargo rustc -- -Zunpretty=expanded`, which needs a nightly toolchain.
+
+use std::env;
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use cargo_info;
+
+/// Expand the crate target whose entrypoint is `source` and return its docs as `//!` comments
+pub fn expanded_docs(project_root: &Path, source: &Path) -> Result<String, String> {
+    let target = target_args(project_root, source)?;
+    let expanded = expand(project_root, &target)?;
+    Ok(crate_docs(&expanded))
+}
+
+/// Arguments selecting the target of the crate whose entrypoint is `source`
+fn target_args(project_root: &Path, source: &Path) -> Result<Vec<String>, String> {
+    let cargo = cargo_info::get_cargo_info(project_root)?;
+    let is_source = |path: &str| {
+        let path = project_root.join(path);
+        path.canonicalize().unwrap_or(path) == source
+    };
+
+    let lib_path = cargo.lib.as_ref().and_then(|lib| lib.path.as_deref()).unwrap_or("src/lib.rs");
+    if is_source(lib_path) {
+        return Ok(vec!["--lib".to_owned()]);
+    }
+    if is_source("src/main.rs") {
+        return Ok(vec!["--bin".to_owned(), cargo.package.name.clone()]);
+    }
+    for bin in cargo.bin.iter().flatten() {
+        if let (Some(name), Some(path)) = (bin.name.as_ref(), bin.path.as_ref()) {
+            if is_source(path) {
+                return Ok(vec!["--bin".to_owned(), name.clone()]);
+            }
+        }
+    }
+    if let Some(name) = source.file_stem() {
+        if is_source(&format!("src/bin/{}.rs", name.to_string_lossy())) {
+            return Ok(vec!["--bin".to_owned(), name.to_string_lossy().into_owned()]);
+        }
+    }
+
+    Err(format!(
+        "Cannot expand '{}', only the library and the binaries of the crate can be expanded",
+        source.to_string_lossy()
+    ))
+}
+
+/// Run `cargo expand`, or `rustc` through cargo if it is not installed
+fn expand(project_root: &Path, target: &[String]) -> Result<String, String> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+
+    let output = Command::new(&cargo)
+        .arg("expand")
+        .args(target)
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run cargo: {}", e))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stderr.contains("no such command") {
+        return Err(format!("`cargo expand` failed: {}", stderr.trim()));
+    }
+
+    let output = Command::new(&cargo)
+        .arg("rustc")
+        .args(target)
+        .args(["--profile=check", "--", "-Zunpretty=expanded"])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run cargo: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Could not expand the crate, install `cargo expand` or use a nightly toolchain: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Collect the docs of the crate, from the inner attributes and doc comments at the top of the
+/// expanded source, as `//!` comments
+fn crate_docs(expanded: &str) -> String {
+    let mut docs = Vec::new();
+    let mut rest = expanded;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.starts_with("//!") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            docs.push(rest[..end].trim_end().to_owned());
+            rest = &rest[end..];
+        } else if let Some(comment) = rest.strip_prefix("/*!") {
+            let end = comment.find("*/").unwrap_or(comment.len());
+            docs.extend(comment[..end].lines().map(|line| format!("//!{}", line.trim_end())));
+            rest = comment.get(end + 2..).unwrap_or("");
+        } else if rest.starts_with("#![") {
+            let end = match attribute_end(rest) {
+                Some(end) => end,
+                None => break,
+            };
+            if let Some(doc) = doc_attribute(&rest[3..end - 1]) {
+                docs.extend(doc.split('\n').map(|line| format!("//!{}", line.trim_end())));
+            }
+            rest = &rest[end..];
+        } else if rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            rest = &rest[end..];
+        } else {
+            break;
+        }
+    }
+
+    docs.join("\n")
+}
+
+/// Position right after the `]` closing the attribute at the start of `text`
+fn attribute_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if let Some((_, len)) = string_literal(rest) {
+            i += len;
+            continue;
+        }
+        let c = rest.chars().next()?;
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        i += c.len_utf8();
+    }
+    None
+}
+
+/// Value of a `doc = "..."` attribute
+fn doc_attribute(attribute: &str) -> Option<String> {
+    let value = attribute.trim().strip_prefix("doc")?.trim_start().strip_prefix('=')?.trim();
+    match string_literal(value) {
+        Some((text, len)) if len == value.len() => Some(text),
+        _ => None,
+    }
+}
+
+/// Decode the string literal at the start of `text`, returning its value and its length in the
+/// source
+fn string_literal(text: &str) -> Option<(String, usize)> {
+    if let Some(raw) = text.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        let end = body.find(&format!("\"{}", "#".repeat(hashes)))?;
+        return Some((body[..end].to_owned(), 1 + hashes + 1 + end + 1 + hashes));
+    }
+
+    let body = text.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, i + 2)),
+            '\\' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                '0' => value.push('\0'),
+                '\n' => {
+                    // an escaped line break skips the indentation of the next line
+                    while chars.clone().next().is_some_and(|(_, c)| c.is_whitespace()) {
+                        chars.next();
+                    }
+                }
+                'x' => {
+                    let code: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                    value.push(u8::from_str_radix(&code, 16).ok()? as char);
+                }
+                'u' => {
+                    let code: String = chars
+                        .by_ref()
+                        .map(|(_, c)| c)
+                        .take_while(|&c| c != '}')
+                        .filter(|&c| c != '{' && c != '_')
+                        .collect();
+                    value.push(u32::from_str_radix(&code, 16).ok().and_then(::std::char::from_u32)?);
+                }
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docs_from_attributes_and_comments() {
+        let expanded = concat_lines!(
+            "#![feature(prelude_import)]",
+            "//! Written as a comment",
+            "#![doc = \"# Title\\n\\nFrom \\\"include_str\\\"\\n\"]",
+            "#![doc = r#\"raw [text]\"#]",
+            "#![allow(dead_code)]",
+            "#[prelude_import]",
+            "use std::prelude::rust_2018::*;",
+            "//! not crate docs",
+        );
+        let expected = concat!(
+            "//! Written as a comment\n",
+            "//!# Title\n",
+            "//!\n",
+            "//!From \"include_str\"\n",
+            "//!\n",
+            "//!raw [text]",
+        );
+
+        assert_eq!(expected, crate_docs(expanded));
+    }
+
+    #[test]
+    fn string_literal_escapes() {
+        assert_eq!(
+            Some(("a\tb\u{e9}\\".to_owned(), 14)),
+            string_literal("\"a\\tb\\u{e9}\\\\\" rest")
+        );
+        assert_eq!(Some(("a\"b".to_owned(), 8)), string_literal("r#\"a\"b\"#"));
+        assert_eq!(None, string_literal("\"never closed"));
+    }
+
+    #[test]
+    fn only_doc_attributes() {
+        assert_eq!(Some(" text".to_owned()), doc_attribute("doc = \" text\""));
+        assert_eq!(None, doc_attribute("doc(html_root_url = \"https://docs.rs\")"));
+        assert_eq!(None, doc_attribute("allow(unused)"));
+    }
+}
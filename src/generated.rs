@@ -0,0 +1,169 @@
+//! Resolve docs generated by a build script
+//!
+//! Some crates generate their docs at build time and include them from `OUT_DIR`:
+//!
+//! ```rust,ignore
+//! include!(concat!(env!("OUT_DIR"), "/lib_docs.rs"));
+//! // or
+//! #![doc = include_str!(concat!(env!("OUT_DIR"), "/lib_docs.md"))]
+//! ```
+//!
+//! The crate is checked with cargo to run its build script, then these lines are replaced by the
+//! content of the generated files, so the docs can be extracted as usual.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use regex::Regex;
+use serde_json::{self, Value};
+
+const REGEX_OUT_DIR_PATH: &str =
+    r#"concat!\s*\(\s*env!\s*\(\s*"OUT_DIR"\s*\)\s*,\s*"([^"]*)"\s*,?\s*\)"#;
+
+/// Replace the includes of files from `OUT_DIR` in `source` with the content of these files
+///
+/// Cargo is only run if the source refers to `OUT_DIR`.
+pub fn resolve_out_dir_includes(project_root: &Path, source: &str) -> Result<String, String> {
+    if !source.contains("OUT_DIR") {
+        return Ok(source.to_owned());
+    }
+
+    let out_dir = out_dir(project_root)?;
+    replace_includes(source, &out_dir)
+}
+
+fn replace_includes(source: &str, out_dir: &Path) -> Result<String, String> {
+    let re_include =
+        Regex::new(&format!(r"^\s*include!\s*\(\s*{}\s*\)\s*;?\s*$", REGEX_OUT_DIR_PATH)).unwrap();
+    let re_doc = Regex::new(&format!(
+        r"^\s*#!\[\s*doc\s*=\s*include_str!\s*\(\s*{}\s*\)\s*\]\s*$",
+        REGEX_OUT_DIR_PATH
+    ))
+    .unwrap();
+
+    let mut result = Vec::new();
+    for line in source.lines() {
+        if let Some(caps) = re_include.captures(line) {
+            result.push(read_generated(out_dir, &caps[1])?.trim_end().to_owned());
+        } else if let Some(caps) = re_doc.captures(line) {
+            for doc in read_generated(out_dir, &caps[1])?.trim_end().lines() {
+                result.push(format!("//! {}", doc).trim_end().to_owned());
+            }
+        } else {
+            result.push(line.to_owned());
+        }
+    }
+
+    Ok(result.join("\n"))
+}
+
+fn read_generated(out_dir: &Path, file: &str) -> Result<String, String> {
+    let path = out_dir.join(file.trim_start_matches('/'));
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read generated file '{}': {}", path.to_string_lossy(), e))?;
+    Ok(content)
+}
+
+/// Run `cargo check` on the crate and return the `OUT_DIR` of its build script
+fn out_dir(project_root: &Path) -> Result<PathBuf, String> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let output = Command::new(cargo)
+        .args(["check", "--message-format=json"])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run cargo: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`cargo check` failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let manifest = project_root.join("Cargo.toml");
+    find_out_dir(&String::from_utf8_lossy(&output.stdout), &manifest).ok_or_else(|| {
+        format!("The crate in '{}' has no build script", project_root.to_string_lossy())
+    })
+}
+
+/// Find the `OUT_DIR` of the package with the given manifest in the json messages of cargo
+fn find_out_dir(messages: &str, manifest: &Path) -> Option<PathBuf> {
+    let messages: Vec<Value> = messages
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    // build script messages do not tell the manifest, artifact messages of the package do
+    let package_id = messages.iter().find_map(|message| {
+        let path = PathBuf::from(message["manifest_path"].as_str()?);
+        let path = path.canonicalize().unwrap_or(path);
+        if message["reason"] == "compiler-artifact" && path == manifest {
+            message["package_id"].as_str()
+        } else {
+            None
+        }
+    })?;
+
+    messages.iter().find_map(|message| {
+        if message["reason"] == "build-script-executed" && message["package_id"] == package_id {
+            message["out_dir"].as_str().map(PathBuf::from)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn replace_generated_includes() {
+        let out_dir = TempDir::new("cargo-readme-test").unwrap();
+        fs::write(out_dir.path().join("intro.md"), "Generated intro\n\nMore text\n").unwrap();
+        fs::write(out_dir.path().join("usage.rs"), "//! # Usage\n").unwrap();
+
+        let source = concat_lines!(
+            "#![doc = include_str!(concat!(env!(\"OUT_DIR\"), \"/intro.md\"))]",
+            "include!(concat!(env!(\"OUT_DIR\"), \"/usage.rs\"));",
+            "",
+            "pub fn f() {}",
+        );
+        let expected = concat!(
+            "//! Generated intro\n",
+            "//!\n",
+            "//! More text\n",
+            "//! # Usage\n",
+            "\n",
+            "pub fn f() {}",
+        );
+
+        assert_eq!(Ok(expected.to_owned()), replace_includes(source, out_dir.path()));
+    }
+
+    #[test]
+    fn out_dir_of_the_package() {
+        let messages = concat_lines!(
+            r#"{"reason":"compiler-artifact","package_id":"dep 1.0.0","manifest_path":"/dep/Cargo.toml"}"#,
+            r#"{"reason":"build-script-executed","package_id":"dep 1.0.0","out_dir":"/out/dep"}"#,
+            r#"{"reason":"compiler-artifact","package_id":"app 0.1.0","manifest_path":"/app/Cargo.toml"}"#,
+            r#"{"reason":"build-script-executed","package_id":"app 0.1.0","out_dir":"/out/app"}"#,
+        );
+
+        assert_eq!(
+            Some(PathBuf::from("/out/app")),
+            find_out_dir(messages, Path::new("/app/Cargo.toml"))
+        );
+        assert_eq!(None, find_out_dir(messages, Path::new("/other/Cargo.toml")));
+    }
+}
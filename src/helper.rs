@@ -1,11 +1,17 @@
+//! Find the files involved in generating a readme: project root, entrypoint, template and output
+
 use std::env;
-use std::io::{self, Write, ErrorKind};
+use std::io::{self, ErrorKind, Read, Write};
 use std::fs::File;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::{self, Map, Value};
 
+use builtin;
 use cargo_info;
+use config::Entrypoint;
 
-const DEFAULT_TEMPLATE: &'static str = "README.tpl";
+const DEFAULT_TEMPLATE: &str = "README.tpl";
 
 /// Get the project root from given path or defaults to current directory
 ///
@@ -13,8 +19,29 @@ const DEFAULT_TEMPLATE: &'static str = "README.tpl";
 /// as is. If no path is given, the current directory is used.
 /// A `Cargo.toml` file must be present is the root directory.
 pub fn get_project_root(given_root: Option<&str>) -> Result<PathBuf, String> {
+    let root = given_dir(given_root)?;
+
+    if !root.join("Cargo.toml").is_file() {
+        return Err(format!(
+            "`{:?}` does not look like a Rust/Cargo project",
+            root
+        ));
+    }
+
+    Ok(root.canonicalize().unwrap_or(root))
+}
+
+/// Get the directory of a cargo script from given path or defaults to current directory
+///
+/// Like `get_project_root`, without a `Cargo.toml`, as a cargo script embeds its manifest.
+pub fn get_script_root(given_root: Option<&str>) -> Result<PathBuf, String> {
+    let root = given_dir(given_root)?;
+    Ok(root.canonicalize().unwrap_or(root))
+}
+
+fn given_dir(given_root: Option<&str>) -> Result<PathBuf, String> {
     let current_dir = env::current_dir().map_err(|e| format!("{}", e))?;
-    let root = match given_root {
+    Ok(match given_root {
         Some(root) => {
             let root = Path::new(root);
             if root.is_absolute() {
@@ -24,37 +51,90 @@ pub fn get_project_root(given_root: Option<&str>) -> Result<PathBuf, String> {
             }
         }
         None => current_dir,
-    };
+    })
+}
 
-    if !root.join("Cargo.toml").is_file() {
+/// Get the source file from which the doc comments will be extracted, along with its path
+///
+/// The input can be an absolute path or a path relative to the project root, which may point
+/// outside of it, like to generated code in `target/`. The returned path is canonicalized.
+/// Without an input, the entrypoint is found as `find_entrypoint` does.
+pub fn get_source(
+    project_root: &Path,
+    input: Option<&str>,
+    prefer: Entrypoint,
+) -> Result<(PathBuf, File), String> {
+    match input {
+        Some(input) => {
+            let input = project_root.join(input);
+            let file = File::open(&input).map_err(|e| {
+                format!("Could not open file '{}': {}", input.to_string_lossy(), e)
+            })?;
+            Ok((input.canonicalize().unwrap_or(input), file))
+        }
+        None => find_entrypoint(project_root, prefer),
+    }
+}
+
+/// Check that `output`, relative to `project_root`, can be written to, and where it points
+///
+/// Files outside of the project root, including through `..` or symbolic links, are refused
+/// unless `allow_outside_root` is set, since a path computed by a script can easily point
+/// somewhere unexpected.
+pub fn check_dest(
+    project_root: &Path,
+    output: &str,
+    allow_outside_root: bool,
+) -> Result<PathBuf, String> {
+    let root = project_root.canonicalize().unwrap_or_else(|_| project_root.to_path_buf());
+    let resolved = resolve(&root.join(output));
+    if !allow_outside_root && !resolved.starts_with(&root) {
         return Err(format!(
-            "`{:?}` does not look like a Rust/Cargo project",
-            root
+            "'{}' is outside of the project root, use `--allow-outside-root` to write there \
+             anyway",
+            output
         ));
     }
 
-    Ok(root)
+    Ok(resolved)
 }
 
-/// Get the source file from which the doc comments will be extracted
-pub fn get_source(project_root: &Path, input: Option<&str>) -> Result<File, String> {
-    match input {
-        Some(input) => {
-            let input = project_root.join(input);
-            File::open(&input).map_err(|e| {
-                format!("Could not open file '{}': {}", input.to_string_lossy(), e)
-            })
+/// Whether `path`, as returned by `check_dest`, is in the `target` directory of the project,
+/// which `cargo clean` removes
+pub fn is_in_target(project_root: &Path, path: &Path) -> bool {
+    let root = project_root.canonicalize().unwrap_or_else(|_| project_root.to_path_buf());
+    path.starts_with(root.join("target"))
+}
+
+/// Where `path` points, without requiring it to exist, following the symbolic links of the
+/// directories that do
+fn resolve(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other.as_os_str()),
         }
-        None => find_entrypoint(&project_root),
+    }
+
+    let parent = resolved.parent().and_then(|parent| parent.canonicalize().ok());
+    match (parent, resolved.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => resolved,
     }
 }
 
 /// Get the destination file where the result will be output to
+///
+/// `None` and `-` both mean stdout.
 pub fn get_dest(project_root: &Path, output: Option<&str>) -> Result<Option<File>, String> {
     match output {
-        Some(filename) => {
+        Some(filename) if filename != "-" => {
             let output = project_root.join(filename);
-            File::create(&output).map(|f| Some(f)).map_err(|e| {
+            File::create(&output).map(Some).map_err(|e| {
                 format!(
                     "Could not create output file '{}': {}",
                     output.to_string_lossy(),
@@ -62,6 +142,37 @@ pub fn get_dest(project_root: &Path, output: Option<&str>) -> Result<Option<File
                 )
             })
         }
+        _ => Ok(None),
+    }
+}
+
+/// Get the template that will be used to render the output, as a file or a built-in template
+///
+/// Templates named `builtin:<name>` are built-in, they leave out the title and license when
+/// `title` and `license` are false. Others are files, see `get_template_file`.
+pub fn get_template(
+    project_root: &Path,
+    template: Option<&str>,
+    title: bool,
+    license: bool,
+) -> Result<Option<String>, String> {
+    if let Some(name) = template.and_then(|template| template.strip_prefix(builtin::PREFIX)) {
+        return builtin::template(name, title, license).map(Some).ok_or_else(|| {
+            format!(
+                "Unknown built-in template '{}', expected one of: {}",
+                name,
+                builtin::NAMES.join(", ")
+            )
+        });
+    }
+
+    match get_template_file(project_root, template)? {
+        Some(mut file) => {
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .map_err(|e| format!("Could not read template file: {}", e))?;
+            Ok(Some(content))
+        }
         None => Ok(None),
     }
 }
@@ -72,7 +183,7 @@ pub fn get_template_file(project_root: &Path, template: Option<&str>) -> Result<
         // template path was given, try to read it
         Some(template) => {
             let template = project_root.join(template);
-            File::open(&template).map(|f| Some(f)).map_err(|e| {
+            File::open(&template).map(Some).map_err(|e| {
                 format!(
                     "Could not open template file '{}': {}",
                     template.to_string_lossy(),
@@ -87,7 +198,7 @@ pub fn get_template_file(project_root: &Path, template: Option<&str>) -> Result<
                 Ok(file) => Ok(Some(file)),
                 // do not generate an error on file not found
                 Err(ref e) if e.kind() != ErrorKind::NotFound => {
-                    return Err(format!(
+                    Err(format!(
                         "Could not open template file '{}': {}",
                         DEFAULT_TEMPLATE,
                         e
@@ -101,24 +212,71 @@ pub fn get_template_file(project_root: &Path, template: Option<&str>) -> Result<
 }
 
 /// Write result to output, either stdout or destination file
+///
+/// Nothing else is ever written to stdout, so it can be redirected to a file or piped.
 pub fn write_output(dest: &mut Option<File>, readme: String) -> Result<(), String> {
+    let mut bytes = readme.into_bytes();
+    // Append new line at end of file to match behavior of `cargo readme > README.md`
+    bytes.push(b'\n');
+
     match dest.as_mut() {
         Some(dest) => {
-            let mut bytes = readme.into_bytes();
-            // Append new line at end of file to match behavior of `cargo readme > README.md`
-            bytes.push(b'\n');
-
-            dest.write_all(&mut bytes).map(|_| ()).map_err(|e| {
+            dest.write_all(&bytes).map_err(|e| {
                 format!("Could not write to output file: {}", e)
             })?;
         }
-        None => println!("{}", readme),
+        None => {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            stdout
+                .write_all(&bytes)
+                .and_then(|_| stdout.flush())
+                .map_err(|e| format!("Could not write to stdout: {}", e))?;
+        }
     }
 
     Ok(())
 }
 
-/// Find the default entrypoiny to read the doc comments from
+/// Write the anchors of the headings of the readme to a json file, under the name of the profile
+///
+/// The file maps each profile to an object mapping the text of the headings to their anchor, so
+/// it can collect the anchors of the readme of every profile. The anchors of other profiles
+/// already in the file are kept. When a heading is repeated, the anchor of the first one is used.
+pub fn write_anchors(path: &Path, profile: &str, anchors: &[(String, String)]) -> Result<(), String> {
+    let mut profiles = match File::open(path) {
+        Ok(mut file) => {
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+            match serde_json::from_str(&content) {
+                Ok(Value::Object(profiles)) => profiles,
+                _ => {
+                    return Err(format!(
+                        "'{}' is not a json object of anchors",
+                        path.to_string_lossy()
+                    ))
+                }
+            }
+        }
+        Err(ref e) if e.kind() == ErrorKind::NotFound => Map::new(),
+        Err(e) => return Err(format!("Could not read file '{}': {}", path.to_string_lossy(), e)),
+    };
+
+    let mut map = Map::new();
+    for (heading, anchor) in anchors {
+        map.entry(heading.clone()).or_insert_with(|| Value::String(anchor.clone()));
+    }
+    profiles.insert(profile.to_owned(), Value::Object(map));
+
+    let mut json = serde_json::to_string_pretty(&profiles).map_err(|e| format!("{}", e))?;
+    json.push('\n');
+    File::create(path)
+        .and_then(|mut file| file.write_all(json.as_bytes()))
+        .map_err(|e| format!("Could not write file '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Find the default entrypoint to read the doc comments from, returning its path and file
 ///
 /// Try to read entrypoint in the following order:
 /// - src/main.rs
@@ -126,66 +284,46 @@ pub fn write_output(dest: &mut Option<File>, readme: String) -> Result<(), Strin
 /// - file defined in the `[lib]` section of Cargo.toml
 /// - file defined in the `[[bin]]` section of Cargo.toml, if there is only one
 ///   - if there is more than one `[[bin]]`, an error is returned
-pub fn find_entrypoint(current_dir: &Path) -> Result<File, String> {
-    let lib_rs = current_dir.join("src/lib.rs");
-    let main_rs = current_dir.join("src/main.rs");
-
-    let cargo = try!(cargo_info::get_cargo_info(current_dir));
-
-    // try src/main.rs
-    match File::open(&main_rs) {
-        Ok(file) => return Ok(file),
-        Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
-            return Err(format!(
-                "Could not open file '{}': {}",
-                main_rs.to_string_lossy(),
-                e
-            ))
-        }
-        _ => {}
-    }
+///
+/// With `Entrypoint::Lib`, the library files are tried before `src/main.rs`.
+pub fn find_entrypoint(
+    current_dir: &Path,
+    prefer: Entrypoint,
+) -> Result<(PathBuf, File), String> {
+    let cargo = cargo_info::get_cargo_info(current_dir)?;
 
-    // try src/lib.rs
-    match File::open(&lib_rs) {
-        Ok(file) => return Ok(file),
-        Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
-            return Err(format!(
-                "Could not open file '{}': {}",
-                lib_rs.to_string_lossy(),
-                e
-            ))
-        }
-        _ => {}
+    let main_rs = current_dir.join("src/main.rs");
+    let mut libs = vec![current_dir.join("src/lib.rs")];
+    // lib defined in `Cargo.toml`
+    if let Some(lib_path) = cargo.lib.and_then(|lib| lib.path) {
+        libs.push(current_dir.join(lib_path));
     }
+    let candidates: Vec<PathBuf> = match prefer {
+        Entrypoint::Bin => Some(main_rs).into_iter().chain(libs).collect(),
+        Entrypoint::Lib => libs.into_iter().chain(Some(main_rs)).collect(),
+    };
 
-    // try lib defined in `Cargo.toml`
-    match cargo.lib {
-        Some(lib) => {
-            match File::open(current_dir.join(&lib.path)) {
-                Ok(file) => return Ok(file),
-                Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
-                    return Err(format!(
-                        "Could not open file '{}': {}",
-                        current_dir.join(&lib.path).to_string_lossy(),
-                        e
-                    ))
-                }
-                _ => {}
+    for path in candidates {
+        match File::open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
+                return Err(format!("Could not open file '{}': {}", path.to_string_lossy(), e))
             }
+            _ => {}
         }
-        _ => {}
     }
 
     // try bin defined in `Cargo.toml`
     match cargo.bin {
         // if there is only one, use it
-        Some(ref bin_list) if bin_list.len() == 1 => {
-            match File::open(current_dir.join(&bin_list[0].path)) {
-                Ok(file) => return Ok(file),
+        Some(ref bin_list) if bin_list.len() == 1 && bin_list[0].path.is_some() => {
+            let bin_path = current_dir.join(bin_list[0].path.as_ref().unwrap());
+            match File::open(&bin_path) {
+                Ok(file) => return Ok((bin_path, file)),
                 Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
                     return Err(format!(
                         "Could not open file '{}': {}",
-                        current_dir.join(&bin_list[0].path).to_string_lossy(),
+                        bin_path.to_string_lossy(),
                         e
                     ))
                 }
@@ -194,12 +332,11 @@ pub fn find_entrypoint(current_dir: &Path) -> Result<File, String> {
         }
         // if there is more than one, return an error
         Some(ref bin_list) if bin_list.len() > 1 => {
-            let first = bin_list[0].path.clone();
             let paths = bin_list
                 .iter()
-                .skip(1)
-                .map(|ref bin| bin.path.clone())
-                .fold(first, |acc, path| format!("{}, {}", acc, path));
+                .map(|bin| bin.path.clone().or_else(|| bin.name.clone()).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(", ");
             return Err(format!("Multiple binaries found, choose one: [{}]", paths));
         }
         _ => {}
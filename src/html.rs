@@ -0,0 +1,138 @@
+//! Render the readme to HTML, styled close to the way GitHub shows it
+//!
+//! The markdown is rendered with the extensions of GitHub, tables, task lists, strikethrough and
+//! footnotes, and the fenced code blocks are highlighted by the language of their fence, with
+//! inline styles so the page needs nothing but itself.
+
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+/// Theme of the highlighted code
+const THEME: &str = "InspiredGitHub";
+
+/// Style of the page, close to the one of GitHub
+pub const CSS: &str = r#"
+body { margin: 0; background: #fff; color: #1f2328; }
+.markdown-body {
+    box-sizing: border-box; max-width: 980px; margin: 0 auto; padding: 45px;
+    font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", "Noto Sans", Helvetica, Arial,
+        sans-serif;
+    font-size: 16px; line-height: 1.5; word-wrap: break-word;
+}
+.markdown-body h1, .markdown-body h2, .markdown-body h3, .markdown-body h4, .markdown-body h5,
+.markdown-body h6 { margin-top: 24px; margin-bottom: 16px; font-weight: 600; line-height: 1.25; }
+.markdown-body h1 { font-size: 2em; padding-bottom: .3em; border-bottom: 1px solid #d1d9e0; }
+.markdown-body h2 { font-size: 1.5em; padding-bottom: .3em; border-bottom: 1px solid #d1d9e0; }
+.markdown-body h3 { font-size: 1.25em; }
+.markdown-body a { color: #0969da; text-decoration: none; }
+.markdown-body a:hover { text-decoration: underline; }
+.markdown-body p, .markdown-body ul, .markdown-body ol, .markdown-body pre,
+.markdown-body table, .markdown-body blockquote { margin-top: 0; margin-bottom: 16px; }
+.markdown-body code {
+    padding: .2em .4em; font-size: 85%; background: rgba(129, 139, 152, .12); border-radius: 6px;
+    font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace;
+}
+.markdown-body pre {
+    padding: 16px; overflow: auto; font-size: 85%; background: #f6f8fa; border-radius: 6px;
+    font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace;
+}
+.markdown-body pre code { padding: 0; font-size: 100%; background: transparent; }
+.markdown-body blockquote { padding: 0 1em; color: #59636e; border-left: .25em solid #d1d9e0; }
+.markdown-body table { border-collapse: collapse; }
+.markdown-body th, .markdown-body td { padding: 6px 13px; border: 1px solid #d1d9e0; }
+.markdown-body img { max-width: 100%; }
+.error { padding: 16px; color: #d1242f; background: #ffebe9; border-radius: 6px; }
+"#;
+
+/// Body of the readme, rendered to HTML
+pub fn to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let syntaxes = SyntaxSet::load_defaults_newlines();
+    let themes = ThemeSet::load_defaults();
+    let theme = &themes.themes[THEME];
+
+    // the code of a fenced block is gathered, then replaced by its highlighted HTML
+    let mut code: Option<(String, String)> = None;
+    let events = Parser::new_ext(markdown, options).filter_map(|event| match event {
+        Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+            let language = info.split([',', ' ']).next().unwrap_or("").to_owned();
+            code = Some((language, String::new()));
+            None
+        }
+        Event::Text(text) if code.is_some() => {
+            code.as_mut().unwrap().1.push_str(&text);
+            None
+        }
+        Event::End(TagEnd::CodeBlock) if code.is_some() => {
+            let (language, text) = code.take().unwrap();
+            let syntax = syntaxes
+                .find_syntax_by_token(&language)
+                .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+            let html = highlighted_html_for_string(&text, &syntaxes, syntax, theme)
+                .unwrap_or_else(|_| format!("<pre><code>{}</code></pre>\n", escape(&text)));
+            Some(Event::Html(CowStr::from(html)))
+        }
+        event => Some(event),
+    });
+
+    let mut body = String::new();
+    html::push_html(&mut body, events);
+    body
+}
+
+/// Standalone HTML page of the readme, with its style embedded
+pub fn page(title: &str, markdown: &str) -> String {
+    format!(
+        concat!(
+            "<!DOCTYPE html>\n",
+            "<html>\n",
+            "<head>\n",
+            "<meta charset=\"utf-8\">\n",
+            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n",
+            "<title>{}</title>\n",
+            "<style>{}</style>\n",
+            "</head>\n",
+            "<body>\n",
+            "<article class=\"markdown-body\">\n{}</article>\n",
+            "</body>\n",
+            "</html>\n",
+        ),
+        escape(title),
+        CSS,
+        to_html(markdown)
+    )
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_fenced_code() {
+        let markdown = "# my-crate\n\n```rust,no_run\nlet answer = 42;\n```\n\n    plain <code>\n";
+        let html = to_html(markdown);
+
+        assert!(html.contains("<h1>my-crate</h1>"));
+        assert!(html.contains("<pre style=\"background-color:#ffffff;\">"));
+        assert!(html.contains("<span style=\"font-weight:bold;color:#a71d5d;\">let</span>"));
+        assert!(html.contains("<pre><code>plain &lt;code&gt;\n</code></pre>"));
+    }
+
+    #[test]
+    fn standalone_page() {
+        let page = page("a & b", "Docs\n");
+
+        assert!(page.starts_with("<!DOCTYPE html>\n"));
+        assert!(page.contains("<title>a &amp; b</title>"));
+        assert!(page.contains("<p>Docs</p>"));
+    }
+}
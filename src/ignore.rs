@@ -0,0 +1,188 @@
+//! Match paths against `.gitignore`-style files
+//!
+//! Supports the usual syntax: `#` comments, `!` to re-include a path, a trailing `/` to only
+//! match directories, a leading or inner `/` to anchor the pattern to the directory of the
+//! ignore file, and the `*`, `?`, `[...]` and `**` wildcards. The last matching pattern wins.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use regex::{self, Regex};
+
+struct Rule {
+    /// Directory containing the file the rule comes from
+    base: PathBuf,
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Ignore rules collected from ignore files
+#[derive(Default)]
+pub struct Ignore {
+    rules: Vec<Rule>,
+}
+
+impl Ignore {
+    /// Add the rules in the file `name` inside `dir`, if it exists
+    pub fn add_file(&mut self, dir: &Path, name: &str) {
+        let file = match File::open(dir.join(name)) {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            self.add_pattern(dir, &line);
+        }
+    }
+
+    /// Add a single pattern relative to `base`
+    pub fn add_pattern(&mut self, base: &Path, pattern: &str) {
+        let pattern = pattern.trim_end();
+        if pattern.is_empty() || pattern.starts_with('#') {
+            return;
+        }
+
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern.trim_start_matches('\\')),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+
+        if let Some(regex) = pattern_to_regex(pattern, anchored) {
+            self.rules.push(Rule {
+                base: base.to_path_buf(),
+                regex,
+                negated,
+                dir_only,
+            });
+        }
+    }
+
+    /// Number of rules
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Drop the rules added after the first `len` ones
+    pub fn truncate(&mut self, len: usize) {
+        self.rules.truncate(len);
+    }
+
+    /// Check if `path` should be ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+
+            let relative = match path.strip_prefix(&rule.base) {
+                Ok(relative) => relative,
+                Err(_) => continue,
+            };
+            let relative: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+
+            if rule.regex.is_match(&relative.join("/")) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// Turn a glob pattern into a regex matching a path relative to the ignore file directory
+fn pattern_to_regex(pattern: &str, anchored: bool) -> Option<Regex> {
+    let mut re = String::from(if anchored { "^" } else { "^(.*/)?" });
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                re.push('[');
+                for c in chars.by_ref() {
+                    re.push(c);
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    re.push_str(&regex::escape(&c.to_string()));
+                }
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    re.push('$');
+    Regex::new(&re).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Ignore;
+
+    fn ignore(patterns: &[&str]) -> Ignore {
+        let mut ignore = Ignore::default();
+        for pattern in patterns {
+            ignore.add_pattern(Path::new("/root"), pattern);
+        }
+        ignore
+    }
+
+    #[test]
+    fn match_at_any_depth() {
+        let ignore = ignore(&["target", "# comment", ""]);
+        assert!(ignore.is_ignored(Path::new("/root/target"), true));
+        assert!(ignore.is_ignored(Path::new("/root/crates/a/target"), true));
+        assert!(!ignore.is_ignored(Path::new("/root/targets"), true));
+    }
+
+    #[test]
+    fn match_anchored() {
+        let ignore = ignore(&["/tests/", "vendor/*/generated"]);
+        assert!(ignore.is_ignored(Path::new("/root/tests"), true));
+        assert!(!ignore.is_ignored(Path::new("/root/tests"), false));
+        assert!(!ignore.is_ignored(Path::new("/root/crates/tests"), true));
+        assert!(ignore.is_ignored(Path::new("/root/vendor/a/generated"), true));
+        assert!(!ignore.is_ignored(Path::new("/root/vendor/a/b/generated"), true));
+    }
+
+    #[test]
+    fn match_double_star() {
+        let ignore = ignore(&["fixtures/**/out"]);
+        assert!(ignore.is_ignored(Path::new("/root/fixtures/out"), true));
+        assert!(ignore.is_ignored(Path::new("/root/fixtures/a/b/out"), true));
+    }
+
+    #[test]
+    fn negate_pattern() {
+        let ignore = ignore(&["fixtures/*", "!fixtures/keep"]);
+        assert!(ignore.is_ignored(Path::new("/root/fixtures/other"), true));
+        assert!(!ignore.is_ignored(Path::new("/root/fixtures/keep"), true));
+    }
+}
@@ -0,0 +1,97 @@
+//! Set up a crate to generate its readme with `cargo readme init`
+//!
+//! Writes a `README.tpl` made from one of the built-in templates, adds a
+//! `[package.metadata.readme]` section to `Cargo.toml` and, if asked to, a GitHub Actions workflow
+//! checking that the readme is up to date. Files that already exist are never overwritten.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use builtin;
+use cargo_info;
+
+const TEMPLATE_FILE: &str = "README.tpl";
+const WORKFLOW_FILE: &str = ".github/workflows/readme.yml";
+
+const METADATA: &str = concat!(
+    "\n",
+    "[package.metadata.readme]\n",
+    "# Settings of cargo-readme, the command line flags take precedence\n",
+    "template = \"README.tpl\"\n",
+    "title = true\n",
+    "license = true\n",
+    "indent-headings = true\n",
+);
+
+const WORKFLOW: &str = concat!(
+    "name: Readme\n",
+    "\n",
+    "on: [push, pull_request]\n",
+    "\n",
+    "jobs:\n",
+    "  readme:\n",
+    "    runs-on: ubuntu-latest\n",
+    "    steps:\n",
+    "      - uses: actions/checkout@v4\n",
+    "      - run: cargo install cargo-readme\n",
+    "      - run: cargo readme check --granular\n",
+);
+
+/// What `init` sets up
+pub struct InitOptions {
+    /// Name of the built-in template to start from
+    pub template: String,
+    /// Whether to add a GitHub Actions workflow running `cargo readme check`
+    pub ci: bool,
+}
+
+/// Set up the crate in `project_root`, returning the files that were created or changed
+pub fn init(project_root: &Path, options: &InitOptions) -> Result<Vec<PathBuf>, String> {
+    let cargo = cargo_info::get_cargo_info(project_root)?;
+    let template_path = project_root.join(TEMPLATE_FILE);
+    if template_path.exists() {
+        return Err(format!("'{}' already exists", TEMPLATE_FILE));
+    }
+
+    let mut changed = Vec::new();
+
+    let template = builtin::template(&options.template, true, cargo.package.license.is_some())
+        .ok_or_else(|| format!("Unknown built-in template '{}'", options.template))?;
+    write_new(&template_path, &template)?;
+    changed.push(PathBuf::from(TEMPLATE_FILE));
+
+    let has_metadata = cargo
+        .package
+        .metadata
+        .as_ref()
+        .is_some_and(|metadata| metadata.readme.is_some());
+    if !has_metadata {
+        let manifest = project_root.join("Cargo.toml");
+        OpenOptions::new()
+            .append(true)
+            .open(&manifest)
+            .and_then(|mut file| file.write_all(METADATA.as_bytes()))
+            .map_err(|e| format!("Could not write to '{}': {}", manifest.to_string_lossy(), e))?;
+        changed.push(PathBuf::from("Cargo.toml"));
+    }
+
+    let workflow_path = project_root.join(WORKFLOW_FILE);
+    if options.ci && !workflow_path.exists() {
+        if let Some(dir) = workflow_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| {
+                format!("Could not create directory '{}': {}", dir.to_string_lossy(), e)
+            })?;
+        }
+        write_new(&workflow_path, WORKFLOW)?;
+        changed.push(PathBuf::from(WORKFLOW_FILE));
+    }
+
+    Ok(changed)
+}
+
+fn write_new(path: &Path, content: &str) -> Result<(), String> {
+    File::create(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("Could not write '{}': {}", path.to_string_lossy(), e))
+}
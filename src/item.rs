@@ -0,0 +1,189 @@
+//! Extract the doc comment of a single item of the source, like `Client::connect`
+//!
+//! The item is found by its path in the file, through the modules, `impl` blocks and traits
+//! written in it: `Client::connect` is the `connect` function of an `impl` block of `Client`,
+//! or of a `Client` module or trait. Modules in other files are not followed. The source is read
+//! line by line, the way the crate docs are, so an item must start on its own line, after its
+//! `///` comments and attributes.
+
+use regex::Regex;
+
+const REGEX_ITEM: &str = concat!(
+    r"^(?:pub(?:\([^)]*\))?\s+)?",
+    r#"(?:(?:default|const|async|unsafe|extern(?:\s+"[^"]*")?)\s+)*"#,
+    r"(?:fn|struct|enum|union|trait|type|const|static|mod|macro_rules!)\s*([A-Za-z_]\w*)",
+);
+const REGEX_IMPL: &str = r"^(?:unsafe\s+)?impl\b(?:<.*?>)?\s+(?:.+?\s+for\s+)?(?:\w+::)*([A-Za-z_]\w*)";
+const REGEX_CONTAINER: &str = r"^(?:pub(?:\([^)]*\))?\s+)?(?:unsafe\s+)?(?:mod|trait)\s+([A-Za-z_]\w*)";
+
+/// Doc comment of the item at `path` in `source`, as `//!` comments to generate a readme from
+///
+/// The docs start with the path of the item as a `# Title` heading.
+pub fn item_docs(source: &str, path: &str) -> Result<String, String> {
+    let segments: Vec<&str> = path.split("::").map(|segment| segment.trim()).collect();
+    if segments.iter().any(|segment| segment.is_empty()) {
+        return Err(format!("Invalid item path '{}'", path));
+    }
+
+    let lines = find_docs(source, &segments)
+        .ok_or_else(|| format!("Item '{}' not found in the source", path))?;
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return Err(format!("Item '{}' has no doc comment", path));
+    }
+
+    let mut docs = format!("//! # `{}`\n//!\n", path);
+    for line in lines {
+        docs.push_str("//! ");
+        docs.push_str(&line);
+        docs.push('\n');
+    }
+    Ok(docs)
+}
+
+/// Lines of the doc comment of the item, without their `///`, if the item is found
+fn find_docs(source: &str, segments: &[&str]) -> Option<Vec<String>> {
+    let item = Regex::new(REGEX_ITEM).unwrap();
+    let implementation = Regex::new(REGEX_IMPL).unwrap();
+    let container = Regex::new(REGEX_CONTAINER).unwrap();
+    let name = |re: &Regex, line: &str| re.captures(line).map(|caps| caps[1].to_owned());
+
+    // depth of the braces, and the depth of the body of each container of the path entered
+    let mut depth = 0;
+    let mut scopes: Vec<isize> = Vec::new();
+    let mut docs = Vec::new();
+
+    for line in source.lines() {
+        let line = line.trim();
+        if line.starts_with("///") && !line.starts_with("////") {
+            let text = line.trim_start_matches("///");
+            docs.push(text.strip_prefix(' ').unwrap_or(text).trim_end().to_owned());
+            continue;
+        }
+        // attributes, comments and blank lines can be between the docs and the item
+        if line.is_empty() || line.starts_with("#[") || line.starts_with("//") {
+            depth += braces(line);
+            continue;
+        }
+
+        if depth == scopes.last().cloned().unwrap_or(0) {
+            let segment = segments[scopes.len()];
+            if scopes.len() + 1 == segments.len() {
+                if name(&item, line).as_deref() == Some(segment) {
+                    return Some(docs);
+                }
+            } else if name(&container, line).as_deref() == Some(segment)
+                || name(&implementation, line).as_deref() == Some(segment)
+            {
+                scopes.push(depth + 1);
+            }
+        }
+        docs.clear();
+
+        depth += braces(line);
+        // leave the containers that are closed, the item may be in another `impl` block
+        while scopes.last().is_some_and(|&scope| depth < scope) {
+            scopes.pop();
+        }
+    }
+
+    None
+}
+
+/// Change of the depth of the braces after a line, not counting the braces in strings, chars and
+/// comments
+fn braces(line: &str) -> isize {
+    let chars: Vec<char> = line.chars().collect();
+    let mut change = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if chars.get(i + 1) == Some(&'/') => break,
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            // a char like '{' or '\'', not a lifetime
+            '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+            '\'' if chars.get(i + 1) == Some(&'\\') => {
+                i += 2;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += 1;
+                }
+            }
+            '{' => change += 1,
+            '}' => change -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    change
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = concat_lines!(
+        "//! Crate docs",
+        "",
+        "/// A client",
+        "pub struct Client {",
+        "    /// Not an item",
+        "    url: String,",
+        "}",
+        "",
+        "impl fmt::Display for Client {",
+        "    /// Display docs",
+        "    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, \"{}\", '}') }",
+        "}",
+        "",
+        "impl Client {",
+        "    /// Connect to the server",
+        "    ///",
+        "    /// Fails if it is down.",
+        "    #[inline]",
+        "    pub fn connect(&self) {",
+        "        // {",
+        "    }",
+        "}",
+        "",
+        "pub mod tools {",
+        "    /// Run the tools",
+        "    pub async fn run() {}",
+        "}",
+        "",
+        "pub fn undocumented() {}",
+    );
+
+    #[test]
+    fn find_item_docs() {
+        assert_eq!(Some(vec!["A client".to_owned()]), find_docs(SOURCE, &["Client"]));
+        let connect = vec!["Connect to the server", "", "Fails if it is down."];
+        assert_eq!(
+            Some(connect.into_iter().map(|line| line.to_owned()).collect()),
+            find_docs(SOURCE, &["Client", "connect"])
+        );
+        assert_eq!(Some(vec!["Display docs".to_owned()]), find_docs(SOURCE, &["Client", "fmt"]));
+        assert_eq!(Some(vec!["Run the tools".to_owned()]), find_docs(SOURCE, &["tools", "run"]));
+        assert_eq!(Some(Vec::new()), find_docs(SOURCE, &["undocumented"]));
+        assert_eq!(None, find_docs(SOURCE, &["run"]));
+        assert_eq!(None, find_docs(SOURCE, &["Client", "url"]));
+    }
+
+    #[test]
+    fn item_docs_with_title() {
+        let expected = concat_lines!(
+            "//! # `tools::run`",
+            "//!",
+            "//! Run the tools",
+        );
+        assert_eq!(Ok(expected.to_owned()), item_docs(SOURCE, "tools::run"));
+        assert!(item_docs(SOURCE, "undocumented").is_err());
+        assert!(item_docs(SOURCE, "tools::").is_err());
+    }
+}
@@ -2,13 +2,44 @@
 
 #[macro_use] extern crate serde_derive;
 
+extern crate flate2;
 extern crate regex;
+extern crate serde;
+extern crate serde_json;
+extern crate tar;
 extern crate toml;
 
+#[cfg(test)]
+extern crate proptest;
+
 #[cfg(test)]
 #[macro_use] mod test_macros;
 
+mod ignore;
 mod readme;
+mod tempdir;
+pub mod builtin;
 pub mod cargo_info;
+pub mod config;
+pub mod discover;
+pub mod expand;
+pub mod generated;
+pub mod helper;
+pub mod init;
+pub mod item;
+pub mod migrate;
+pub mod package;
+pub mod pandoc;
+pub mod reexport;
+pub mod script;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod version_sync;
+pub mod workspace;
 
-pub use readme::generate_readme;
+pub use readme::{generate_readme, generate_readme_with_options, Options, Readme, Warning};
+pub use readme::{Generator, SplitDocs};
+pub use readme::{DEFAULT_MAX_OUTPUT_BYTES, MAX_SANDBOXED_SIZE};
+pub use readme::{heading_anchors, transform_str};
+pub use readme::{combined_docs, compare_sections, is_up_to_date, unified_diff, Difference};
+pub use readme::{doc_stats, DocStats};
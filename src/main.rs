@@ -19,7 +19,39 @@
 //!     cargo readme > README.md
 //!
 //! And that's it! Your `README.md` is populated with the contents of the doc comments from your
-//! `lib.rs` (or `main.rs`).
+//! `lib.rs` (or `main.rs`). Only the readme is written to stdout, warnings and errors go to
+//! stderr, so the output is safe to redirect or pipe.
+//!
+//! When `Cargo.toml` sets where the readme is, like `readme = "docs/README.md"`, the readme is
+//! written there instead, since it is the file `cargo publish` ships. `--output` still picks
+//! another file, with a warning, and `--stdout-only` writes to stdout. Files outside of the
+//! project root are refused unless `--allow-outside-root` is given, and the ones in `target/` get
+//! a warning, since `cargo clean` removes them.
+//!
+//! To paste the readme in a web form, like the editor of GitHub, `--copy` puts it on the system
+//! clipboard instead. It needs the `clipboard` feature:
+//!
+//!     cargo install cargo-readme --features clipboard
+//!
+//! For sites that do not render markdown, `--format html` writes the readme as a standalone HTML
+//! page instead, with its style embedded and the code blocks highlighted, like
+//! `cargo readme --format html --output README.html`. It needs the `html` feature.
+//!
+//! Handouts in other formats are made with [pandoc](https://pandoc.org), which must be installed:
+//! `cargo readme --pandoc-to pdf --output crate.pdf` converts the readme and writes it to
+//! `crate.pdf`, and any other output format of pandoc, like `odt` or `docx`, works the same way.
+//!
+//! `cargo readme stats` shows the size of the crate docs: doc comment lines, sections, examples,
+//! with how many are `ignore` or `no_run`, words and an estimated reading time. With
+//! `--recursive --json`, it writes the stats of every crate of a workspace for a dashboard.
+//!
+//! `cargo readme check` can also keep the docs from getting too thin: `--min-examples N` fails
+//! when they have fewer than N rust code blocks, and `--require-intro-words N` when they have
+//! fewer than N words before their first heading.
+//!
+//! To see how the readme will look, `cargo readme serve` renders it to HTML and serves it on
+//! <http://127.0.0.1:8000/>, or the port given with `--port`. The page reloads whenever a file
+//! of the crate changes. It needs the `serve` feature.
 //!
 //! # Usage
 //!
@@ -60,6 +92,16 @@
 //! - code block became "```rust"
 //! - hidden line `# assert_eq!(4, sum2(2, 2));` was removed
 //!
+//! The license line can be worded differently with the `license-text` setting, where
+//! `{{license}}` is the license of the crate, for example as a section of its own:
+//!
+//!     [package.metadata.readme]
+//!     license-text = "## License\n\nLicensed under {{license}}."
+//!
+//! Without a template, the title and license can also be put elsewhere than at the start and
+//! the end of the readme, by writing `<!-- readme:title -->` and `<!-- readme:license -->` on
+//! lines of their own in the doc comments, like under a row of badges.
+//!
 //! `cargo-readme` also supports multiline doc comments `/*! */` (but you cannot mix styles):
 //!
 //!     /*!
@@ -76,6 +118,22 @@
 //!     ```
 //!     */
 //!
+//! As with rustdoc, `//!` comments can be spread between inner attributes, like `#![no_std]` or
+//! `#![cfg_attr(...)]`, and other comments at the top of the file, they all make the crate docs.
+//! In a `main.rs` written as a cargo script, the shebang line and the `---` manifest before
+//! them are skipped.
+//!
+//! A single-file tool, written as a cargo script without a `Cargo.toml`, gets a readme too with
+//! `cargo readme --input tool.rs`: the crate information is read from the manifest embedded in
+//! the script, in its `---` frontmatter or in a `cargo` code block of its docs, which is left out
+//! of the readme, and the crate is named after the file unless the manifest names it.
+//!
+//! Docs that start with their own `# Title` would get two titles, which `cargo-readme` warns
+//! about. With `--set-title-from-h1`, or `title-from-h1 = true` in the settings, that heading is
+//! the title of the readme instead of the crate name, and is not indented like the others. When
+//! the heading is the crate name, ignoring case and `-` versus `_`, it is left out instead, unless
+//! `dedupe-title = false` is set.
+//!
 //! If you have additional information that does not fit in doc comments, you can use a template.
 //! Just create a file called `README.tpl` in the same directory as `Cargo.toml` with the following
 //! content:
@@ -113,18 +171,418 @@
 //!
 //! By default, `README.tpl` will be used as the template, but you can override it using the
 //! `--template` to choose a different template or `--no-template` to disable it.
+//!
+//! Built-in templates can be used without adding any file to the repository, with
+//! `--template builtin:minimal`, `builtin:badges` for crates.io and docs.rs badges, `builtin:full`
+//! for installation instructions too, or `builtin:workspace-index` for the root crate of a
+//! workspace.
+//!
+//! `cargo readme init` writes a `README.tpl` to start from, with badges and installation
+//! instructions if you pick the `badges` or `full` template, adds a `[package.metadata.readme]`
+//! section to `Cargo.toml` and can add a GitHub Actions workflow running `cargo readme check`.
+//! It asks what to set up, unless `--yes` is given.
+//!
+//! Crates using cargo-rdme or cargo-doc2readme can move with `cargo readme migrate --from
+//! cargo-rdme`: the readme, with `{{readme}}` in place of its `<!-- cargo-rdme -->` markers, or
+//! the `README.j2` template becomes `README.tpl`, and the settings that have an equivalent are
+//! added to `Cargo.toml`. What cannot be converted, like Jinja blocks, is listed to be done by
+//! hand.
+//!
+//! Besides `{{crate}}` and `{{license}}`, `{{version}}` is replaced by the version of the crate
+//! and `{{lib}}` by the name of its library as used in code, with underscores instead of dashes
+//! or the name given in the `[lib]` section. `{{edition}}` is the Rust edition of the crate and
+//! `{{toolchain}}` the channel pinned in its `rust-toolchain.toml` file. To record what the
+//! readme was generated from, `{{git-sha}}` is the current commit and `{{git-tag}}` its
+//! description by `git describe --tags`.
+//! For copyright lines and "last updated" footers, `{{year}}` is the current year and `{{date}}`
+//! the current date, as `2024-05-01` or in the format given, like `{{date "%B %e, %Y"}}`. They
+//! are taken from the `SOURCE_DATE_EPOCH` environment variable instead when it is set.
+//! Values can be reshaped with filters, like `{{crate | replace "-" "_"}}` for the name used in
+//! code, `{{version | major-minor}}` for a dependency line or `{{license | spdx-short}}`. The
+//! `lower` and `upper` filters are available too.
+//!
+//! Values known outside of the crate, like download counts fetched by CI, are given with
+//! `--var downloads=1234567` and rendered by `{{downloads}}`. `{{downloads | humanize}}` rounds
+//! them to `1.2M`, and `{{size | bytes}}` writes a size in bytes like `1.5 KiB`.
+//!
+//! With `--crates-io`, the template can also show the crate as published: `{{published-version}}`
+//! for a "latest release" callout, `{{total-downloads}}` and `{{owners}}`. They are fetched with
+//! `curl` and cached for an hour in `target/cargo-readme/crates-io`, which is also used when
+//! crates.io cannot be reached.
+//!
+//! With `--docs-rs`, the status of the docs.rs build of the latest version is fetched the same
+//! way, cached in `target/cargo-readme/docs-rs`. `{{docsrs-ok}}` is `true` or `false`, and there
+//! is a warning when the build failed, as the links of the readme to the docs are broken.
+//!
+//! Arrays of `Cargo.toml` can be looped over, for credits or a row of keyword badges. The text
+//! between `{{#each authors}}` and `{{/each}}` is repeated for every author, with `{{this}}`
+//! replaced by it. `keywords`, `categories` and arrays under `[package.metadata]`, like
+//! `metadata.credits`, work the same, and `{{this.name}}` is a field of items that are tables:
+//!
+//!     {{#each metadata.credits}}
+//!     - [{{this.name}}]({{this.url}})
+//!     {{/each}}
+//!
+//! The crates of a workspace can share a base template and only change parts of it. The base
+//! template marks these parts with `{{block usage}}` and `{{/block}}`, and the template of a crate
+//! starts with `{{extends "../README.base.tpl"}}`, followed by the blocks it replaces. Blocks it
+//! leaves out keep the content of the base template:
+//!
+//!     {{extends "../README.base.tpl"}}
+//!
+//!     {{block usage}}
+//!     Run `cargo add {{crate}}`.
+//!     {{/block}}
+//!
+//! To write a tag in the template without it being replaced, escape it as `\{{crate}}` or put it
+//! between `{{{{raw}}}}` and `{{{{/raw}}}}`. The doc comments are never searched for tags, so
+//! they need no escaping.
+//!
+//! Notes for the maintainers of the template go in comments, `{{! note }}`, or `{{!-- note --}}`
+//! when the note has `}}` in it. They are left out of the readme, and so is their line when
+//! nothing else is on it.
+//!
+//! Readmes that need `{{` in their text, like Helm charts or Jinja examples, can write the tags
+//! of their template between other delimiters, set in `Cargo.toml`:
+//!
+//!     [package.metadata.readme]
+//!     template-delimiters = ["<%", "%>"]
+//!
+//! The template then has `<% readme %>` tags, and `{{ .Values.image }}` is kept as it is.
+//!
+//! A tag starting with `{{-` removes the whitespace before it, line breaks included, and a tag
+//! ending with `-}}` the whitespace after it. Sections that can be empty, like a `{{#each}}` over
+//! an array the crate may not have, then leave no blank lines behind:
+//!
+//!     {{#each keywords}}
+//!     - {{this}}
+//!     {{/each -}}
+//!
+//! Templates can also embed the usage of your binary with `{{cli-help}}`, which runs the built
+//! binary with `--help` and puts its output in a code block. You can give the command to run
+//! instead, like `{{cli-help "target/debug/my-tool help subcommand"}}`. Commands are run from an
+//! empty temporary directory, and you can forbid running them at all with `--no-exec`. To render
+//! templates you do not trust, use `--sandbox`, which also keeps tags from reading files outside
+//! of the project root and limits the size of the readme to 1 MiB. Any readme larger than 16 MiB
+//! is an error, as it most likely comes from a template including files in a loop; the limit is
+//! set with `--max-output-bytes`.
+//!
+//! If the binary cannot be run while generating the readme, `{{cli-reference "cli.json"}}` renders
+//! the same information as Commands and Options sections from a json description of the command
+//! line interface.
+//!
+//! Files kept elsewhere in the repository, like configuration examples, can be embedded as code
+//! blocks with `{{code "examples/config.toml"}}`, or just some of their lines with
+//! `{{code "src/bin/tool.rs" lang="rust" lines="1-40"}}`.
+//!
+//! To show code that is compiled along with the crate, mark it in any `.rs` file of the crate
+//! between `// readme:snippet-begin name` and `// readme:snippet-end` comments, then embed it
+//! with `{{snippet "name"}}`.
+//!
+//! Benchmark results can be kept up to date too: `{{include-bench "target/criterion"}}` renders a
+//! table with the results of the last Criterion run.
+//!
+//! In a workspace, `{{workspace-crates}}` lists the other crates of the workspace with their
+//! descriptions, linking to their directories, or to crates.io with
+//! `{{workspace-crates links="crates.io"}}`.
+//!
+//! To generate the readmes of every crate in a directory at once, use `--recursive`. Directories
+//! ignored by `.gitignore` or `.readmeignore` files are skipped; the latter uses the same syntax
+//! and is meant for crates that are committed but should not get a readme, like test fixtures.
+//!
+//! When the crate docs are not written as doc comments, like with
+//! `#![doc = include_str!("../intro.md")]` or docs produced by a macro, use `--expand` to read
+//! them from the macro-expanded source. This needs `cargo expand`, or a nightly toolchain.
+//! Docs generated by a build script and included from `OUT_DIR` only need `--after-build`,
+//! which runs `cargo check` and reads the generated files.
+//!
+//! Crates with both `src/main.rs` and `src/lib.rs` get the docs of `src/main.rs`. For the docs
+//! of the library, use `--prefer-lib`, or set `prefer = "lib"` in `[package.metadata.readme]`,
+//! which `--prefer-bin` overrides.
+//!
+//! Facade crates, which are little more than `pub use inner::*;`, can take their docs from the
+//! crate they re-export with `--follow-reexport-docs`, as long as it is a path dependency, or
+//! from any file with `--input ../inner/src/lib.rs`. The title and license are still the ones of
+//! the facade.
+//!
+//! Crates with both a library and a command line tool can have the docs of both in the readme
+//! with `--combine lib,bin`, each under its own heading. The headings default to "As a library"
+//! and "As a CLI" and are set with `combine-headings = { lib = "...", bin = "..." }`.
+//!
+//! Finding no doc comments with text in the source file is an error, as the readme would only
+//! have its title and license, which usually means the docs are somewhere else. Use
+//! `--allow-empty` to generate it anyway.
+//!
+//! To check in CI that the readme is up to date, run `cargo readme check`, which fails if
+//! `README.md` is not what would be generated. With `--granular`, it reports which sections and
+//! code blocks are out of date instead, which is easier to review in a long readme.
+//!
+//! A readme can be up to date with a template that writes versions by hand, like
+//! `my-crate = "1.2"` in an installation section, while the crate moved on. `--check-versions`
+//! also fails when the install snippets of the readme give versions of the crate, or of its path
+//! dependencies like the other crates of its workspace, that differ from their `Cargo.toml`.
+//!
+//! Published crates can be checked too: `cargo readme check --from-package my-crate-1.0.0.crate`
+//! regenerates the readme from the packaged sources and compares it with the one in the package,
+//! and `--from-crates-io my-crate@1.0.0` downloads the package first.
+//!
+//! Before publishing, `cargo readme diff-upstream` shows what the next publish will change in the
+//! readme, as a diff from the readme of the latest version on crates.io to the one generated
+//! now. `--upstream-version 1.0.0` compares with another published version instead.
+//!
+//! To preview the readme of a crate that does not use `cargo-readme` yet, generate it straight
+//! from its repository with `cargo readme --git https://github.com/user/repo`, optionally with
+//! `--rev` to pick a branch, tag or commit, and `--project-root` for a crate in a subdirectory.
+//!
+//! To make sure the readme is regenerated when the docs change, you can also enable the `test-support` feature
+//! of `cargo-readme` in your `[dev-dependencies]` and add this test:
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate cargo_readme;
+//!
+//! #[test]
+//! fn readme_is_up_to_date() {
+//!     assert_readme_up_to_date!("README.md");
+//! }
+//! ```
+//!
+//! # Configuration
+//!
+//! Settings are read from the `[package.metadata.readme]` section of `Cargo.toml`. For instance,
+//! the media rendered by the `{{demo}}` tag are listed like this:
+//!
+//!     [package.metadata.readme]
+//!     demo = [
+//!         { path = "assets/demo.gif", alt = "Demo" },
+//!         { path = "https://asciinema.org/a/123456", alt = "Recording" },
+//!     ]
+//!
+//! Images are embedded, asciinema recordings are embedded through their preview and anything
+//! else is linked. Local files that do not exist are reported as warnings.
+//!
+//! The sponsor links of `.github/FUNDING.yml`, in the crate or at the root of its repository,
+//! are rendered by the `{{funding}}` tag. With `funding-footer = true`, they are added at the end
+//! of the readme under a "Support this project" heading instead, so every crate of an
+//! organization asks for support the same way without a template.
+//!
+//! `{{contributors}}` lists the authors of the commits of the git repository, the most active
+//! first, as given by `git shortlog -sn`. Git runs once for all the crates of a repository, and
+//! not at all with `--no-exec`. The list stops after 100 authors, or `{{contributors limit=20}}`.
+//! Bots, whose name ends with `[bot]`, are left out, with the authors matching a pattern of
+//! `contributors-ignore`, where `*` matches any text:
+//!
+//!     [package.metadata.readme]
+//!     contributors-ignore = ["renovate*", "Release Automation"]
+//!
+//! `{{ci-badge}}` is the CI status badge of the repository set in the `repository` field of
+//! `Cargo.toml`, and `{{source-url "examples/basic.rs"}}` the url of the page of a file of the
+//! crate, for links to it. Both follow the forge hosting the repository: GitHub, GitLab,
+//! sourcehut and Codeberg are told apart by their host, and a self-hosted one is set with
+//! `forge = "gitlab"`. Links point to the `main` branch, or to `default-branch`. On GitHub, the
+//! badge is the one of the `ci.yml` workflow, or `{{ci-badge workflow="test.yml"}}`. Another CI
+//! gets its own badge with the `ci-badge` setting:
+//!
+//!     [package.metadata.readme]
+//!     default-branch = "master"
+//!     ci-badge = { image = "https://ci.example.com/my-crate.svg", link = "https://ci.example.com" }
+//!
+//! Relative links and images, like `[the guide](docs/guide.md)`, only work where the readme is
+//! next to the files of the repository. `--absolute-links` or `absolute-links = true` makes them
+//! point to the forge instead, to the page of the file for links and to its raw content for
+//! images, for crates.io and the other sites showing the readme.
+//!
+//! Policies that every readme must include come from their file in the repository too:
+//! `{{security}}` embeds `SECURITY.md` and `{{msrv-policy}}` embeds `MSRV.md`, without their
+//! title. They are looked for in the crate, in `.github/` and in `docs/`, up to the root of the
+//! repository. `{{security mode="link"}}` links to the file instead.
+//!
+//! Files can be added around the docs without writing a template, for a header with badges or a
+//! footer shared by several crates:
+//!
+//!     [package.metadata.readme]
+//!     prepend = "docs/header.md"
+//!     append = ["docs/footer.md", "SECURITY-NOTE.md"]
+//!
+//! The top level sections of the docs can be put in a different order in the readme, for instance
+//! to show how to install the crate before the examples. Sections that are not listed keep their
+//! order after the listed ones:
+//!
+//!     [package.metadata.readme]
+//!     section-order = ["Installation", "Examples"]
+//!
+//! Sections titled "Rustdoc-only", at any level, are left out of the readme, along with their
+//! subsections. Use them for content that only makes sense on docs.rs, like links to items.
+//!
+//! Headings can be renamed too, when the readme should use different terms than docs.rs:
+//!
+//!     [package.metadata.readme]
+//!     rename-headings = { "Examples" = "Quick start" }
+//!
+//! and given a prefix, like an emoji, for the style of the readme while the docs stay neutral
+//! on docs.rs. Headings are named as they are once renamed, in `heading-prefixes` and in the
+//! settings that come after it, like `section-order`, where they have their prefix:
+//!
+//!     [package.metadata.readme]
+//!     heading-prefixes = { "Quick start" = "🚀", "Installation" = "📦" }
+//!
+//! Mentions of the public items of the crate in inline code, like `` `Client` `` or `` `run()` ``,
+//! become links to their page on docs.rs with `--item-links FILE` or `item-links = "FILE"`. The
+//! items are read from the JSON output of rustdoc, which needs a nightly toolchain:
+//!
+//!     cargo +nightly rustdoc -- -Z unstable-options --output-format json
+//!     cargo readme --item-links target/doc/my_crate.json
+//!
+//! or from a list with the kind and the path of an item on each line, like `struct client::Client`
+//! or `fn run`.
+//!
+//! The rustdoc JSON can also replace the source file, with `--rustdoc-json FILE` or
+//! `rustdoc-json = "FILE"`. The docs are then the ones rustdoc sees, with `cfg_attr` and
+//! `include_str!` already resolved, and intra-doc links, like ``[`Client`]``, point to the pages
+//! of the items instead of being left broken. The public items mentioned in inline code are
+//! linked too, unless `--item-links` names another file.
+//!
+//! Headings are numbered, like `## 1. Usage` and `### 1.2. Errors`, with `--number-headings` or
+//! `number-headings = true`, for documentation standards that require numbered sections. The
+//! anchors written by `--emit-anchors` include the numbers.
+//!
+//! Release tools, like cargo-release or release-plz, can generate the readme of the upcoming
+//! release before bumping the version of `Cargo.toml` with `--override-version 1.3.0`, which
+//! renders the readme as if it was the version of the crate.
+//!
+//! As a hook of release tools, `--hook` makes cargo-readme quiet and predictable: only errors
+//! are written to stderr, the readme always goes to a file, never stdout, and the exit code is 0
+//! on success, 1 if the readme cannot be generated and 2 if the command line is invalid. The
+//! version being released is read from `NEW_VERSION` and nothing is written when `DRY_RUN` is
+//! `true`, as set by cargo-release:
+//!
+//!     [package.metadata.release]
+//!     pre-release-hook = ["cargo", "readme", "--hook"]
+//!
+//! Install instructions mentioning the version, like `cargo install my-crate --version 1.3.0`,
+//! are wrong until it is published. With `--warn-unreleased` or `warn-unreleased = true`, there
+//! is a warning when the readme mentions the version of `Cargo.toml` and it is newer than the
+//! latest release, the first version heading of `CHANGELOG.md`, or the latest git tag.
+//!
+//! Items can have their own page too: `--item Client::connect` generates the document from the
+//! doc comment of the `connect` method of `Client` instead of the crate docs, with the path of
+//! the item as title. The item is looked up in the source file, through the modules, traits and
+//! `impl` blocks written in it.
+//!
+//! To process the docs with another tool, `--raw` outputs the doc comments as they are, with no
+//! template, title or license and without indenting the headings, rewriting the code blocks or
+//! removing their hidden lines. The source file is still found the same way.
+//!
+//! Rust code blocks are labeled `rust` in the readme. Renderers that prefer another label get
+//! it with `code-fence-label = "rs"`, and `keep-fence-attributes = true` keeps the attributes
+//! of the block after the label, like `rust,no_run`.
+//!
+//! Inline links become reference links, like `[the docs][1]`, with `--reference-links document`
+//! or `reference-links = "document"`, which gathers their destinations at the end of the readme.
+//! With `section` they are gathered at the end of each section instead. Long urls then stay out
+//! of the text, as some style guides require, and out of the lines that change in diffs.
+//!
+//! For one sentence per line, or the line length of a style guide, `--max-line-length 100` or
+//! `max-line-length = 100` breaks the longer lines of prose between their sentences, which
+//! renders the same. Code blocks, tables, headings, inline code and links are never broken, and
+//! a sentence longer than the limit is left whole. `--sembr` or `sembr = true` puts every sentence
+//! on its own line instead, as semantic line breaks, so diffs of the readme show which sentences
+//! changed.
+//!
+//! Link targets used across the crates of a workspace can be defined once, in a
+//! `readme-links.toml` at the root of the crate or of its workspace:
+//!
+//!     book = "https://doc.rust-lang.org/book/"
+//!     guidelines = "https://example.com/guidelines"
+//!
+//! Readmes that use one of these labels, like `[the book][book]` or `[guidelines]`, without
+//! defining it get its definition added at the end.
+//!
+//! When the crate docs are too long for a landing page, `--split DOCUMENTATION.md` keeps only
+//! the text before the first heading and the first section in the readme, which then ends with
+//! a "Read more" link to `DOCUMENTATION.md`, where the other sections go. The `split` setting
+//! can keep more sections, or the ones with the given titles:
+//!
+//!     [package.metadata.readme]
+//!     split = { file = "DOCUMENTATION.md", summary = ["Installation", "Usage"] }
+//!
+//! With `feature-notes = true`, examples that need features of the crate that are not enabled by
+//! default get a note like "Requires the `serde` feature." under them. The features are taken
+//! from the `cfg(feature = "...")` attributes in the example, which can be on hidden lines.
+//!
+//! The section can also set `template`, `title`, `license` and `indent-headings`, the same as the
+//! command line flags, which take precedence. To produce different flavors of the readme, put
+//! the settings that change in profiles and pick one with `--config-profile`:
+//!
+//!     [package.metadata.readme.profiles.internal]
+//!     license = false
+//!     strip-external-links = true
+//!
+//! To see which settings a readme is generated with, `--print-config` prints each of them with
+//! its value and where it comes from: the default, `[package.metadata.readme]`, the profile or a
+//! command line flag. `--print-config json` prints them as json instead of toml.
+//!
+//! Tools linking into the readme can get its anchors with `--emit-anchors anchors.json`, which
+//! writes the anchor of each heading under the name of the profile, `default` without
+//! `--config-profile`. Running it once per profile collects the anchors of every flavor.
+//!
+//! Packagers can regenerate the readme as part of a reproducible build: `{{date}}` and
+//! `{{year}}` follow `SOURCE_DATE_EPOCH`, and nothing else depends on the time. `--reproducible`
+//! fails when it is not set, and warns about the tags that need more than the sources:
+//! `{{git-sha}}`, `{{git-tag}}` and `{{contributors}}` read the git history, which source
+//! archives leave out. `{{cli-help}}` shows the help of the binary of the crate as built, and
+//! documents converted with `--pandoc-to` get the dates pandoc gives them, which follow
+//! `SOURCE_DATE_EPOCH` too.
+//!
+//! To show where a readme comes from, `--attest attestation.json` writes the version of
+//! cargo-readme, its arguments, and the sha256 hashes of `Cargo.toml`, the source file, the
+//! template and the readme to a json file. `cargo readme verify-attestation attestation.json`
+//! fails if any of them changed since.
 
 #[macro_use] extern crate clap;
 
 extern crate cargo_readme;
+#[macro_use] extern crate serde_json;
+extern crate sha2;
+#[cfg(feature = "clipboard")]
+extern crate arboard;
+#[cfg(feature = "html")]
+extern crate pulldown_cmark;
+#[cfg(feature = "html")]
+extern crate syntect;
 
-use std::io::{self, Write};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 
 use clap::{Arg, ArgMatches, App, AppSettings, SubCommand};
 
-use cargo_readme::cargo_info;
+use cargo_readme::{cargo_info, discover, expand, generated, helper, item, pandoc, reexport};
+use cargo_readme::version_sync;
+use cargo_readme::script;
+use cargo_readme::builtin;
+use cargo_readme::{Generator, Readme};
+use cargo_readme::config::{Config, Entrypoint, ReferenceLinks, Split};
+use cargo_readme::init::{self, InitOptions};
+use cargo_readme::migrate::{self, Tool};
+use cargo_readme::package::{self, Package};
+
+mod attest;
+#[cfg(feature = "html")]
+mod html;
+mod message;
+mod print_config;
+#[cfg(feature = "serve")]
+mod serve;
+
+use message::MessageFormat;
 
-mod helper;
+/// Exit code when the readme cannot be generated
+const EXIT_FAILURE: i32 = 1;
+/// Exit code of an invalid command line with `--hook`, which otherwise is that of clap
+const EXIT_USAGE: i32 = 2;
 
 fn main() {
     let matches = App::new("cargo-readme")
@@ -139,105 +597,1413 @@ fn main() {
         .subcommand(SubCommand::with_name("readme")
             .author("Livio Ribeiro <livioribeiro@outlook.com>")
             .about("Generate README.md from doc comments")
-            .arg(Arg::with_name("INPUT")
-                .short("i")
-                .long("input")
-                .takes_value(true)
-                .help("File to read from.{n}\
-                       If not provided, will try to use `src/main.rs`, then `src/lib.rs`. If \
-                       neither file could be found, will look into `Cargo.toml` for a `[lib]`, \
-                       then for a single `[[bin]]`. If multiple binaries are found, you will be \
-                       asked to choose one."))
+            .args(&generate_args())
             .arg(Arg::with_name("OUTPUT")
                 .short("o")
                 .long("output")
                 .takes_value(true)
-                .help("File to write to. If not provided, will output to stdout."))
-            .arg(Arg::with_name("ROOT")
-                .short("r")
-                .long("project-root")
+                .help("File to write to. If not provided, or if it is `-`, will output to \
+                       stdout.{n}\
+                       Defaults to the `readme` field of `Cargo.toml` when it is a path."))
+            .arg(Arg::with_name("ALLOW_OUTSIDE_ROOT")
+                .long("allow-outside-root")
+                .help("Allow writing the readme, and the other files given with `--split` or \
+                       `--emit-anchors`, outside of the project root.{n}\
+                       Without it, files that end up outside of the project root, through `..`, \
+                       an absolute path or a symbolic link, are refused."))
+            .arg(Arg::with_name("STDOUT_ONLY")
+                .long("stdout-only")
+                .conflicts_with_all(&["OUTPUT", "RECURSIVE"])
+                .help("Write the readme to stdout and nothing else.{n}\
+                       This is what happens without `--output` too, unless `Cargo.toml` sets \
+                       the `readme` path. The flag makes it explicit for scripts. Warnings, \
+                       errors and the output of commands run for the template never go to \
+                       stdout."))
+            .arg(Arg::with_name("COPY")
+                .long("copy")
+                .conflicts_with_all(&["OUTPUT", "STDOUT_ONLY", "RECURSIVE"])
+                .help("Put the readme on the system clipboard instead of writing it.{n}\
+                       To paste it in a web form, like the editor of GitHub. Needs cargo-readme \
+                       to be built with the `clipboard` feature."))
+            .arg(Arg::with_name("FORMAT")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["markdown", "html"])
+                .default_value("markdown")
+                .help("Format to write the readme in.{n}\
+                       'html' writes a standalone page, with its style embedded and the code \
+                       blocks highlighted, for sites that do not render markdown. Needs \
+                       cargo-readme to be built with the `html` feature."))
+            .arg(Arg::with_name("PANDOC_TO")
+                .long("pandoc-to")
                 .takes_value(true)
-                .help("Directory to be set as project root (where `Cargo.toml` is){n}\
-                       Defaults to the current directory."))
-            .arg(Arg::with_name("TEMPLATE")
-                .short("t")
-                .long("template")
+                .value_name("FORMAT")
+                .conflicts_with_all(&["STDOUT_ONLY", "COPY"])
+                .help("Convert the readme with pandoc to FORMAT, like 'pdf', 'odt' or 'docx'.{n}\
+                       The document is written to the file given with `--output`. Pandoc must \
+                       be installed, PDF also needs a LaTeX engine."))
+            .arg(Arg::with_name("HOOK")
+                .long("hook")
+                .conflicts_with_all(&["STDOUT_ONLY", "COPY", "MESSAGE_FORMAT"])
+                .help("Run as a hook of release tools, like cargo-release or release-plz.{n}\
+                       Nothing but errors is written to stderr, and the readme goes to the file \
+                       given with `--output`, the `readme` field of `Cargo.toml` or \
+                       `README.md`, never to stdout. The exit code is 0 on success, 1 if the \
+                       readme cannot be generated and 2 if the command line is invalid. The \
+                       version is taken from `NEW_VERSION` unless `--override-version` is \
+                       given, and nothing is written when `DRY_RUN` is 'true', as set by \
+                       cargo-release."))
+            .arg(Arg::with_name("EMIT_ANCHORS")
+                .long("emit-anchors")
+                .value_name("FILE")
                 .takes_value(true)
-                .conflicts_with("NO_TEMPLATE")
-                .help("Template used to render the output.{n}\
-                       Default behavior is to use `README.tpl` if it exists."))
-            .arg(Arg::with_name("NO_TITLE")
-                .long("no-title")
-                .help("Do not prepend title line.{n}\
-                       By default, the title ('# crate-name') is prepended to the output. If a \
-                       template is used and it contains the tag '{{crate}}', the template takes \
-                       precedence and this option is ignored."))
-            .arg(Arg::with_name("NO_LICENSE")
-                .long("no-license")
-                .help("Do not append license line. By default, the license, if defined in \
-                       `Cargo.toml`, will be prepended to the output. If a template is used \
-                       and it contains the tag '{{license}}', the template takes precedence and \
-                       this option is ignored."))
-            .arg(Arg::with_name("NO_TEMPLATE")
-                .long("no-template")
-                .help("Ignore template file when generating README.{n}\
-                       Only useful to ignore default template `README.tpl`."))
-            .arg(Arg::with_name("NO_INDENT_HEADINGS")
-                .long("no-indent-headings")
-                .help("Do not add an extra level to headings.{n}\
-                       By default, '#' headings become '##', so the first '#' can be the crate \
-                       name. Use this option to prevent this behavior.{n}")))
-        .get_matches();
+                .conflicts_with("RECURSIVE")
+                .help("Write the anchors of the headings of the readme to a json file.{n}\
+                       The file maps the profile given with `--config-profile`, or 'default', \
+                       to the anchor of each heading. Anchors of other profiles already in the \
+                       file are kept, so running once per profile collects all of them."))
+            .arg(Arg::with_name("ATTEST")
+                .long("attest")
+                .value_name("FILE")
+                .takes_value(true)
+                .conflicts_with_all(&[
+                    "STDOUT_ONLY", "COPY", "RECURSIVE", "FROM_PACKAGE", "FROM_CRATES_IO", "GIT",
+                ])
+                .help("Write the version of cargo-readme, its arguments and the sha256 hashes of \
+                       the inputs and of the readme to a json file.{n}\
+                       `cargo readme verify-attestation FILE` checks that none of them changed \
+                       since."))
+            .arg(Arg::with_name("PRINT_CONFIG")
+                .long("print-config")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .min_values(0)
+                .possible_values(&["toml", "json"])
+                .conflicts_with("RECURSIVE")
+                .help("Print the settings the readme would be generated with instead of the \
+                       readme, in 'toml' or 'json'.{n}\
+                       Each setting has its value once `--config-profile` and the flags are \
+                       applied, and where it comes from: the default, \
+                       `[package.metadata.readme]`, the profile or the command line."))
+            .arg(Arg::with_name("RECURSIVE")
+                .long("recursive")
+                .conflicts_with_all(&["INPUT", "FROM_PACKAGE", "FROM_CRATES_IO", "GIT"])
+                .help("Generate the readme of every crate in the project root and its \
+                       subdirectories.{n}\
+                       Each readme is written to the file given by `--output` relative to its \
+                       crate, `README.md` by default. Directories listed in `.gitignore` or \
+                       `.readmeignore` files are skipped."))
+            .subcommand(SubCommand::with_name("check")
+                .about("Check that the readme is up to date with the doc comments")
+                .args(&generate_args())
+                .arg(Arg::with_name("FILE")
+                    .index(1)
+                    .help("Readme to check, relative to the project root.{n}\
+                           Defaults to the `readme` field of `Cargo.toml`, or `README.md`."))
+                .arg(Arg::with_name("GRANULAR")
+                    .long("granular")
+                    .help("Compare each section and code block, and report the ones that are \
+                           out of date instead of the whole file."))
+                .arg(Arg::with_name("MIN_EXAMPLES")
+                    .long("min-examples")
+                    .takes_value(true)
+                    .value_name("N")
+                    .help("Fail if the crate docs have fewer than N rust code blocks."))
+                .arg(Arg::with_name("CHECK_VERSIONS")
+                    .long("check-versions")
+                    .help("Fail if the install snippets of the readme give versions of the crate \
+                           or of its path dependencies that are not their current ones.{n}\
+                           For templates writing the versions by hand, like `my-crate = \"1.2\"` \
+                           or `cargo add my-crate@1.2`, across the crates of a workspace."))
+                .arg(Arg::with_name("REQUIRE_INTRO_WORDS")
+                    .long("require-intro-words")
+                    .takes_value(true)
+                    .value_name("N")
+                    .help("Fail if the crate docs have fewer than N words before their first \
+                           heading.")))
+            .subcommand(SubCommand::with_name("diff-upstream")
+                .about("Show how the readme differs from the one published on crates.io")
+                .args(&generate_args())
+                .arg(Arg::with_name("UPSTREAM_VERSION")
+                    .long("upstream-version")
+                    .takes_value(true)
+                    .value_name("VERSION")
+                    .help("Published version to compare with.{n}\
+                           Defaults to the latest one, leaving out pre-releases.")))
+            .subcommand(SubCommand::with_name("serve")
+                .about("Serve a live preview of the readme, rendered to HTML")
+                .args(&generate_args())
+                .arg(Arg::with_name("PORT")
+                    .long("port")
+                    .takes_value(true)
+                    .default_value("8000")
+                    .help("Port to serve the preview on, on localhost.{n}\
+                           The page reloads when a file of the crate changes. Needs cargo-readme \
+                           to be built with the `serve` feature.")))
+            .subcommand(SubCommand::with_name("verify-attestation")
+                .about("Check that the readme and its inputs match an attestation")
+                .arg(Arg::with_name("ROOT")
+                    .short("r")
+                    .long("project-root")
+                    .takes_value(true)
+                    .help("Directory to be set as project root (where `Cargo.toml` is){n}\
+                           Defaults to the current directory."))
+                .arg(Arg::with_name("FILE")
+                    .index(1)
+                    .required(true)
+                    .help("Attestation written with `--attest`, relative to the project root.")))
+            .subcommand(SubCommand::with_name("stats")
+                .about("Show the size of the crate docs")
+                .arg(Arg::with_name("ROOT")
+                    .short("r")
+                    .long("project-root")
+                    .takes_value(true)
+                    .help("Directory to be set as project root (where `Cargo.toml` is){n}\
+                           Defaults to the current directory."))
+                .arg(Arg::with_name("INPUT")
+                    .short("i")
+                    .long("input")
+                    .takes_value(true)
+                    .conflicts_with("RECURSIVE")
+                    .help("File to read the docs from, relative to the project root.{n}\
+                           Defaults to the one the readme is generated from."))
+                .arg(Arg::with_name("RECURSIVE")
+                    .long("recursive")
+                    .help("Show the stats of every crate in the project root and its \
+                           subdirectories."))
+                .arg(Arg::with_name("JSON")
+                    .long("json")
+                    .help("Write the stats as a json array, with an object for each crate.")))
+            .subcommand(SubCommand::with_name("init")
+                .about("Set up the crate to generate its readme from a template")
+                .arg(Arg::with_name("ROOT")
+                    .short("r")
+                    .long("project-root")
+                    .takes_value(true)
+                    .help("Directory to be set as project root (where `Cargo.toml` is){n}\
+                           Defaults to the current directory."))
+                .arg(Arg::with_name("STYLE")
+                    .long("style")
+                    .takes_value(true)
+                    .possible_values(builtin::NAMES)
+                    .help("Built-in template to start from.{n}\
+                           'minimal' has the title, docs and license, 'badges' adds crates.io \
+                           and docs.rs badges, 'full' adds installation instructions and \
+                           'workspace-index' lists the other crates of the workspace."))
+                .arg(Arg::with_name("CI")
+                    .long("ci")
+                    .help("Add a GitHub Actions workflow checking that the readme is up to \
+                           date."))
+                .arg(Arg::with_name("YES")
+                    .short("y")
+                    .long("yes")
+                    .help("Do not ask anything, use the 'minimal' template and no workflow unless \
+                           other options are given.")))
+            .subcommand(SubCommand::with_name("migrate")
+                .about("Move the crate to cargo-readme from another readme generator")
+                .arg(Arg::with_name("ROOT")
+                    .short("r")
+                    .long("project-root")
+                    .takes_value(true)
+                    .help("Directory to be set as project root (where `Cargo.toml` is){n}\
+                           Defaults to the current directory."))
+                .arg(Arg::with_name("FROM")
+                    .long("from")
+                    .takes_value(true)
+                    .value_name("TOOL")
+                    .required(true)
+                    .possible_values(Tool::NAMES)
+                    .help("Tool generating the readme now.{n}\
+                           The readme of cargo-rdme, with its markers replaced by \
+                           `{{readme}}`, or the template of cargo-doc2readme becomes \
+                           `README.tpl`, and its settings are added to `Cargo.toml`."))
+                .arg(Arg::with_name("TEMPLATE")
+                    .long("template")
+                    .takes_value(true)
+                    .value_name("FILE")
+                    .help("Template of cargo-doc2readme, relative to the project root.{n}\
+                           Defaults to `README.j2`."))))
+        .get_matches_safe();
+    let matches = match matches {
+        Ok(matches) => matches,
+        // release tools tell a wrong command line apart from a readme that cannot be generated
+        Err(ref e) if e.use_stderr() && env::args_os().any(|arg| arg == "--hook") => {
+            io::stderr()
+                .write_fmt(format_args!("{}\n", e.message))
+                .expect("An error occurred while trying to show a message");
+            std::process::exit(EXIT_USAGE);
+        }
+        Err(e) => e.exit(),
+    };
 
     if let Some(m) = matches.subcommand_matches("readme") {
-        match execute(m) {
-            Err(e) => {
-                io::stderr()
-                    .write_fmt(format_args!("Error: {}\n", e))
-                    .expect("An error occurred while trying to show an error message");
-                std::process::exit(1);
-            }
-            _ => {}
+        let (m, result) = match m.subcommand() {
+            ("check", Some(check)) => (check, execute_check(check)),
+            ("diff-upstream", Some(diff)) => (diff, execute_diff_upstream(diff)),
+            ("serve", Some(serve)) => (serve, execute_serve(serve)),
+            ("verify-attestation", Some(verify)) => (verify, execute_verify_attestation(verify)),
+            ("stats", Some(stats)) => (stats, execute_stats(stats)),
+            ("init", Some(init)) => (init, execute_init(init)),
+            ("migrate", Some(migrate)) => (migrate, execute_migrate(migrate)),
+            _ => (m, execute(m)),
+        };
+        if let Err(e) = result {
+            message_format(m).error(None, &e);
+            std::process::exit(EXIT_FAILURE);
         }
     }
 }
 
+/// How to show warnings and errors, quietly with `--hook`
+fn message_format(m: &ArgMatches) -> MessageFormat {
+    if m.is_present("HOOK") {
+        MessageFormat::Quiet
+    } else {
+        MessageFormat::from_name(m.value_of("MESSAGE_FORMAT"))
+    }
+}
+
+/// Arguments that control how the readme is generated, shared by `readme` and `readme check`
+fn generate_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("INPUT")
+            .short("i")
+            .long("input")
+            .takes_value(true)
+            .help("File to read from, relative to the project root or absolute.{n}\
+                   If not provided, will try to use `src/main.rs`, then `src/lib.rs`. If \
+                   neither file could be found, will look into `Cargo.toml` for a `[lib]`, \
+                   then for a single `[[bin]]`. If multiple binaries are found, you will be \
+                   asked to choose one."),
+        Arg::with_name("PREFER_LIB")
+            .long("prefer-lib")
+            .conflicts_with("PREFER_BIN")
+            .help("Read the docs of `src/lib.rs`, or the `[lib]` of `Cargo.toml`, before \
+                   `src/main.rs`, for crates with both."),
+        Arg::with_name("PREFER_BIN")
+            .long("prefer-bin")
+            .help("Read the docs of `src/main.rs` before the library, which is the default \
+                   unless the `prefer` setting is `lib`."),
+        Arg::with_name("ROOT")
+            .short("r")
+            .long("project-root")
+            .takes_value(true)
+            .help("Directory to be set as project root (where `Cargo.toml` is){n}\
+                   Defaults to the current directory."),
+        Arg::with_name("FROM_PACKAGE")
+            .long("from-package")
+            .takes_value(true)
+            .value_name("FILE")
+            .conflicts_with_all(&["ROOT", "FROM_CRATES_IO"])
+            .help("Read the crate from a `.crate` file made by `cargo package`.{n}\
+                   Useful to regenerate the readme of a published version and compare it with \
+                   the one in the package. The output is relative to the current directory."),
+        Arg::with_name("FROM_CRATES_IO")
+            .long("from-crates-io")
+            .takes_value(true)
+            .value_name("NAME@VERSION")
+            .conflicts_with("ROOT")
+            .help("Download the crate from crates.io with `curl`, then the same as \
+                   `--from-package`."),
+        Arg::with_name("GIT")
+            .long("git")
+            .takes_value(true)
+            .value_name("URL")
+            .conflicts_with_all(&["FROM_PACKAGE", "FROM_CRATES_IO"])
+            .help("Fetch the crate from a git repository, without its history.{n}\
+                   `--project-root` is then the directory of the crate in the repository. The \
+                   output is relative to the current directory."),
+        Arg::with_name("REV")
+            .long("rev")
+            .takes_value(true)
+            .requires("GIT")
+            .help("Branch, tag or commit to fetch with `--git`.{n}\
+                   Defaults to the default branch of the repository."),
+        Arg::with_name("TEMPLATE")
+            .short("t")
+            .long("template")
+            .takes_value(true)
+            .conflicts_with("NO_TEMPLATE")
+            .help("Template used to render the output.{n}\
+                   Default behavior is to use `README.tpl` if it exists. Built-in templates are \
+                   selected with `builtin:<name>`, where the name is 'minimal', 'badges', 'full' \
+                   or 'workspace-index'."),
+        Arg::with_name("CONFIG_PROFILE")
+            .long("config-profile")
+            .takes_value(true)
+            .help("Settings profile to use.{n}\
+                   The keys of `[package.metadata.readme.profiles.<profile>]` in `Cargo.toml` \
+                   replace the ones in `[package.metadata.readme]`."),
+        Arg::with_name("NO_TITLE")
+            .long("no-title")
+            .help("Do not prepend title line.{n}\
+                   By default, the title ('# crate-name') is prepended to the output. If a \
+                   template is used and it contains the tag '{{crate}}', the template takes \
+                   precedence and this option is ignored."),
+        Arg::with_name("SET_TITLE_FROM_H1")
+            .long("set-title-from-h1")
+            .conflicts_with("NO_TITLE")
+            .help("Use the `# Title` heading the docs start with as the title.{n}\
+                   The crate name is left out and the heading is not indented like the other \
+                   headings."),
+        Arg::with_name("NO_LICENSE")
+            .long("no-license")
+            .help("Do not append license line. By default, the license, if defined in \
+                   `Cargo.toml`, will be prepended to the output. If a template is used \
+                   and it contains the tag '{{license}}', the template takes precedence and \
+                   this option is ignored."),
+        Arg::with_name("NO_TEMPLATE")
+            .long("no-template")
+            .help("Ignore template file when generating README.{n}\
+                   Only useful to ignore default template `README.tpl`."),
+        Arg::with_name("NO_EXEC")
+            .long("no-exec")
+            .help("Do not run commands while rendering the template.{n}\
+                   Tags that need to run a command, like '{{cli-help}}', will fail \
+                   instead."),
+        Arg::with_name("SANDBOX")
+            .long("sandbox")
+            .help("Restrict the template, to render templates that are not trusted.{n}\
+                   No command is run, files outside of the project root cannot be read, \
+                   including through symbolic links, and the readme cannot be larger than 1 MiB."),
+        Arg::with_name("MESSAGE_FORMAT")
+            .long("message-format")
+            .takes_value(true)
+            .possible_values(&["human", "github"])
+            .help("Format of warnings and errors.{n}\
+                   Defaults to 'github' when running in GitHub Actions, so they are shown \
+                   inline on pull requests, and to 'human' otherwise."),
+        Arg::with_name("MIN_LINES")
+            .long("min-lines")
+            .takes_value(true)
+            .value_name("N")
+            .help("Fail if the docs have fewer than N non blank lines.{n}\
+                   Catches docs that were moved or deleted by mistake."),
+        Arg::with_name("ALLOW_EMPTY")
+            .long("allow-empty")
+            .help("Generate the readme even if the docs are empty.{n}\
+                   By default, finding no doc comments with text in the source file is an \
+                   error, as the readme would only have its title and license."),
+        Arg::with_name("MAX_OUTPUT_BYTES")
+            .long("max-output-bytes")
+            .takes_value(true)
+            .value_name("BYTES")
+            .help("Fail if the readme is larger than BYTES.{n}\
+                   Defaults to 16 MiB, which stops templates including files in a loop or huge \
+                   generated docs from writing enormous files."),
+        Arg::with_name("REQUIRE_SECTIONS")
+            .long("require-sections")
+            .takes_value(true)
+            .use_delimiter(true)
+            .value_name("SECTIONS")
+            .help("Fail if the docs do not have these top level sections, separated by \
+                   commas."),
+        Arg::with_name("ITEM_LINKS")
+            .long("item-links")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Link the public items of the crate mentioned in inline code to docs.rs.{n}\
+                   The items are read from FILE, either the rustdoc JSON of the crate, or a list \
+                   with the kind and path of an item on each line, like 'struct client::Client'."),
+        Arg::with_name("CRATES_IO")
+            .long("crates-io")
+            .help("Let the template show the crate as published to crates.io.{n}\
+                   `{{published-version}}`, `{{total-downloads}}` and `{{owners}}` are fetched \
+                   with `curl` and cached for an hour in `target/cargo-readme/crates-io`, \
+                   which is also used when crates.io cannot be reached."),
+        Arg::with_name("DOCS_RS")
+            .long("docs-rs")
+            .help("Warn when the docs of the latest version failed to build on docs.rs.{n}\
+                   The status is fetched with `curl`, cached for an hour in \
+                   `target/cargo-readme/docs-rs`, and shown by `{{docsrs-ok}}`."),
+        Arg::with_name("VAR")
+            .long("var")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .value_name("NAME=VALUE")
+            .help("Render the tag `{{NAME}}` of the template as VALUE.{n}\
+                   For values known outside of the crate, like download counts fetched in CI, \
+                   which filters like `{{downloads | humanize}}` can format. Built-in tags take \
+                   precedence."),
+        Arg::with_name("OVERRIDE_VERSION")
+            .long("override-version")
+            .takes_value(true)
+            .value_name("VERSION")
+            .help("Render the readme for VERSION instead of the version of `Cargo.toml`.{n}\
+                   For release tools, to generate the readme of the upcoming release before the \
+                   version of `Cargo.toml` is bumped."),
+        Arg::with_name("REPRODUCIBLE")
+            .long("reproducible")
+            .help("Fail unless the `SOURCE_DATE_EPOCH` environment variable is set, and warn \
+                   about the tags that depend on more than the sources.{n}\
+                   `{{date}}` and `{{year}}` are taken from `SOURCE_DATE_EPOCH` whenever it is \
+                   set, so the readme is the same on every run. `{{git-sha}}`, `{{git-tag}}` \
+                   and `{{contributors}}` need the git history, which source archives leave \
+                   out."),
+        Arg::with_name("WARN_UNRELEASED")
+            .long("warn-unreleased")
+            .help("Warn if the readme mentions the version of the package and it is not \
+                   released yet.{n}\
+                   The latest release is the first version heading of `CHANGELOG.md`, or \
+                   without a changelog, the latest git tag."),
+        Arg::with_name("NUMBER_HEADINGS")
+            .long("number-headings")
+            .help("Number the headings of the docs, like '1.' and '1.2.'."),
+        Arg::with_name("ABSOLUTE_LINKS")
+            .long("absolute-links")
+            .help("Make the relative links and images point to the repository.{n}\
+                   The urls follow the forge of the `repository` of `Cargo.toml`, pages for the \
+                   links and raw content for the images, so they work on crates.io too."),
+        Arg::with_name("REFERENCE_LINKS")
+            .long("reference-links")
+            .takes_value(true)
+            .value_name("PLACEMENT")
+            .possible_values(&["document", "section"])
+            .help("Turn the inline links into reference links.{n}\
+                   Their definitions go at the end of the readme with 'document', or at the end \
+                   of each section with 'section'."),
+        Arg::with_name("MAX_LINE_LENGTH")
+            .long("max-line-length")
+            .takes_value(true)
+            .value_name("N")
+            .help("Break the lines of prose longer than N characters between their sentences.{n}\
+                   Code blocks, tables, headings, inline code and links are never broken, and \
+                   sentences longer than N are left whole."),
+        Arg::with_name("SEMBR")
+            .long("sembr")
+            .conflicts_with("MAX_LINE_LENGTH")
+            .help("Put every sentence of the prose on its own line, as semantic line breaks.{n}\
+                   The readme renders the same, and its diffs show which sentences changed."),
+        Arg::with_name("SPLIT")
+            .long("split")
+            .takes_value(true)
+            .value_name("FILE")
+            .help("Keep only the first section of the docs in the readme, moving the others to \
+                   FILE.{n}\
+                   The readme ends with a 'Read more' link to FILE. The `split` setting in \
+                   `Cargo.toml` can keep more sections, or the ones with given titles."),
+        Arg::with_name("SUMMARY_SECTIONS")
+            .long("summary-sections")
+            .takes_value(true)
+            .value_name("N")
+            .help("Keep the first N sections of the docs in the readme with `--split`."),
+        Arg::with_name("EXPAND")
+            .long("expand")
+            .help("Read the docs from the macro-expanded source.{n}\
+                   Needed when the crate docs come from `include_str!` or a macro. Uses \
+                   `cargo expand` if it is installed, or `rustc -Zunpretty=expanded`, which \
+                   needs a nightly toolchain."),
+        Arg::with_name("AFTER_BUILD")
+            .long("after-build")
+            .conflicts_with("EXPAND")
+            .help("Check the crate with cargo first, to read docs generated by its build script.{n}\
+                   Lines including files from `OUT_DIR` at the top of the source, like \
+                   `#![doc = include_str!(concat!(env!(\"OUT_DIR\"), \"/docs.md\"))]`, are \
+                   replaced by the generated files."),
+        Arg::with_name("RUSTDOC_JSON")
+            .long("rustdoc-json")
+            .takes_value(true)
+            .value_name("FILE")
+            .conflicts_with_all(&["EXPAND", "AFTER_BUILD"])
+            .help("Read the docs from FILE, the rustdoc JSON of the crate.{n}\
+                   The docs are the ones rustdoc sees, with `cfg_attr` and `include_str!` \
+                   resolved, their intra-doc links point to the pages of the items, and the \
+                   public items mentioned in inline code are linked to docs.rs."),
+        Arg::with_name("FOLLOW_REEXPORT_DOCS")
+            .long("follow-reexport-docs")
+            .conflicts_with_all(&["EXPAND", "AFTER_BUILD", "RUSTDOC_JSON"])
+            .help("Read the docs from the crate re-exported with `pub use inner::*;`.{n}\
+                   For facade crates whose docs are written in an inner crate, which must be a \
+                   path dependency. The title and license are still those of this crate."),
+        Arg::with_name("COMBINE")
+            .long("combine")
+            .takes_value(true)
+            .use_delimiter(true)
+            .value_name("TARGETS")
+            .possible_values(&["lib", "bin"])
+            .conflicts_with_all(&[
+                "INPUT",
+                "EXPAND",
+                "AFTER_BUILD",
+                "RUSTDOC_JSON",
+                "FOLLOW_REEXPORT_DOCS",
+            ])
+            .help("Combine the docs of the library and the binary, like `--combine lib,bin`.{n}\
+                   The docs of each target are put under their own heading, 'As a library' and \
+                   'As a CLI' by default, which the `combine-headings` setting changes."),
+        Arg::with_name("ITEM")
+            .long("item")
+            .takes_value(true)
+            .value_name("PATH")
+            .conflicts_with_all(&["COMBINE", "EXPAND", "AFTER_BUILD", "RUSTDOC_JSON"])
+            .help("Generate the document from the doc comment of a single item, like \
+                   `--item Client::connect`.{n}\
+                   The item is looked up in the source file, through its modules, traits and \
+                   `impl` blocks, and its path is the title of the document."),
+        Arg::with_name("RAW")
+            .long("raw")
+            .conflicts_with_all(&[
+                "TEMPLATE",
+                "SET_TITLE_FROM_H1",
+                "NUMBER_HEADINGS",
+                "REFERENCE_LINKS",
+                "ABSOLUTE_LINKS",
+                "MAX_LINE_LENGTH",
+                "SEMBR",
+                "ITEM_LINKS",
+                "SPLIT",
+            ])
+            .help("Output the doc comments as they are, without any transformation.{n}\
+                   No template, title or license is added, headings are not indented and code \
+                   blocks are not rewritten, for feeding the docs to another tool."),
+        Arg::with_name("NO_INDENT_HEADINGS")
+            .long("no-indent-headings")
+            .help("Do not add an extra level to headings.{n}\
+                   By default, '#' headings become '##', so the first '#' can be the crate \
+                   name. Use this option to prevent this behavior.{n}"),
+    ]
+}
+
 /// Takes the arguments matches from clap and outputs the result, either to stdout of a file
 fn execute(m: &ArgMatches) -> Result<(), String> {
-    // get inputs
-    let input = m.value_of("INPUT");
-    let output = m.value_of("OUTPUT");
-    let template = m.value_of("TEMPLATE");
-    let add_title = !m.is_present("NO_TITLE");
-    let add_license = !m.is_present("NO_LICENSE");
-    let no_template = m.is_present("NO_TEMPLATE");
-    let indent_headings = !m.is_present("NO_INDENT_HEADINGS");
+    if m.is_present("PRINT_CONFIG") {
+        let package = get_package(m)?;
+        let project_root = match package {
+            Some(ref package) => package.root().to_path_buf(),
+            None => get_project_root(m)?,
+        };
+        return print_config::print_config(m, &project_root);
+    }
+
+    if let Some(package) = get_package(m)? {
+        // the package is in a temporary directory, so the output is relative to the current one
+        let current_dir = env::current_dir().map_err(|e| format!("{}", e))?;
+        let readme = render(m, &mut Generator::new(), package.root())?;
+        let output = m.value_of("OUTPUT");
+        check_dests(m, &current_dir, &readme, output)?;
+        emit_anchors(m, &current_dir, &readme.content)?;
+        write_split(&current_dir, &readme)?;
+        let content = format_output(m, package.root(), readme.content)?;
+        if m.value_of("PANDOC_TO").is_some() {
+            return convert_with_pandoc(m, package.root(), &current_dir, output, &content);
+        }
+        if m.is_present("COPY") {
+            return copy_to_clipboard(content);
+        }
+        let mut dest = helper::get_dest(&current_dir, output)?;
+        return helper::write_output(&mut dest, content);
+    }
 
     // get project root
+    let project_root = get_project_root(m)?;
+
+    if !m.is_present("RECURSIVE") {
+        let output = output_file(m, &project_root)?;
+        return generate(m, &mut Generator::new(), &project_root, output.as_deref());
+    }
+
+    if m.value_of("OUTPUT") == Some("-") {
+        return Err("Cannot write every readme to stdout, `--recursive` needs a file name".to_owned());
+    }
+    // the crates share the compiled regexes and, when they use the same one, the template
+    let mut generator = Generator::new();
+    for crate_root in discover::find_crates(&project_root)? {
+        let crate_dir = crate_root.strip_prefix(&project_root).unwrap_or(&crate_root);
+        let output = output_file(m, &crate_root)?.unwrap_or_else(|| "README.md".to_owned());
+        generate(m, &mut generator, &crate_root, Some(&output))
+            .map_err(|e| format!("{}: {}", crate_dir.join(&output).to_string_lossy(), e))?;
+
+        if !m.is_present("HOOK") {
+            io::stderr()
+                .write_fmt(format_args!(
+                    "Generated {}\n",
+                    crate_dir.join(output).to_string_lossy()
+                ))
+                .expect("An error occurred while trying to show a message");
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the readme of the crate is what would be generated now
+fn execute_check(m: &ArgMatches) -> Result<(), String> {
+    let package = get_package(m)?;
+    let project_root = match package {
+        Some(ref package) => package.root().to_path_buf(),
+        None => get_project_root(m)?,
+    };
+    let cargo = get_cargo_info(m, &project_root)?;
+    let file = m.value_of("FILE").or(cargo.readme_path()).unwrap_or("README.md");
+    check_coverage(m, &project_root)?;
+
+    let generated = render(m, &mut Generator::new(), &project_root)?;
+
+    // the sections moved out of the readme must be up to date too
+    if let Some(ref split) = generated.split {
+        let current = read_file(&project_root.join(&split.file))?;
+        if !cargo_readme::is_up_to_date(&split.content, &current) {
+            return Err(format!(
+                "{} is out of date, regenerate it with `cargo readme --output {}`",
+                split.file, file
+            ));
+        }
+    }
+    let generated = generated.content;
+
+    let path = project_root.join(file);
+    let readme = read_file(&path)?;
+
+    // versions written in the template go out of date without the readme changing
+    if m.is_present("CHECK_VERSIONS") {
+        let message_format = message_format(m);
+        let shown_path = message_format.source_path(&path, &project_root);
+        let mismatches = version_sync::version_mismatches(&project_root, &readme)?;
+        for mismatch in &mismatches {
+            message_format.error(Some((shown_path.as_path(), mismatch.line)), &mismatch.message);
+        }
+        if !mismatches.is_empty() {
+            return Err(format!("{} has {} outdated versions", file, mismatches.len()));
+        }
+    }
+
+    if cargo_readme::is_up_to_date(&generated, &readme) {
+        return Ok(());
+    }
+
+    if m.is_present("GRANULAR") {
+        let message_format = message_format(m);
+        let path = message_format.source_path(&path, &project_root);
+        let differences = cargo_readme::compare_sections(&generated, &readme);
+        for difference in &differences {
+            let location = difference.line.map(|line| (path.as_path(), line));
+            message_format.error(location, &difference.message);
+        }
+        if !differences.is_empty() {
+            return Err(format!("{} has {} outdated parts", file, differences.len()));
+        }
+    }
+
+    Err(format!("{} is out of date, regenerate it with `cargo readme --output {}`", file, file))
+}
+
+/// Check that the crate docs reach the thresholds given with `--min-examples` and
+/// `--require-intro-words`
+fn check_coverage(m: &ArgMatches, project_root: &Path) -> Result<(), String> {
+    let threshold = |name: &str| -> Result<Option<usize>, String> {
+        match m.value_of(name) {
+            Some(n) => n.parse().map(Some).map_err(|_| format!("Invalid number: {}", n)),
+            None => Ok(None),
+        }
+    };
+    let min_examples = threshold("MIN_EXAMPLES")?;
+    let intro_words = threshold("REQUIRE_INTRO_WORDS")?;
+    if min_examples.is_none() && intro_words.is_none() {
+        return Ok(());
+    }
+
+    let (_, source) = get_source(m, project_root)?;
+    let stats = cargo_readme::doc_stats(source)?;
+    let mut failures = Vec::new();
+    match min_examples {
+        Some(n) if stats.examples < n => failures.push(format!(
+            "{} examples where `--min-examples` requires {}",
+            stats.examples, n
+        )),
+        _ => {}
+    }
+    match intro_words {
+        Some(n) if stats.intro_words < n => failures.push(format!(
+            "{} words before the first heading where `--require-intro-words` requires {}",
+            stats.intro_words, n
+        )),
+        _ => {}
+    }
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+    Err(format!("The crate docs are too thin: {}", failures.join(", and ")))
+}
+
+/// Show the changes from the readme published on crates.io to the one generated now
+fn execute_diff_upstream(m: &ArgMatches) -> Result<(), String> {
+    let package = get_package(m)?;
+    let project_root = match package {
+        Some(ref package) => package.root().to_path_buf(),
+        None => helper::get_project_root(m.value_of("ROOT"))?,
+    };
+
+    let name = cargo_info::get_cargo_info(&project_root)?.package.name;
+    let version = match m.value_of("UPSTREAM_VERSION") {
+        Some(version) => version.to_owned(),
+        None => package::latest_version(&name)?,
+    };
+    let spec = format!("{}@{}", name, version);
+    // a crate published without a readme gets all of the generated one
+    let published = Package::download(&spec)?.readme()?.unwrap_or_default();
+
+    let generated = render(m, &mut Generator::new(), &project_root)?.content;
+    let diff = cargo_readme::unified_diff(&published, &generated, &spec, "generated");
+    if diff.is_empty() {
+        io::stderr()
+            .write_fmt(format_args!("The readme is the same as the one published in {}\n", spec))
+            .expect("An error occurred while trying to show a message");
+        return Ok(());
+    }
+
+    io::stdout()
+        .write_all(diff.as_bytes())
+        .map_err(|e| format!("Could not write the diff: {}", e))
+}
+
+/// Serve a live preview of the readme, generated again when the crate changes
+#[cfg(feature = "serve")]
+fn execute_serve(m: &ArgMatches) -> Result<(), String> {
     let project_root = helper::get_project_root(m.value_of("ROOT"))?;
+    let port = m.value_of("PORT").unwrap_or("8000");
+    let port = port.parse().map_err(|_| format!("Invalid port: {}", port))?;
+
+    let mut generator = Generator::new();
+    serve::serve(port, &project_root, || {
+        render(m, &mut generator, &project_root).map(|readme| readme.content)
+    })
+}
+
+#[cfg(not(feature = "serve"))]
+fn execute_serve(_m: &ArgMatches) -> Result<(), String> {
+    Err("`cargo readme serve` needs cargo-readme to be built with the `serve` feature".to_owned())
+}
+
+/// Check that the readme and its inputs are the ones of the attestation given
+fn execute_verify_attestation(m: &ArgMatches) -> Result<(), String> {
+    let project_root = helper::get_project_root(m.value_of("ROOT"))?;
+    let file = m.value_of("FILE").unwrap();
+    let readme = attest::verify_attestation(&project_root.join(file), &project_root)?;
+    io::stderr()
+        .write_fmt(format_args!("'{}' matches the attestation '{}'\n", readme, file))
+        .expect("An error occurred while trying to show a message");
+    Ok(())
+}
+
+/// Show the doc comment lines, sections, examples, words and reading time of the crate docs
+fn execute_stats(m: &ArgMatches) -> Result<(), String> {
+    let project_root = helper::get_project_root(m.value_of("ROOT"))?;
+    let crates = if m.is_present("RECURSIVE") {
+        discover::find_crates(&project_root)?
+    } else {
+        vec![project_root]
+    };
+
+    let mut all = Vec::new();
+    for crate_root in crates {
+        let name = cargo_info::get_cargo_info(&crate_root)?.package.name;
+        let (_, source) = get_source(m, &crate_root)?;
+        let stats = cargo_readme::doc_stats(source).map_err(|e| format!("{}: {}", name, e))?;
+        all.push((name, stats));
+    }
+
+    let output = if m.is_present("JSON") {
+        let crates: Vec<serde_json::Value> = all
+            .iter()
+            .map(|(name, stats)| {
+                let mut value = serde_json::to_value(stats).unwrap();
+                value["crate"] = serde_json::Value::from(name.as_str());
+                value["reading_minutes"] = serde_json::Value::from(stats.reading_minutes());
+                value
+            })
+            .collect();
+        serde_json::to_string_pretty(&crates).map_err(|e| format!("{}", e))? + "\n"
+    } else {
+        all.iter()
+            .map(|(name, stats)| {
+                format!(
+                    concat!(
+                        "{}\n",
+                        "  doc lines     {}\n",
+                        "  sections      {}\n",
+                        "  examples      {} ({} ignore, {} no_run)\n",
+                        "  words         {} ({} in the intro)\n",
+                        "  reading time  {} min\n",
+                    ),
+                    name,
+                    stats.lines,
+                    stats.sections,
+                    stats.examples,
+                    stats.ignored_examples,
+                    stats.no_run_examples,
+                    stats.words,
+                    stats.intro_words,
+                    stats.reading_minutes()
+                )
+            })
+            .collect()
+    };
+
+    io::stdout()
+        .write_all(output.as_bytes())
+        .map_err(|e| format!("Could not write to stdout: {}", e))
+}
+
+/// Set up the crate to generate its readme, asking what to set up unless `--yes` is given
+fn execute_init(m: &ArgMatches) -> Result<(), String> {
+    let project_root = helper::get_project_root(m.value_of("ROOT"))?;
+    let ask = !m.is_present("YES");
+
+    let template = match m.value_of("STYLE") {
+        Some(name) => name.to_owned(),
+        None if ask => {
+            let answer = prompt(&format!("Template ({}) [minimal]: ", builtin::NAMES.join(", ")))?;
+            if answer.is_empty() { "minimal".to_owned() } else { answer }
+        }
+        None => "minimal".to_owned(),
+    };
+
+    let ci = if m.is_present("CI") || !ask {
+        m.is_present("CI")
+    } else {
+        prompt("Add a GitHub Actions workflow checking the readme? [y/N]: ")?
+            .eq_ignore_ascii_case("y")
+    };
+
+    for file in init::init(&project_root, &InitOptions { template, ci })? {
+        io::stderr()
+            .write_fmt(format_args!("Wrote {}\n", file.to_string_lossy()))
+            .expect("An error occurred while trying to show a message");
+    }
+
+    Ok(())
+}
+
+/// Move the crate to cargo-readme from the tool given with `--from`
+fn execute_migrate(m: &ArgMatches) -> Result<(), String> {
+    let project_root = helper::get_project_root(m.value_of("ROOT"))?;
+    let tool = m.value_of("FROM").and_then(Tool::from_name).expect("checked by clap");
+
+    let migration = migrate::migrate(&project_root, tool, m.value_of("TEMPLATE"))?;
+    for file in migration.changed {
+        io::stderr()
+            .write_fmt(format_args!("Wrote {}\n", file.to_string_lossy()))
+            .expect("An error occurred while trying to show a message");
+    }
+    for note in migration.notes {
+        message_format(m).warning(None, &note);
+    }
+
+    Ok(())
+}
+
+/// Ask a question on stderr, keeping stdout clean, and read the answer from stdin
+fn prompt(question: &str) -> Result<String, String> {
+    let mut stderr = io::stderr();
+    stderr
+        .write_all(question.as_bytes())
+        .and_then(|_| stderr.flush())
+        .map_err(|e| format!("{}", e))?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).map_err(|e| format!("{}", e))?;
+    Ok(answer.trim().to_owned())
+}
+
+/// Crate given with `--from-package`, `--from-crates-io` or `--git`, if any
+fn get_package(m: &ArgMatches) -> Result<Option<Package>, String> {
+    if let Some(file) = m.value_of("FROM_PACKAGE") {
+        return Package::unpack(Path::new(file)).map(Some);
+    }
+    if let Some(url) = m.value_of("GIT") {
+        return Package::clone_git(url, m.value_of("REV"), m.value_of("ROOT")).map(Some);
+    }
+    match m.value_of("FROM_CRATES_IO") {
+        Some(spec) => Package::download(spec).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Project root of the crate, or directory of the cargo script given with `--input` when there
+/// is no `Cargo.toml`
+fn get_project_root(m: &ArgMatches) -> Result<PathBuf, String> {
+    helper::get_project_root(m.value_of("ROOT")).or_else(|e| match m.value_of("INPUT") {
+        Some(_) => helper::get_script_root(m.value_of("ROOT")),
+        None => Err(e),
+    })
+}
+
+/// Crate information from `Cargo.toml`, or from the manifest embedded in the cargo script given
+/// with `--input` when there is no `Cargo.toml`
+fn get_cargo_info(m: &ArgMatches, project_root: &Path) -> Result<cargo_info::Cargo, String> {
+    match script_input(m, project_root) {
+        Some(input) => script::read_manifest(&project_root.join(input)),
+        None => cargo_info::get_cargo_info(project_root),
+    }
+}
+
+/// File the docs of the crate in `project_root` are read from, the one given with `--input` or
+/// else its entrypoint
+fn get_source(m: &ArgMatches, project_root: &Path) -> Result<(PathBuf, File), String> {
+    let config = resolve_config(m, project_root)?;
+    helper::get_source(project_root, m.value_of("INPUT"), config.prefer)
+}
+
+/// The file given with `--input` when the crate is that file alone, a cargo script, because
+/// there is no `Cargo.toml`
+fn script_input<'a>(m: &'a ArgMatches, project_root: &Path) -> Option<&'a str> {
+    m.value_of("INPUT").filter(|_| !project_root.join("Cargo.toml").is_file())
+}
+
+/// File to write the readme of the crate in `project_root` to, if not stdout
+///
+/// This is the file given with `--output`, or the one set with the `readme` field of
+/// `Cargo.toml`, which is the one `cargo publish` ships. Writing elsewhere gets a warning.
+fn output_file(m: &ArgMatches, project_root: &Path) -> Result<Option<String>, String> {
+    if m.is_present("STDOUT_ONLY") || m.is_present("COPY") {
+        return Ok(None);
+    }
+    // a hook writes to the readme the crate publishes, and never to stdout
+    if m.is_present("HOOK") {
+        let cargo = get_cargo_info(m, project_root)?;
+        let output = m.value_of("OUTPUT").or(cargo.readme_path()).unwrap_or("README.md");
+        return Ok(Some(output.to_owned()));
+    }
+    // a converted document is not the readme, it never replaces the one of `Cargo.toml`
+    if m.is_present("PANDOC_TO") {
+        return Ok(m.value_of("OUTPUT").map(|output| output.to_owned()));
+    }
+
+    let cargo = get_cargo_info(m, project_root)?;
+    let readme = cargo.readme_path();
+    let output = match m.value_of("OUTPUT") {
+        Some(output) => output,
+        None => return Ok(readme.map(|readme| readme.to_owned())),
+    };
+
+    let same_file = |readme: &str| {
+        Path::new(output.trim_start_matches("./")) == Path::new(readme.trim_start_matches("./"))
+    };
+    match readme {
+        Some(readme) if output != "-" && !same_file(readme) => {
+            message_format(m).warning(
+                None,
+                &format!(
+                    "Writing the readme to '{}', but `cargo publish` ships '{}', as set with the \
+                     `readme` field of Cargo.toml",
+                    output, readme
+                ),
+            );
+        }
+        _ => {}
+    }
+
+    Ok(Some(output.to_owned()))
+}
+
+/// Generate the readme of the crate in `project_root` and write it to `output`, or stdout
+fn generate(
+    m: &ArgMatches,
+    generator: &mut Generator,
+    project_root: &Path,
+    output: Option<&str>,
+) -> Result<(), String> {
+    if m.is_present("ATTEST") && output.unwrap_or("-") == "-" {
+        return Err("`--attest` needs the readme to be written to a file, given with `--output` \
+                    or the `readme` field of Cargo.toml"
+            .to_owned());
+    }
+    let readme = render(m, generator, project_root)?;
+    check_dests(m, project_root, &readme, output)?;
+    if m.is_present("HOOK") && env::var("DRY_RUN").is_ok_and(|dry_run| dry_run == "true") {
+        return Ok(());
+    }
+    emit_anchors(m, project_root, &readme.content)?;
+    write_split(project_root, &readme)?;
+    let content = format_output(m, project_root, readme.content)?;
+    if m.value_of("PANDOC_TO").is_some() {
+        convert_with_pandoc(m, project_root, project_root, output, &content)?;
+        return attest::write_attestation(m, project_root, output);
+    }
+    if m.is_present("COPY") {
+        return copy_to_clipboard(content);
+    }
+    let mut dest = helper::get_dest(project_root, output)?;
+    helper::write_output(&mut dest, content)?;
+    attest::write_attestation(m, project_root, output)
+}
+
+/// Convert the readme of the crate in `project_root` with pandoc to the format given with
+/// `--pandoc-to`, writing it to `output`, relative to `dir`
+fn convert_with_pandoc(
+    m: &ArgMatches,
+    project_root: &Path,
+    dir: &Path,
+    output: Option<&str>,
+    readme: &str,
+) -> Result<(), String> {
+    let output = match output {
+        Some(output) if output != "-" => dir.join(output),
+        _ => return Err("`--pandoc-to` needs a file to write to, given with `--output`".to_owned()),
+    };
+    let output = env::current_dir().map(|current| current.join(&output)).unwrap_or(output);
+    let from = if m.value_of("FORMAT") == Some("html") { "html" } else { "gfm" };
+    let to = m.value_of("PANDOC_TO").unwrap_or("pdf");
+    let name = get_cargo_info(m, project_root)?.package.name;
+    pandoc::convert(project_root, readme, from, to, &name, &output)
+}
+
+/// Convert the readme of the crate in `project_root` to the format given with `--format`
+fn format_output(m: &ArgMatches, project_root: &Path, readme: String) -> Result<String, String> {
+    match m.value_of("FORMAT") {
+        Some("html") => {
+            let name = get_cargo_info(m, project_root)?.package.name;
+            html_page(&name, &readme)
+        }
+        _ => Ok(readme),
+    }
+}
+
+#[cfg(feature = "html")]
+fn html_page(title: &str, readme: &str) -> Result<String, String> {
+    Ok(html::page(title, readme))
+}
+
+#[cfg(not(feature = "html"))]
+fn html_page(_title: &str, _readme: &str) -> Result<String, String> {
+    Err("`--format html` needs cargo-readme to be built with the `html` feature".to_owned())
+}
+
+/// Put the readme on the system clipboard
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(readme: String) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| format!("Could not open the clipboard: {}", e))?;
+    clipboard
+        .set_text(readme)
+        .map_err(|e| format!("Could not copy the readme to the clipboard: {}", e))?;
+
+    io::stderr()
+        .write_all(b"Copied the readme to the clipboard\n")
+        .expect("An error occurred while trying to show a message");
+    Ok(())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_readme: String) -> Result<(), String> {
+    Err("`--copy` needs cargo-readme to be built with the `clipboard` feature".to_owned())
+}
+
+/// Check the files to write along with the readme to `output`, relative to `dir`, before any is
+/// written
+///
+/// They must be in `dir` unless `--allow-outside-root` is given. The ones in `target/` get a
+/// warning, as `cargo clean` removes them.
+fn check_dests(
+    m: &ArgMatches,
+    dir: &Path,
+    readme: &Readme,
+    output: Option<&str>,
+) -> Result<(), String> {
+    let split = readme.split.as_ref().map(|split| split.file.as_str());
+    let files = [output.filter(|&output| output != "-"), m.value_of("EMIT_ANCHORS"), split];
+    for file in files.iter().flatten() {
+        let path = helper::check_dest(dir, file, m.is_present("ALLOW_OUTSIDE_ROOT"))?;
+        if helper::is_in_target(dir, &path) {
+            message_format(m).warning(
+                None,
+                &format!("'{}' is in `target/`, which `cargo clean` removes", file),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the sections moved out of the readme with `--split`, if any, relative to `dir`
+fn write_split(dir: &Path, readme: &Readme) -> Result<(), String> {
+    match readme.split {
+        Some(ref split) => {
+            let mut dest = helper::get_dest(dir, Some(&split.file))?;
+            helper::write_output(&mut dest, split.content.clone())
+        }
+        None => Ok(()),
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    let mut content = String::new();
+    File::open(path)
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+    Ok(content)
+}
+
+/// Check that `version` is a version cargo accepts, like `1.2.3` or `2.0.0-rc.1`
+fn check_version(version: &str) -> Result<String, String> {
+    let numbers = version.split(['-', '+']).next().unwrap_or("");
+    let parts: Vec<&str> = numbers.split('.').collect();
+    let valid = parts.len() == 3
+        && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        && !version.contains(char::is_whitespace);
+    if !valid {
+        return Err(format!("Invalid version '{}', expected a version like 1.2.3", version));
+    }
+    Ok(version.to_owned())
+}
+
+/// Write the anchors of the readme to the file given with `--emit-anchors`, relative to `dir`
+fn emit_anchors(m: &ArgMatches, dir: &Path, readme: &str) -> Result<(), String> {
+    match m.value_of("EMIT_ANCHORS") {
+        Some(file) => {
+            let profile = m.value_of("CONFIG_PROFILE").unwrap_or("default");
+            helper::write_anchors(&dir.join(file), profile, &cargo_readme::heading_anchors(readme))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Values of the tags given with `--var NAME=VALUE`
+fn parse_vars(m: &ArgMatches) -> Result<BTreeMap<String, String>, String> {
+    let mut vars = BTreeMap::new();
+    for var in m.values_of("VAR").into_iter().flatten() {
+        let (name, value) = var
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid variable '{}', expected NAME=VALUE", var))?;
+        vars.insert(name.trim().to_owned(), value.to_owned());
+    }
+    Ok(vars)
+}
+
+/// Time of the dates of the readme from `SOURCE_DATE_EPOCH`, as set by reproducible builds,
+/// which `--reproducible` requires
+fn source_date_epoch(required: bool) -> Result<Option<u64>, String> {
+    match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.trim().parse().map(Some).map_err(|_| {
+            format!("Invalid SOURCE_DATE_EPOCH '{}', expected a number of seconds", epoch)
+        }),
+        Err(_) if required => {
+            Err("`--reproducible` needs the `SOURCE_DATE_EPOCH` environment variable".to_owned())
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Settings of `Cargo.toml`, in the profile given with `--config-profile`, with the command line
+/// flags taking precedence over them
+fn resolve_config(m: &ArgMatches, project_root: &Path) -> Result<Config, String> {
+    let mut config = get_cargo_info(m, project_root)?.config(m.value_of("CONFIG_PROFILE"))?;
+    if let Some(template) = m.value_of("TEMPLATE") {
+        config.template = Some(template.to_owned());
+    }
+    if m.is_present("NO_TITLE") {
+        config.title = Some(false);
+    }
+    if m.is_present("NO_LICENSE") {
+        config.license = Some(false);
+    }
+    if m.is_present("NO_INDENT_HEADINGS") {
+        config.indent_headings = Some(false);
+    }
+    if let Some(file) = m.value_of("SPLIT") {
+        match config.split {
+            Some(ref mut split) => split.file = file.to_owned(),
+            None => {
+                config.split = Some(Split {
+                    file: file.to_owned(),
+                    sections: None,
+                    summary: Vec::new(),
+                })
+            }
+        }
+    }
+    if let Some(file) = m.value_of("ITEM_LINKS") {
+        config.item_links = Some(file.to_owned());
+    }
+    if let Some(file) = m.value_of("RUSTDOC_JSON") {
+        config.rustdoc_json = Some(file.to_owned());
+    }
+    if m.is_present("PREFER_LIB") {
+        config.prefer = Entrypoint::Lib;
+    } else if m.is_present("PREFER_BIN") {
+        config.prefer = Entrypoint::Bin;
+    }
+    if m.is_present("NUMBER_HEADINGS") {
+        config.number_headings = true;
+    }
+    if m.is_present("WARN_UNRELEASED") {
+        config.warn_unreleased = true;
+    }
+    // the path of an item is the title of its document
+    let item_title = m.is_present("ITEM") && !m.is_present("NO_TITLE");
+    if m.is_present("SET_TITLE_FROM_H1") || item_title {
+        config.title_from_h1 = true;
+    }
+    if m.is_present("ABSOLUTE_LINKS") {
+        config.absolute_links = true;
+    }
+    match m.value_of("REFERENCE_LINKS") {
+        Some("document") => config.reference_links = Some(ReferenceLinks::Document),
+        Some("section") => config.reference_links = Some(ReferenceLinks::Section),
+        _ => {}
+    }
+    if m.is_present("SEMBR") {
+        config.sembr = true;
+    }
+    if let Some(n) = m.value_of("MAX_LINE_LENGTH") {
+        match n.parse() {
+            Ok(max) if max > 0 => config.max_line_length = Some(max),
+            _ => return Err(format!("Invalid line length: {}", n)),
+        }
+    }
+    if let Some(n) = m.value_of("SUMMARY_SECTIONS") {
+        let split = config
+            .split
+            .as_mut()
+            .ok_or("`--summary-sections` needs `--split` or the `split` setting")?;
+        split.sections = Some(n.parse().map_err(|_| format!("Invalid number of sections: {}", n))?);
+        split.summary.clear();
+    }
+
+    Ok(config)
+}
+
+/// Generate the readme of the crate in `project_root`, showing the warnings found on the way
+fn render(
+    m: &ArgMatches,
+    generator: &mut Generator,
+    project_root: &Path,
+) -> Result<Readme, String> {
+    let config = resolve_config(m, project_root)?;
+
+    // as a hook, the version being released is given by cargo-release
+    let version = match m.value_of("OVERRIDE_VERSION") {
+        Some(version) => Some(version.to_owned()),
+        None if m.is_present("HOOK") => env::var("NEW_VERSION").ok().filter(|v| !v.is_empty()),
+        None => None,
+    };
+
+    // get inputs
+    let input = m.value_of("INPUT");
+    let template = config.template.as_deref();
+    let no_template = m.is_present("NO_TEMPLATE");
+    let options = cargo_readme::Options {
+        add_title: config.title.unwrap_or(true),
+        add_license: config.license.unwrap_or(true),
+        indent_headings: config.indent_headings.unwrap_or(true),
+        allow_exec: !m.is_present("NO_EXEC"),
+        min_lines: match m.value_of("MIN_LINES") {
+            Some(n) => n.parse().map_err(|_| format!("Invalid number of lines: {}", n))?,
+            None => 0,
+        },
+        required_sections: m
+            .values_of("REQUIRE_SECTIONS")
+            .map(|sections| sections.map(|s| s.to_owned()).collect())
+            .unwrap_or_default(),
+        max_output_bytes: match m.value_of("MAX_OUTPUT_BYTES") {
+            Some(n) => n.parse().map_err(|_| format!("Invalid number of bytes: {}", n))?,
+            None => cargo_readme::DEFAULT_MAX_OUTPUT_BYTES,
+        },
+        config: config.clone(),
+        sandbox: m.is_present("SANDBOX"),
+        raw: m.is_present("RAW"),
+        version: match version {
+            Some(version) => Some(check_version(&version)?),
+            None => None,
+        },
+        date: source_date_epoch(m.is_present("REPRODUCIBLE"))?,
+        reproducible: m.is_present("REPRODUCIBLE"),
+        vars: parse_vars(m)?,
+        crates_io: m.is_present("CRATES_IO"),
+        docs_rs: m.is_present("DOCS_RS"),
+        cargo: match script_input(m, project_root) {
+            Some(input) => Some(script::read_manifest(&project_root.join(input))?),
+            None => None,
+        },
+    };
 
     // get source file
-    let mut source = helper::get_source(&project_root, input)?;
+    let (source_path, mut source) = helper::get_source(project_root, input, config.prefer)?;
+    let mut text = String::new();
+    source
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Could not read file '{}': {}", source_path.to_string_lossy(), e))?;
+    if options.cargo.is_some() {
+        text = script::without_manifest_block(&text);
+    }
 
-    // get destination file
-    let mut dest = helper::get_dest(&project_root, output)?;
+    // read the docs of the re-exported crate instead, for facade crates
+    let source_path = if m.is_present("FOLLOW_REEXPORT_DOCS") {
+        let source_path = reexport::reexported_source(project_root, &text)?;
+        text.clear();
+        File::open(&source_path)
+            .and_then(|mut f| f.read_to_string(&mut text))
+            .map_err(|e| {
+                format!("Could not read file '{}': {}", source_path.to_string_lossy(), e)
+            })?;
+        source_path
+    } else {
+        source_path
+    };
 
-    // get template file
-    let mut template_file = if no_template {
+    // get template, the built-in ones being written with the delimiters of the crate
+    let builtin = template.is_some_and(|template| template.starts_with(builtin::PREFIX));
+    let template = if no_template || options.raw {
         None
     } else {
-        helper::get_template_file(&project_root, template)?
+        helper::get_template(project_root, template, options.add_title, options.add_license)?
+    };
+    let template = match config.template_delimiters {
+        Some((ref open, ref close)) if builtin => {
+            template.map(|template| template.replace("{{", open).replace("}}", close))
+        }
+        _ => template,
+    };
+
+    // get the docs from elsewhere than the source file if asked to
+    let docs = if let Some(targets) = m.values_of("COMBINE") {
+        let targets: Vec<&str> = targets.collect();
+        Some(cargo_readme::combined_docs(project_root, &targets, &config.combine_headings)?)
+    } else if let Some(path) = m.value_of("ITEM") {
+        Some(item::item_docs(&text, path)?)
+    } else if m.is_present("EXPAND") {
+        Some(expand::expanded_docs(project_root, &source_path)?)
+    } else if m.is_present("AFTER_BUILD") {
+        Some(generated::resolve_out_dir_includes(project_root, &text)?)
+    } else {
+        None
     };
 
     // generate output
-    let readme = cargo_readme::generate_readme(
-        &project_root,
-        &mut source,
-        template_file.as_mut(),
-        add_title,
-        add_license,
-        indent_headings,
+    let docs_changed = docs.is_some() || config.rustdoc_json.is_some();
+    let readme = generator.generate(
+        project_root,
+        &mut Cursor::new(docs.unwrap_or(text)),
+        template.map(Cursor::new).as_mut(),
+        &options,
     )?;
 
-    helper::write_output(&mut dest, readme)
+    // show warnings pointing to the source file, unless the docs were changed before extraction
+    let message_format = message_format(m);
+    let source_path = message_format.source_path(&source_path, project_root);
+    if let Some(lines) = readme.empty_docs {
+        let scanned = match config.rustdoc_json {
+            Some(ref file) => file.clone(),
+            None => source_path.to_string_lossy().into_owned(),
+        };
+        let message = format!(
+            "No docs found in '{}': {} doc comment lines, none of them with text",
+            scanned, lines
+        );
+        if !m.is_present("ALLOW_EMPTY") {
+            return Err(format!("{}, use `--allow-empty` to generate the readme anyway", message));
+        }
+        message_format.warning(None, &message);
+    }
+    for warning in &readme.warnings {
+        let location = warning
+            .src_line
+            .filter(|_| !docs_changed)
+            .map(|line| (source_path.as_path(), line));
+        message_format.warning(location, &warning.message);
+    }
+
+    Ok(readme)
 }
@@ -0,0 +1,146 @@
+//! Show warnings and errors, either for people or for CI services to pick up
+
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use cargo_readme::workspace;
+
+/// How warnings and errors are written to stderr
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MessageFormat {
+    /// `Warning: src/lib.rs:3: message`
+    Human,
+    /// GitHub Actions workflow commands, shown inline on pull requests
+    Github,
+    /// Errors as with `Human` and no warnings, for `--hook`
+    Quiet,
+}
+
+impl MessageFormat {
+    /// Format with the given name, or detected from the environment if there is none
+    pub fn from_name(name: Option<&str>) -> Self {
+        match name {
+            Some("github") => MessageFormat::Github,
+            Some(_) => MessageFormat::Human,
+            None if env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true") => {
+                MessageFormat::Github
+            }
+            None => MessageFormat::Human,
+        }
+    }
+
+    /// Path of the source file as shown in messages
+    ///
+    /// People read paths relative to the project root, while GitHub needs them relative to the
+    /// repository, which is the current directory when running in a workflow. Sources outside of
+    /// that directory are shown with `..` components.
+    pub fn source_path(self, source: &Path, project_root: &Path) -> PathBuf {
+        let base = match self {
+            MessageFormat::Human | MessageFormat::Quiet => project_root.to_path_buf(),
+            MessageFormat::Github => env::current_dir()
+                .and_then(|dir| dir.canonicalize())
+                .unwrap_or_default(),
+        };
+        match source.strip_prefix(&base) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => PathBuf::from(workspace::relative_path(&base, source)),
+        }
+    }
+
+    /// Show a warning, pointing to a line of a file if it is known
+    pub fn warning(self, location: Option<(&Path, usize)>, message: &str) {
+        if self != MessageFormat::Quiet {
+            show(&self.format("warning", location, message));
+        }
+    }
+
+    /// Show an error, pointing to a line of a file if it is known
+    pub fn error(self, location: Option<(&Path, usize)>, message: &str) {
+        show(&self.format("error", location, message));
+    }
+
+    fn format(self, level: &str, location: Option<(&Path, usize)>, message: &str) -> String {
+        match self {
+            MessageFormat::Human | MessageFormat::Quiet => {
+                let mut level = level.to_owned();
+                level[..1].make_ascii_uppercase();
+                match location {
+                    Some((file, line)) => {
+                        format!("{}: {}:{}: {}", level, file.to_string_lossy(), line, message)
+                    }
+                    None => format!("{}: {}", level, message),
+                }
+            }
+            MessageFormat::Github => match location {
+                Some((file, line)) => format!(
+                    "::{} file={},line={}::{}",
+                    level,
+                    escape_property(&file.to_string_lossy()),
+                    line,
+                    escape_data(message)
+                ),
+                None => format!("::{}::{}", level, escape_data(message)),
+            },
+        }
+    }
+}
+
+fn show(message: &str) {
+    io::stderr()
+        .write_fmt(format_args!("{}\n", message))
+        .expect("An error occurred while trying to show a message");
+}
+
+/// Escape the message of a workflow command
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property, like the file name, of a workflow command
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::MessageFormat;
+
+    #[test]
+    fn human_messages() {
+        let format = MessageFormat::Human;
+        assert_eq!(
+            "Warning: src/lib.rs:3: message",
+            format.format("warning", Some((Path::new("src/lib.rs"), 3)), "message")
+        );
+        assert_eq!("Error: message", format.format("error", None, "message"));
+    }
+
+    #[test]
+    fn github_messages() {
+        let format = MessageFormat::Github;
+        assert_eq!(
+            "::warning file=src/lib.rs,line=3::message",
+            format.format("warning", Some((Path::new("src/lib.rs"), 3)), "message")
+        );
+        assert_eq!(
+            "::error::100%25 broken%0Aon two lines",
+            format.format("error", None, "100% broken\non two lines")
+        );
+    }
+
+    #[test]
+    fn github_escape_file_name() {
+        let format = MessageFormat::Github;
+        assert_eq!(
+            "::warning file=src/a%2Cb%3A.rs,line=1::message",
+            format.format("warning", Some((Path::new("src/a,b:.rs"), 1)), "message")
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Move a crate from another readme generator to cargo-readme with `cargo readme migrate`
+//!
+//! - cargo-rdme keeps the readme itself, with the crate docs between `<!-- cargo-rdme start -->`
+//!   and `<!-- cargo-rdme end -->`, or at a lone `<!-- cargo-rdme -->` marker, and its settings
+//!   in `.cargo-rdme.toml`. The readme becomes `README.tpl`, with `{{readme}}` in place of the
+//!   markers, and `heading-base-level = 1` becomes `indent-headings = false`.
+//! - cargo-doc2readme renders a Jinja template, `README.j2` by default. Its `{{ crate }}`,
+//!   `{{ readme }}`, `{{ license }}` and `{{ version }}` become the tags of the same name of
+//!   `README.tpl`. Other expressions and `{% ... %}` blocks are kept as they are, and reported to
+//!   be rewritten by hand.
+//!
+//! The settings are added to `Cargo.toml` in a `[package.metadata.readme]` section, unless there
+//! is one already. Files that already exist are never overwritten, and the files of the other
+//! tool are left for the user to remove.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use cargo_info;
+
+const TEMPLATE_FILE: &str = "README.tpl";
+const RDME_CONFIG: &str = ".cargo-rdme.toml";
+const RDME_MARKER: &str = "<!-- cargo-rdme -->";
+const RDME_START: &str = "<!-- cargo-rdme start -->";
+const RDME_END: &str = "<!-- cargo-rdme end -->";
+const DOC2README_TEMPLATE: &str = "README.j2";
+/// Variables of cargo-doc2readme templates that are tags of cargo-readme too
+const DOC2README_TAGS: [&str; 4] = ["crate", "readme", "license", "version"];
+
+/// Readme generators that can be migrated from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tool {
+    Rdme,
+    Doc2readme,
+}
+
+impl Tool {
+    /// Names of the tools, as given to `--from`
+    pub const NAMES: &'static [&'static str] = &["cargo-rdme", "cargo-doc2readme"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cargo-rdme" => Some(Tool::Rdme),
+            "cargo-doc2readme" => Some(Tool::Doc2readme),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Rdme => "cargo-rdme",
+            Tool::Doc2readme => "cargo-doc2readme",
+        }
+    }
+}
+
+/// What was done, and what is left to do by hand
+pub struct Migration {
+    /// Files created or changed, relative to the project root
+    pub changed: Vec<PathBuf>,
+    /// What could not be migrated
+    pub notes: Vec<String>,
+}
+
+/// Migrate the crate in `project_root` from `tool`, reading the template of cargo-doc2readme
+/// from `template` if given
+pub fn migrate(
+    project_root: &Path,
+    tool: Tool,
+    template: Option<&str>,
+) -> Result<Migration, String> {
+    let cargo = cargo_info::get_cargo_info(project_root)?;
+    let template_path = project_root.join(TEMPLATE_FILE);
+    if template_path.exists() {
+        return Err(format!("'{}' already exists", TEMPLATE_FILE));
+    }
+
+    let mut notes = Vec::new();
+    let mut settings = vec![format!("template = \"{}\"", TEMPLATE_FILE)];
+    let (source, content) = match tool {
+        Tool::Rdme => {
+            let config = read_rdme_config(project_root)?;
+            let readme = config
+                .get("readme-path")
+                .and_then(|path| path.as_str())
+                .unwrap_or_else(|| cargo.readme_file().unwrap_or("README.md"))
+                .to_owned();
+            if config.get("heading-base-level").and_then(|level| level.as_integer()) == Some(1) {
+                settings.push("indent-headings = false".to_owned());
+            }
+            for key in config.keys() {
+                match key.as_str() {
+                    "readme-path" | "heading-base-level" => {}
+                    "entrypoint" => notes.push(format!(
+                        "`entrypoint` of '{}' has no setting, give the file to read the docs \
+                         from with `--input`",
+                        RDME_CONFIG
+                    )),
+                    key => notes.push(format!(
+                        "`{}` of '{}' has no equivalent and was left out",
+                        key, RDME_CONFIG
+                    )),
+                }
+            }
+            let content = read_file(project_root, &readme)?;
+            let content = replace_rdme_markers(&content)
+                .ok_or_else(|| format!("'{}' has no `{}` marker", readme, RDME_MARKER))?;
+            (readme, content)
+        }
+        Tool::Doc2readme => {
+            let template = template.unwrap_or(DOC2README_TEMPLATE).to_owned();
+            let content = read_file(project_root, &template)?;
+            let (content, unknown) = convert_jinja(&content);
+            for (line, text) in unknown {
+                notes.push(format!(
+                    "line {} of '{}' needs to be rewritten by hand: `{}`",
+                    line, template, text
+                ));
+            }
+            (template, content)
+        }
+    };
+
+    File::create(&template_path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("Could not write '{}': {}", template_path.to_string_lossy(), e))?;
+    let mut changed = vec![PathBuf::from(TEMPLATE_FILE)];
+
+    let has_metadata = cargo
+        .package
+        .metadata
+        .as_ref()
+        .is_some_and(|metadata| metadata.readme.is_some());
+    if has_metadata {
+        notes.push(format!(
+            "`[package.metadata.readme]` already exists, make sure it has {}",
+            settings.join(" and ")
+        ));
+    } else {
+        let metadata = format!(
+            "\n[package.metadata.readme]\n# Settings of cargo-readme, migrated from {}\n{}\n",
+            tool.name(),
+            settings.join("\n")
+        );
+        let manifest = project_root.join("Cargo.toml");
+        OpenOptions::new()
+            .append(true)
+            .open(&manifest)
+            .and_then(|mut file| file.write_all(metadata.as_bytes()))
+            .map_err(|e| format!("Could not write to '{}': {}", manifest.to_string_lossy(), e))?;
+        changed.push(PathBuf::from("Cargo.toml"));
+    }
+
+    // the readme of cargo-rdme is now generated, the other files are left over
+    let leftover = match tool {
+        Tool::Rdme => Some(RDME_CONFIG).filter(|config| project_root.join(config).exists()),
+        Tool::Doc2readme => Some(source.as_str()),
+    };
+    if let Some(file) = leftover {
+        notes.push(format!("'{}' is no longer needed", file));
+    }
+
+    Ok(Migration { changed, notes })
+}
+
+/// Settings of cargo-rdme, empty if there is no `.cargo-rdme.toml`
+fn read_rdme_config(project_root: &Path) -> Result<toml::value::Table, String> {
+    if !project_root.join(RDME_CONFIG).is_file() {
+        return Ok(toml::value::Table::new());
+    }
+    let content = read_file(project_root, RDME_CONFIG)?;
+    toml::from_str(&content).map_err(|e| format!("Invalid '{}': {}", RDME_CONFIG, e))
+}
+
+fn read_file(project_root: &Path, path: &str) -> Result<String, String> {
+    let mut content = String::new();
+    File::open(project_root.join(path))
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path, e))?;
+    Ok(content)
+}
+
+/// The readme with `{{readme}}` in place of the docs cargo-rdme put between its markers, if
+/// there are any
+fn replace_rdme_markers(readme: &str) -> Option<String> {
+    if let Some(start) = readme.find(RDME_START) {
+        let end = start + readme[start..].find(RDME_END)? + RDME_END.len();
+        return Some(format!("{}{{{{readme}}}}{}", &readme[..start], &readme[end..]));
+    }
+    let start = readme.find(RDME_MARKER)?;
+    let end = start + RDME_MARKER.len();
+    Some(format!("{}{{{{readme}}}}{}", &readme[..start], &readme[end..]))
+}
+
+/// The Jinja template with the variables cargo-readme knows turned into its tags, and the line
+/// and text of the expressions and blocks it does not
+fn convert_jinja(template: &str) -> (String, Vec<(usize, String)>) {
+    let mut converted = String::new();
+    let mut unknown = Vec::new();
+    for (number, line) in template.split_inclusive('\n').enumerate() {
+        let mut rest = line;
+        while let Some(start) = rest.find("{{") {
+            let end = match rest[start..].find("}}") {
+                Some(end) => start + end + 2,
+                None => break,
+            };
+            let name = rest[start + 2..end - 2].trim();
+            converted.push_str(&rest[..start]);
+            if DOC2README_TAGS.contains(&name) {
+                converted.push_str(&format!("{{{{{}}}}}", name));
+            } else {
+                converted.push_str(&rest[start..end]);
+                unknown.push((number + 1, rest[start..end].to_owned()));
+            }
+            rest = &rest[end..];
+        }
+        converted.push_str(rest);
+
+        if line.contains("{%") || line.contains("{#") {
+            unknown.push((number + 1, line.trim().to_owned()));
+        }
+    }
+    (converted, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rdme_markers() {
+        let readme = concat_lines!(
+            "# my-crate",
+            "",
+            "<!-- cargo-rdme start -->",
+            "",
+            "Old docs",
+            "",
+            "<!-- cargo-rdme end -->",
+            "",
+            "## Contributing",
+        );
+        assert_eq!(
+            Some(concat_lines!("# my-crate", "", "{{readme}}", "", "## Contributing").to_owned()),
+            replace_rdme_markers(readme)
+        );
+        assert_eq!(
+            Some("# my-crate\n\n{{readme}}\n".to_owned()),
+            replace_rdme_markers("# my-crate\n\n<!-- cargo-rdme -->\n")
+        );
+        assert_eq!(None, replace_rdme_markers("# my-crate\n"));
+    }
+
+    #[test]
+    fn jinja_variables() {
+        let template = concat_lines!(
+            "# {{ crate }} {{ badges }}",
+            "",
+            "{{ readme }}",
+            "{%- if links != \"\" %}",
+            "{{ links }}",
+            "{%- endif -%}",
+        );
+        let (converted, unknown) = convert_jinja(template);
+        assert_eq!(
+            concat_lines!(
+                "# {{crate}} {{ badges }}",
+                "",
+                "{{readme}}",
+                "{%- if links != \"\" %}",
+                "{{ links }}",
+                "{%- endif -%}",
+            ),
+            converted
+        );
+        assert_eq!(
+            vec![
+                (1, "{{ badges }}".to_owned()),
+                (4, "{%- if links != \"\" %}".to_owned()),
+                (5, "{{ links }}".to_owned()),
+                (6, "{%- endif -%}".to_owned()),
+            ],
+            unknown
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Get a crate from elsewhere than the working tree
+//!
+//! A crate can be read as it was packaged by `cargo package` or published to a registry, which
+//! allows regenerating the readme of a published version to compare it with the readme shipped
+//! in the package, or from a git repository, to preview its readme without cloning it by hand.
+//! The readme shipped with the latest published version can be diffed with the one generated
+//! from the working tree, to see what the next publish will change.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use flate2::read::GzDecoder;
+use serde_json::{self, Value};
+use tar::Archive;
+
+use cargo_info;
+use tempdir::TempDir;
+
+const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
+const CRATES_IO_DOWNLOAD: &str = "https://static.crates.io/crates";
+const DOCS_RS: &str = "https://docs.rs/crate";
+/// crates.io rejects requests without a user agent
+const USER_AGENT: &str = concat!(
+    "cargo-readme/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/livioribeiro/cargo-readme)"
+);
+
+/// A crate unpacked or cloned to a temporary directory, which is removed when this is dropped
+pub struct Package {
+    // kept for the directory to live as long as the package
+    _dir: TempDir,
+    root: PathBuf,
+}
+
+impl Package {
+    /// Unpack a `.crate` file
+    pub fn unpack(crate_file: &Path) -> Result<Package, String> {
+        let dir = TempDir::new("cargo-readme-package")
+            .map_err(|e| format!("Could not create directory to unpack the crate: {}", e))?;
+        unpack_to(crate_file, dir.path())?;
+
+        let root = find_root(dir.path()).ok_or_else(|| {
+            format!("'{}' is not a packaged crate", crate_file.to_string_lossy())
+        })?;
+        Ok(Package { _dir: dir, root })
+    }
+
+    /// Download a crate from crates.io, given as `name@version`, and unpack it
+    ///
+    /// The crate is downloaded with `curl`.
+    pub fn download(spec: &str) -> Result<Package, String> {
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) if !name.is_empty() && !version.is_empty() => (name, version),
+            _ => return Err(format!("Expected a crate as `name@version`, found '{}'", spec)),
+        };
+
+        let dir = TempDir::new("cargo-readme-download")
+            .map_err(|e| format!("Could not create directory to download the crate: {}", e))?;
+        let crate_file = dir.path().join(format!("{}-{}.crate", name, version));
+        let url = format!("{}/{}/{}-{}.crate", CRATES_IO_DOWNLOAD, name, name, version);
+        curl(&url, Some(&crate_file))?;
+
+        Package::unpack(&crate_file)
+    }
+
+    /// Fetch the given revision of a git repository, or its default branch, without its history
+    ///
+    /// The crate is looked for in `subdir` of the repository if it is given, at its root
+    /// otherwise.
+    pub fn clone_git(url: &str, rev: Option<&str>, subdir: Option<&str>) -> Result<Package, String> {
+        // git would take it for one of its options
+        if let Some(rev) = rev.filter(|rev| rev.starts_with('-')) {
+            return Err(format!("Expected a branch, tag or commit, found '{}'", rev));
+        }
+
+        let dir = TempDir::new("cargo-readme-git")
+            .map_err(|e| format!("Could not create directory to clone the repository: {}", e))?;
+
+        git(dir.path(), &["init", "--quiet"])?;
+        git(dir.path(), &["fetch", "--quiet", "--depth", "1", "--", url, rev.unwrap_or("HEAD")])?;
+        git(dir.path(), &["checkout", "--quiet", "FETCH_HEAD"])?;
+
+        let root = dir.path().join(subdir.unwrap_or(""));
+        if !root.join("Cargo.toml").is_file() {
+            return Err(format!(
+                "No Cargo.toml found in '{}' of {}",
+                subdir.unwrap_or("."),
+                url
+            ));
+        }
+        let root = root.canonicalize().unwrap_or(root);
+        Ok(Package { _dir: dir, root })
+    }
+
+    /// Directory of the crate, where its `Cargo.toml` is
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Readme shipped with the crate, if it has one
+    pub fn readme(&self) -> Result<Option<String>, String> {
+        let cargo = cargo_info::get_cargo_info(&self.root)?;
+        // `cargo package` puts the readme at the root of the package, even when it was elsewhere
+        let file = match cargo.readme_file().and_then(|file| Path::new(file).file_name()) {
+            Some(file) => self.root.join(file),
+            None => return Ok(None),
+        };
+
+        let mut readme = String::new();
+        File::open(&file)
+            .and_then(|mut f| f.read_to_string(&mut readme))
+            .map_err(|e| format!("Could not read file '{}': {}", file.to_string_lossy(), e))?;
+        Ok(Some(readme))
+    }
+}
+
+/// Latest version of a crate published to crates.io, leaving out pre-releases unless there are
+/// only pre-releases
+pub fn latest_version(name: &str) -> Result<String, String> {
+    let json = fetch_json(&format!("{}/{}", CRATES_IO_API, name))?;
+    max_version(&json)
+        .ok_or_else(|| format!("No published version of '{}' found on crates.io", name))
+}
+
+/// A crate as published to crates.io
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Published {
+    /// Latest version, leaving out pre-releases unless there are only pre-releases
+    pub version: String,
+    /// Downloads of all the versions
+    pub downloads: u64,
+    /// Logins of the users and teams owning the crate
+    pub owners: Vec<String>,
+}
+
+/// Latest version, downloads and owners of a crate published to crates.io
+pub fn published(name: &str) -> Result<Published, String> {
+    let json = fetch_json(&format!("{}/{}", CRATES_IO_API, name))?;
+    let owners = fetch_json(&format!("{}/{}/owners", CRATES_IO_API, name))?;
+
+    Ok(Published {
+        version: max_version(&json)
+            .ok_or_else(|| format!("No published version of '{}' found on crates.io", name))?,
+        downloads: json["crate"]["downloads"].as_u64().unwrap_or(0),
+        owners: owners["users"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|user| user["login"].as_str())
+            .map(|login| login.to_owned())
+            .collect(),
+    })
+}
+
+fn max_version(json: &Value) -> Option<String> {
+    let krate = json.get("crate");
+    ["max_stable_version", "max_version"]
+        .iter()
+        .filter_map(|key| krate.and_then(|krate| krate.get(key)).and_then(|v| v.as_str()))
+        .next()
+        .map(|version| version.to_owned())
+}
+
+/// The docs.rs build of the latest version of a crate
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DocsBuild {
+    pub version: String,
+    /// Whether the docs were built, a failed build leaving the crate without docs on docs.rs
+    #[serde(rename = "doc_status")]
+    pub ok: bool,
+}
+
+/// Status of the docs.rs build of the latest version of a crate
+pub fn docs_build(name: &str) -> Result<DocsBuild, String> {
+    let url = format!("{}/{}/latest/status.json", DOCS_RS, name);
+    let json = fetch_json(&url)?;
+    serde_json::from_value(json).map_err(|e| format!("Invalid response from '{}': {}", url, e))
+}
+
+/// JSON response at `url`, of the crates.io API or docs.rs
+fn fetch_json(url: &str) -> Result<Value, String> {
+    let response = curl(url, None)?;
+    serde_json::from_slice(&response).map_err(|e| format!("Invalid response from '{}': {}", url, e))
+}
+
+/// Download `url`, to `output` if it is given, returning the response otherwise
+fn curl(url: &str, output: Option<&Path>) -> Result<Vec<u8>, String> {
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--fail", "--location", "--user-agent", USER_AGENT]);
+    if let Some(output) = output {
+        command.arg("--output").arg(output);
+    }
+
+    let output = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run curl to download '{}': {}", url, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Could not download '{}': {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn unpack_to(crate_file: &Path, dest: &Path) -> Result<(), String> {
+    let file = File::open(crate_file).map_err(|e| {
+        format!("Could not open file '{}': {}", crate_file.to_string_lossy(), e)
+    })?;
+
+    // entries that would be written outside of `dest` are skipped
+    Archive::new(GzDecoder::new(file)).unpack(dest).map_err(|e| {
+        format!("Could not unpack '{}': {}", crate_file.to_string_lossy(), e)
+    })
+}
+
+/// Packages have a single `<name>-<version>` directory with the crate in it, the first by name
+/// is taken if there are several
+fn find_root(dir: &Path) -> Option<PathBuf> {
+    let mut roots: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .collect();
+    roots.sort();
+    let root = roots.into_iter().next()?;
+    Some(root.canonicalize().unwrap_or(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Read;
+
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use tar::{Builder, Header};
+
+    use super::*;
+
+    fn append(builder: &mut Builder<GzEncoder<File>>, path: &str, content: &str) {
+        let mut header = Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn unpack_crate() {
+        let dir = TempDir::new("cargo-readme-test").unwrap();
+        let crate_file = dir.path().join("packaged-0.1.0.crate");
+
+        let encoder = GzEncoder::new(File::create(&crate_file).unwrap(), Compression::default());
+        let mut builder = Builder::new(encoder);
+        append(&mut builder, "packaged-0.1.0/Cargo.toml", "[package]\nname = \"packaged\"\n");
+        append(&mut builder, "packaged-0.1.0/src/lib.rs", "//! Packaged docs\n");
+        append(&mut builder, "packaged-0.1.0/README.md", "# packaged\n");
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let package = Package::unpack(&crate_file).unwrap();
+        let mut lib = String::new();
+        File::open(package.root().join("src/lib.rs"))
+            .and_then(|mut f| f.read_to_string(&mut lib))
+            .unwrap();
+
+        assert!(package.root().ends_with("packaged-0.1.0"));
+        assert_eq!("//! Packaged docs\n", lib);
+        assert_eq!(Ok(Some("# packaged\n".to_owned())), package.readme());
+    }
+
+    #[test]
+    fn invalid_crate_spec() {
+        let error = Package::download("packaged").err();
+        assert_eq!(Some("Expected a crate as `name@version`, found 'packaged'".to_owned()), error);
+    }
+}
@@ -0,0 +1,99 @@
+//! Convert the readme to other formats, like PDF or OpenDocument, with pandoc
+//!
+//! The readme is given to pandoc on its standard input, as GitHub flavored markdown, or as HTML
+//! when it was rendered with `--format html`. Pandoc runs in the project root, so the images
+//! the readme links to are found. The `PANDOC` environment variable names another pandoc to run.
+
+use std::env;
+use std::ffi::OsString;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Convert `readme`, written in the pandoc format `from`, to the format `to` in `output`
+///
+/// `title` is the title of the document, used by the formats that have one outside of the text.
+pub fn convert(
+    project_root: &Path,
+    readme: &str,
+    from: &str,
+    to: &str,
+    title: &str,
+    output: &Path,
+) -> Result<(), String> {
+    let pandoc = env::var_os("PANDOC").unwrap_or_else(|| OsString::from("pandoc"));
+
+    let mut child = Command::new(&pandoc)
+        .args(pandoc_args(from, to, title))
+        .arg("--output")
+        .arg(output)
+        .current_dir(project_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => {
+                "Could not find pandoc, install it from https://pandoc.org/installing.html or set \
+                 the PANDOC environment variable"
+                    .to_owned()
+            }
+            _ => format!("Could not run pandoc: {}", e),
+        })?;
+    // pandoc reads all of its input before converting it, so writing cannot block on its output
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(readme.as_bytes())
+        .map_err(|e| format!("Could not give the readme to pandoc: {}", e))?;
+
+    let result = child.wait_with_output().map_err(|e| format!("Could not run pandoc: {}", e))?;
+    if !result.status.success() {
+        return Err(format!(
+            "pandoc could not convert the readme to '{}': {}",
+            to,
+            String::from_utf8_lossy(&result.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Arguments of pandoc, except for the output file
+fn pandoc_args(from: &str, to: &str, title: &str) -> Vec<String> {
+    let mut args = vec![
+        format!("--from={}", from),
+        format!("--to={}", to),
+        "--standalone".to_owned(),
+        // the title of the page or file, without adding a title block above the readme's own
+        format!("--metadata=pagetitle:{}", title),
+    ];
+    // the default margins of LaTeX are made for printed books
+    if to == "pdf" || to == "latex" {
+        args.push("--variable=geometry:margin=2.5cm".to_owned());
+        args.push("--variable=colorlinks:true".to_owned());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_for_pdf() {
+        assert_eq!(
+            vec![
+                "--from=gfm",
+                "--to=pdf",
+                "--standalone",
+                "--metadata=pagetitle:my-crate",
+                "--variable=geometry:margin=2.5cm",
+                "--variable=colorlinks:true",
+            ],
+            pandoc_args("gfm", "pdf", "my-crate")
+        );
+        assert_eq!(4, pandoc_args("html", "odt", "my-crate").len());
+    }
+}
@@ -0,0 +1,137 @@
+//! Show the settings the readme is generated with, and where each of them comes from
+//!
+//! `--print-config` prints every setting of `[package.metadata.readme]` with the value it ends
+//! up with, once the profile given with `--config-profile` and the command line flags are
+//! applied, and its source:
+//!
+//! ```toml
+//! template = "README.oss.tpl"  # package.metadata.readme.profiles.oss
+//! max-line-length = 80  # command line
+//! number-headings = false  # default
+//! # not set: ci-badge, forge
+//! ```
+//!
+//! With `--print-config json`, it is an object with the `value` and `source` of each setting,
+//! `null` if it is not set. A flag giving the value a setting already has does not change its
+//! source.
+
+use std::path::Path;
+
+use clap::ArgMatches;
+use serde_json::{self, Map, Value};
+
+use cargo_readme::config::Config;
+
+const DEFAULT: &str = "default";
+const COMMAND_LINE: &str = "command line";
+const SETTINGS: &str = "package.metadata.readme";
+
+/// Print the settings of the crate in `project_root`, in the format given with `--print-config`
+pub fn print_config(m: &ArgMatches, project_root: &Path) -> Result<(), String> {
+    let profile = m.value_of("CONFIG_PROFILE");
+    let cargo = super::get_cargo_info(m, project_root)?;
+    let settings = cargo.package.metadata.as_ref().and_then(|m| m.readme.as_ref());
+    let profile_settings = profile.and_then(|profile| settings?.get("profiles")?.get(profile));
+
+    let manifest = to_json(&cargo.config(profile)?)?;
+    let effective = to_json(&super::resolve_config(m, project_root)?)?;
+    let mut resolved = Vec::new();
+    for (key, value) in effective {
+        let source = if manifest.get(&key) != Some(&value) {
+            COMMAND_LINE.to_owned()
+        } else if profile_settings.is_some_and(|settings| settings.get(&key).is_some()) {
+            format!("{}.profiles.{}", SETTINGS, profile.unwrap_or_default())
+        } else if settings.is_some_and(|settings| settings.get(&key).is_some()) {
+            SETTINGS.to_owned()
+        } else {
+            DEFAULT.to_owned()
+        };
+        resolved.push((key, value, source));
+    }
+
+    let output = match m.value_of("PRINT_CONFIG") {
+        Some("json") => {
+            let object: Map<String, Value> = resolved
+                .into_iter()
+                .map(|(key, value, source)| (key, json!({ "value": value, "source": source })))
+                .collect();
+            serde_json::to_string_pretty(&object).map_err(|e| format!("{}", e))?
+        }
+        _ => {
+            let mut lines = Vec::new();
+            let mut unset = Vec::new();
+            for (key, value, source) in resolved {
+                match value {
+                    Value::Null => unset.push(key),
+                    value => lines.push(format!("{} = {}  # {}", key, inline(&value), source)),
+                }
+            }
+            if !unset.is_empty() {
+                lines.push(format!("# not set: {}", unset.join(", ")));
+            }
+            lines.join("\n")
+        }
+    };
+    println!("{}", output);
+
+    Ok(())
+}
+
+fn to_json(config: &Config) -> Result<Map<String, Value>, String> {
+    match serde_json::to_value(config).map_err(|e| format!("{}", e))? {
+        Value::Object(object) => Ok(object),
+        _ => unreachable!("settings are a struct"),
+    }
+}
+
+/// The value written on a single line of TOML, with inline tables
+fn inline(value: &Value) -> String {
+    match *value {
+        Value::Array(ref values) => {
+            let values: Vec<String> = values.iter().map(inline).collect();
+            format!("[{}]", values.join(", "))
+        }
+        Value::Object(ref object) => {
+            let entries: Vec<String> = object
+                .iter()
+                .filter(|&(_, value)| !value.is_null())
+                .map(|(key, value)| format!("{} = {}", toml_key(key), inline(value)))
+                .collect();
+            if entries.is_empty() {
+                "{}".to_owned()
+            } else {
+                format!("{{ {} }}", entries.join(", "))
+            }
+        }
+        // the escapes of JSON strings are valid in TOML too
+        ref value => value.to_string(),
+    }
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if bare && !key.is_empty() {
+        key.to_owned()
+    } else {
+        Value::String(key.to_owned()).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_toml() {
+        let value = json!({
+            "file": "DOCUMENTATION.md",
+            "sections": null,
+            "summary": ["Usage", "Quick \"start\""],
+        });
+        assert_eq!(
+            "{ file = \"DOCUMENTATION.md\", summary = [\"Usage\", \"Quick \\\"start\\\"\"] }",
+            inline(&value)
+        );
+        assert_eq!("{ \"Quick start\" = \"🚀\" }", inline(&json!({ "Quick start": "🚀" })));
+    }
+}
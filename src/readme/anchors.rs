@@ -0,0 +1,86 @@
+//! Anchors of the headings of the generated readme
+//!
+//! The anchors are computed the way GitHub does: the text of the heading in lower case, without
+//! punctuation, with spaces replaced by `-`, and with `-1`, `-2`... appended to repeated ones.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use super::matchers::Matchers;
+use super::sections::headings;
+
+const REGEX_LINK: &str = r"!?\[([^\[\]]*)\]\([^)]*\)";
+
+/// Text and anchor of each heading of the readme, in order
+pub fn heading_anchors(readme: &str) -> Vec<(String, String)> {
+    let re_link = Regex::new(REGEX_LINK).unwrap();
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    headings(readme.lines(), &Matchers::new())
+        .into_iter()
+        .flatten()
+        .map(|(_, title)| {
+            let title = re_link.replace_all(&title, "$1").into_owned();
+            let slug = slug(&title);
+            let count = seen.entry(slug.clone()).or_insert(0);
+            let anchor = match *count {
+                0 => slug,
+                n => format!("{}-{}", slug, n),
+            };
+            *count += 1;
+            (title, anchor)
+        })
+        .collect()
+}
+
+fn slug(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchors_of_headings() {
+        let readme = concat_lines!(
+            "# my_crate",
+            "",
+            "## Getting started!",
+            "",
+            "```rust",
+            "# hidden",
+            "```",
+            "",
+            "## Using [`Client`](https://docs.rs/my_crate/Client)",
+            "### Examples",
+            "### Examples",
+            "## Café & co.",
+        );
+        let expected = vec![
+            ("my_crate", "my_crate"),
+            ("Getting started!", "getting-started"),
+            ("Using `Client`", "using-client"),
+            ("Examples", "examples"),
+            ("Examples", "examples-1"),
+            ("Café & co.", "café--co"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .into_iter()
+            .map(|(title, anchor)| (title.to_owned(), anchor.to_owned()))
+            .collect();
+
+        assert_eq!(expected, heading_anchors(readme));
+    }
+}
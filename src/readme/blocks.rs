@@ -0,0 +1,269 @@
+//! Follow the code blocks of markdown, in block quotes and list items too
+//!
+//! As in CommonMark, code fences can be indented by up to three spaces. Code blocks in block
+//! quotes and list items are found the same as the others, their indentation is counted from the
+//! start of the content of the list item, and a code block ends at the end of the quote or list
+//! item it is in.
+
+use std::cmp;
+
+use regex::Regex;
+
+use super::matchers::Matchers;
+
+pub const REGEX_LIST_ITEM: &str = r"^( *)([-+*]|\d{1,9}[.)])( +|$)";
+
+/// A line opening a code block
+///
+/// As in CommonMark, a fence is made of at least three backticks or tildes, indented by up to
+/// three spaces, and followed by an info string telling the language. The code block runs until
+/// a line with at least as many of the same character, and nothing else, so longer fences can
+/// contain shorter ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fence {
+    pub indent: String,
+    pub marker: char,
+    pub len: usize,
+    pub info: String,
+}
+
+impl Fence {
+    /// Parse a line opening a code block
+    pub fn open(text: &str) -> Option<Fence> {
+        let (indent, rest) = split_fence_indent(text)?;
+        let marker = rest.chars().next().filter(|&c| c == '`' || c == '~')?;
+        let len = rest.len() - rest.trim_start_matches(marker).len();
+        let info = rest[len..].trim();
+        // backticks in the info string would make it inline code
+        if len < 3 || (marker == '`' && info.contains('`')) {
+            return None;
+        }
+
+        Some(Fence {
+            indent: indent.to_owned(),
+            marker,
+            len,
+            info: info.to_owned(),
+        })
+    }
+
+    /// Whether the line closes the code block opened by this fence
+    pub fn is_closed_by(&self, text: &str) -> bool {
+        match split_fence_indent(text) {
+            Some((_, rest)) => {
+                let len = rest.len() - rest.trim_start_matches(self.marker).len();
+                len >= self.len && rest[len..].trim().is_empty()
+            }
+            None => false,
+        }
+    }
+
+    /// The fence line with the given info string instead of its own
+    pub fn with_info(&self, info: &str) -> String {
+        let marker: String = (0..self.len).map(|_| self.marker).collect();
+        format!("{}{}{}", self.indent, marker, info)
+    }
+}
+
+/// Where a line is relative to the code blocks
+#[derive(Debug, PartialEq)]
+pub enum Block {
+    /// Outside of code blocks
+    Text,
+    /// Opens a code block
+    Open(Fence),
+    /// Inside a code block
+    Code,
+    /// Closes a code block
+    Close,
+}
+
+/// Reads lines one after the other, keeping track of the code block and the containers they
+/// are in
+pub struct Blocks {
+    fence: Option<Fence>,
+    /// Number of block quotes the current code block is in
+    quote_depth: usize,
+    /// Column where the content of the list items the current line is in starts, by nesting
+    lists: Vec<usize>,
+    /// Number of block quotes the lists are in
+    lists_quote_depth: usize,
+    /// Column of the content of the list item the current code block is in
+    fence_column: usize,
+    re_list_item: Regex,
+}
+
+impl Blocks {
+    pub fn new(matchers: &Matchers) -> Self {
+        Blocks {
+            fence: None,
+            quote_depth: 0,
+            lists: Vec::new(),
+            lists_quote_depth: 0,
+            fence_column: 0,
+            re_list_item: matchers.list_item.clone(),
+        }
+    }
+
+    /// Read the next line, returning the markers of the block quotes and list items it is in,
+    /// the rest of the line and where it is relative to the code blocks
+    pub fn parse(&mut self, line: &str) -> (String, String, Block) {
+        // inside a code block, only the quote levels the block is in are markup
+        let max_depth = match self.fence {
+            Some(_) => self.quote_depth,
+            None => usize::MAX,
+        };
+        let (quote, depth, text) = split_quote(line, max_depth);
+
+        // a code block in a block quote or a list item ends with it
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        let blank = text.trim().is_empty();
+        if self.fence.is_some() && (depth < self.quote_depth || (!blank && indent < self.fence_column)) {
+            self.fence = None;
+        }
+
+        // inside a code block, only the indentation of the list item the block is in is markup
+        let (list, text) = match self.fence {
+            Some(_) => {
+                let (list, text) = text.split_at(cmp::min(indent, self.fence_column));
+                (list.to_owned(), text.to_owned())
+            }
+            None => {
+                if depth != self.lists_quote_depth {
+                    self.lists.clear();
+                    self.lists_quote_depth = depth;
+                }
+                self.split_list(&text)
+            }
+        };
+
+        let closed = self.fence.as_ref().map(|fence| fence.is_closed_by(&text));
+        let block = match closed {
+            Some(true) => {
+                self.fence = None;
+                Block::Close
+            }
+            Some(false) => Block::Code,
+            None => match Fence::open(&text) {
+                Some(fence) => {
+                    self.quote_depth = depth;
+                    self.fence_column = self.lists.last().cloned().unwrap_or(0);
+                    self.fence = Some(fence.clone());
+                    Block::Open(fence)
+                }
+                None => Block::Text,
+            },
+        };
+
+        (format!("{}{}", quote, list), text, block)
+    }
+
+    /// Whether the lines read so far left a code block open
+    pub fn in_code(&self) -> bool {
+        self.fence.is_some()
+    }
+
+    /// Split the indentation of the list items the line is in, or the marker of the list item it
+    /// starts, from the rest of the line
+    fn split_list(&mut self, text: &str) -> (String, String) {
+        if text.trim().is_empty() {
+            return (String::new(), text.to_owned());
+        }
+
+        // lines indented less than the content of a list item are after it
+        let indent = text.len() - text.trim_start_matches(' ').len();
+        while self.lists.last().is_some_and(|&column| indent < column) {
+            self.lists.pop();
+        }
+        let column = self.lists.last().cloned().unwrap_or(0);
+
+        let mut split = cmp::min(indent, column);
+        if indent - column <= 3 {
+            if let Some(caps) = self.re_list_item.captures(text) {
+                // the content is after the spaces following the marker, unless there are more
+                // than four of them, which start indented code in the item
+                let marker_end = caps.get(2).unwrap().end();
+                let spaces = caps[3].len();
+                let content = if spaces == 0 || spaces > 4 {
+                    marker_end + 1
+                } else {
+                    marker_end + spaces
+                };
+                self.lists.push(content);
+                split = cmp::min(content, text.len());
+            }
+        }
+
+        let (list, text) = text.split_at(split);
+        (list.to_owned(), text.to_owned())
+    }
+}
+
+/// Split a line that may be a code fence into its indentation and the rest, unless it is
+/// indented by more than three spaces
+fn split_fence_indent(text: &str) -> Option<(&str, &str)> {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+    if indent <= 3 {
+        Some(text.split_at(indent))
+    } else {
+        None
+    }
+}
+
+/// Split the block quote markers, up to `max_depth` of them, from the start of a line
+///
+/// Returns the markers, how many of them there are and the rest of the line.
+fn split_quote(text: &str, max_depth: usize) -> (String, usize, String) {
+    let mut rest = text;
+    let mut depth = 0;
+    while depth < max_depth {
+        match split_fence_indent(rest).and_then(|(_, marker)| marker.strip_prefix('>')) {
+            Some(after) => {
+                rest = after.strip_prefix(' ').unwrap_or(after);
+                depth += 1;
+            }
+            None => break,
+        }
+    }
+
+    let quote = &text[..text.len() - rest.len()];
+    (quote.to_owned(), depth, rest.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fences() {
+        let fence = Fence::open(" ~~~~ rust,ignore ").unwrap();
+        assert_eq!(('~', 4, "rust,ignore"), (fence.marker, fence.len, &*fence.info));
+        assert!(fence.is_closed_by("~~~~~"));
+        assert!(!fence.is_closed_by("~~~"));
+        assert!(!fence.is_closed_by("````"));
+        assert!(!fence.is_closed_by("~~~~ rust"));
+
+        assert_eq!(None, Fence::open("``"));
+        assert_eq!(None, Fence::open("``` inline `code`"));
+        assert_eq!(None, Fence::open("    ```"));
+    }
+
+    #[test]
+    fn blocks_in_containers() {
+        let mut blocks = Blocks::new(&Matchers::new());
+        let mut parse = |line: &str| {
+            let (prefix, text, block) = blocks.parse(line);
+            (prefix, text, block != Block::Text)
+        };
+
+        assert_eq!(("> ".to_owned(), " ```".to_owned(), true), parse(">  ```"));
+        assert_eq!(("> ".to_owned(), " code".to_owned(), true), parse(">  code"));
+        // the quote ends, and the code block with it
+        assert_eq!(("".to_owned(), "after".to_owned(), false), parse("after"));
+
+        assert_eq!(("1. ".to_owned(), "```".to_owned(), true), parse("1. ```"));
+        assert_eq!(("   ".to_owned(), "- code".to_owned(), true), parse("   - code"));
+        assert_eq!(("   ".to_owned(), "```".to_owned(), true), parse("   ```"));
+        assert_eq!(("".to_owned(), "text".to_owned(), false), parse("text"));
+    }
+}
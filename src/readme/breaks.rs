@@ -0,0 +1,207 @@
+//! Break the long lines of prose of the generated readme between sentences
+//!
+//! A line longer than the maximum is broken after the end of a sentence, a `.`, `!` or `?`
+//! followed by a space and a capital letter, unless the `.` ends a common abbreviation like
+//! `Mr.` or `e.g.`, keeping as many sentences on each line as fit, or
+//! only one with semantic line breaks, for diffs that show which sentences changed. The
+//! new lines are indented to stay in the block quote or list item of the line, so the readme
+//! renders the same. Nothing else is broken: sentences longer than the maximum are left whole,
+//! and code blocks, tables, headings, HTML and link definitions are not touched, nor are inline
+//! code and links.
+
+use super::blocks::{Block, Blocks};
+use super::links::code_spans;
+use super::matchers::Matchers;
+
+/// Abbreviations whose `.` does not end the sentence, even before a capital letter
+const ABBREVIATIONS: [&str; 10] =
+    ["Mr.", "Mrs.", "Ms.", "Dr.", "Prof.", "St.", "e.g.", "i.e.", "vs.", "etc."];
+
+/// Break the lines of prose longer than `max` characters between their sentences
+pub fn break_long_lines(readme: &str, max: usize, matchers: &Matchers) -> String {
+    let mut blocks = Blocks::new(matchers);
+    let mut lines = Vec::new();
+    for line in readme.split('\n') {
+        let (prefix, text, block) = blocks.parse(line);
+        if block != Block::Text || line.chars().count() <= max || !is_prose(&text, matchers) {
+            lines.push(line.to_owned());
+            continue;
+        }
+
+        // the new lines continue the quote or list item, without starting another item
+        let continuation: String =
+            prefix.chars().map(|c| if c == '>' || c.is_whitespace() { c } else { ' ' }).collect();
+        let mut current = prefix;
+        for (i, sentence) in sentences(&text).into_iter().enumerate() {
+            if i > 0 && current.chars().count() + 1 + sentence.chars().count() > max {
+                lines.push(current);
+                current = continuation.clone();
+            } else if i > 0 {
+                current.push(' ');
+            }
+            current.push_str(sentence);
+        }
+        lines.push(current);
+    }
+
+    lines.join("\n")
+}
+
+/// Put every sentence of the prose on its own line
+pub fn one_sentence_per_line(readme: &str, matchers: &Matchers) -> String {
+    // no line fits more than one sentence
+    break_long_lines(readme, 0, matchers)
+}
+
+/// Whether the text of a line is a paragraph, not a heading, table row, HTML or indented code
+fn is_prose(text: &str, matchers: &Matchers) -> bool {
+    let spans = code_spans(text);
+    let outside_code = |i: usize| spans.iter().all(|&(start, end)| i < start || end <= i);
+    !(text.starts_with("    ")
+        || text.starts_with('\t')
+        || text.trim_start().starts_with('<')
+        || matchers.heading.is_match(text)
+        || matchers.link_definition.is_match(text)
+        || text.match_indices('|').any(|(i, _)| outside_code(i)))
+}
+
+/// The sentences of a line, split where a line break cannot change how it renders
+fn sentences(text: &str) -> Vec<&str> {
+    let spans = code_spans(text);
+    let in_code = |i: usize| spans.iter().any(|&(start, end)| start <= i && i < end);
+
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    // depth of the brackets and parentheses of links, and of autolinks
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if in_code(i) => {}
+            '[' | '(' | '<' => depth += 1,
+            ']' | ')' | '>' if depth > 0 => depth -= 1,
+            ' ' if depth == 0 && is_break(text, start, i) => {
+                sentences.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    sentences.push(&text[start..]);
+
+    sentences
+}
+
+/// Whether the sentence started at `start` ends at the space at `i`, before one that can start
+/// a line
+fn is_break(text: &str, start: usize, i: usize) -> bool {
+    ends_sentence(&text[start..i]) && starts_sentence(&text[i + 1..])
+}
+
+/// Whether the text ends with the punctuation of the end of a sentence, maybe in quotes
+fn ends_sentence(text: &str) -> bool {
+    let text = text.trim_end_matches(['"', '\'', '*', '_']);
+    let last_word = text.rsplit(' ').next().unwrap_or(text);
+    text.ends_with(['.', '!', '?'])
+        && !text.ends_with("..")
+        && !ABBREVIATIONS.iter().any(|abbreviation| last_word.eq_ignore_ascii_case(abbreviation))
+}
+
+/// Whether a line can start with the text and still continue the paragraph
+///
+/// Lines starting with `-`, `#`, a number or a fence would start a list item, a heading or a
+/// code block instead, so only sentences starting with a capital letter, a link or emphasis are
+/// moved to the next line.
+fn starts_sentence(text: &str) -> bool {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), _) if c.is_uppercase() => true,
+        (Some('['), _) | (Some('"'), _) => true,
+        (Some('*'), Some(next)) | (Some('_'), Some(next)) => {
+            !next.is_whitespace() && next != '*' && next != '_'
+        }
+        (Some('`'), Some(next)) => next != '`',
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn break_between_sentences() {
+        let readme = concat_lines!(
+            "First sentence of the intro. Second sentence, with `code. Not` broken. Third one.",
+            "",
+            "- A list item that is too long. It continues with [a link. To](http://a.b) here.",
+            "> Quoted text that is too long! And more.",
+            "",
+            "```",
+            "let long = \"code that is too long. But never broken, even if it is far too long\";",
+            "```",
+            "| A table row. Too long for the limit of forty | cells |",
+            "## A heading that is too long. It stays whole",
+            "A sentence that is far too long to fit on its own line. Ok.",
+            "Then a list item? - No, a dash is kept on the line of its sentence. 2. Too.",
+        );
+        let expected = concat_lines!(
+            "First sentence of the intro.",
+            "Second sentence, with `code. Not` broken.",
+            "Third one.",
+            "",
+            "- A list item that is too long.",
+            "  It continues with [a link. To](http://a.b) here.",
+            "> Quoted text that is too long!",
+            "> And more.",
+            "",
+            "```",
+            "let long = \"code that is too long. But never broken, even if it is far too long\";",
+            "```",
+            "| A table row. Too long for the limit of forty | cells |",
+            "## A heading that is too long. It stays whole",
+            "A sentence that is far too long to fit on its own line.",
+            "Ok.",
+            "Then a list item? - No, a dash is kept on the line of its sentence. 2.",
+            "Too.",
+        );
+        assert_eq!(expected, break_long_lines(readme, 40, &Matchers::new()));
+    }
+
+    #[test]
+    fn semantic_line_breaks() {
+        let readme = concat_lines!(
+            "Short. Sentences! Each on a line? Yes.",
+            "",
+            "1. An item. Its [second. Sentence](http://a.b).",
+            "",
+            "Split after 2. Digits.",
+        );
+        let expected = concat_lines!(
+            "Short.",
+            "Sentences!",
+            "Each on a line?",
+            "Yes.",
+            "",
+            "1. An item.",
+            "   Its [second. Sentence](http://a.b).",
+            "",
+            "Split after 2.",
+            "Digits.",
+        );
+        assert_eq!(expected, one_sentence_per_line(readme, &Matchers::new()));
+    }
+
+    #[test]
+    fn abbreviations_end_no_sentence() {
+        let readme = concat_lines!(
+            "Mr. Smith said hi. Not split: e.g. lowercase, i.e. Dr. Who and E.G. This.",
+            "Apples vs. Oranges, and so on etc. The end.",
+        );
+        let expected = concat_lines!(
+            "Mr. Smith said hi.",
+            "Not split: e.g. lowercase, i.e. Dr. Who and E.G. This.",
+            "Apples vs. Oranges, and so on etc. The end.",
+        );
+        assert_eq!(expected, one_sentence_per_line(readme, &Matchers::new()));
+    }
+}
@@ -0,0 +1,370 @@
+//! Compare a readme with the one that would be generated now
+//!
+//! Besides telling whether the readme is up to date, the comparison can be done section by
+//! section and code block by code block, to point to the parts of a long readme that drifted from
+//! the docs. Every heading starts a section here, whatever its level. It can also be shown as a
+//! unified diff, like `diff -u` does.
+
+use super::sections;
+use super::blocks::Fence;
+use super::matchers::Matchers;
+
+/// A part of the readme that is not what would be generated
+#[derive(Debug, PartialEq)]
+pub struct Difference {
+    /// Line of the existing readme where the difference is, starting at 1, if it is there at all
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Whether the readme is the same as the generated one, ignoring line endings and trailing
+/// whitespace at the end of the file
+pub fn is_up_to_date(generated: &str, readme: &str) -> bool {
+    normalize(generated).trim_end() == normalize(readme).trim_end()
+}
+
+/// Compare the readme with the generated one section by section
+pub fn compare_sections(generated: &str, readme: &str) -> Vec<Difference> {
+    let generated = normalize(generated);
+    let readme = normalize(readme);
+    let expected = split(&generated);
+    let mut actual = split(&readme);
+
+    let mut differences = Vec::new();
+    for section in &expected {
+        let index = actual.iter().position(|other| other.key() == section.key());
+        let other = match index {
+            Some(index) => actual.remove(index),
+            None => {
+                differences.push(Difference {
+                    line: None,
+                    message: format!("{} is missing", section.describe()),
+                });
+                continue;
+            }
+        };
+
+        let name = other.describe();
+        let (expected_text, expected_code) = section.split_code();
+        let (actual_text, actual_code) = other.split_code();
+
+        if expected_text != actual_text {
+            differences.push(Difference {
+                line: Some(other.start),
+                message: format!("{} is out of date", name),
+            });
+        }
+
+        for (i, expected_block) in expected_code.iter().enumerate() {
+            match actual_code.get(i) {
+                Some(actual_block) if actual_block.1 == expected_block.1 => {}
+                Some(actual_block) => differences.push(Difference {
+                    line: Some(actual_block.0),
+                    message: format!("code block {} of {} is out of date", i + 1, name),
+                }),
+                None => differences.push(Difference {
+                    line: Some(other.start),
+                    message: format!("code block {} of {} is missing", i + 1, name),
+                }),
+            }
+        }
+        for (i, actual_block) in actual_code.iter().enumerate().skip(expected_code.len()) {
+            differences.push(Difference {
+                line: Some(actual_block.0),
+                message: format!("code block {} of {} is not in the docs", i + 1, name),
+            });
+        }
+    }
+
+    for section in actual {
+        differences.push(Difference {
+            line: Some(section.start),
+            message: format!("{} is not in the docs", section.describe()),
+        });
+    }
+
+    differences.sort_by_key(|difference| difference.line);
+    differences
+}
+
+/// Lines of context around the changes in a unified diff
+const DIFF_CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Unified diff from the `old` text to the `new` one, empty if they are the same
+///
+/// Line endings are ignored, as in `is_up_to_date`.
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let old = normalize(old);
+    let new = normalize(new);
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let changes = diff_lines(&old, &new);
+
+    let changed: Vec<usize> =
+        (0..changes.len()).filter(|&i| changes[i].0 != Change::Same).collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // ranges of changes, with their context, merged when their context overlaps
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(DIFF_CONTEXT);
+        let end = (i + 1 + DIFF_CONTEXT).min(changes.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in hunks {
+        let count = |skip: Change, range: &[(Change, &str)]| {
+            range.iter().filter(|&&(change, _)| change != skip).count()
+        };
+        let old_start = count(Change::Added, &changes[..start]);
+        let new_start = count(Change::Removed, &changes[..start]);
+        let old_count = count(Change::Added, &changes[start..end]);
+        let new_count = count(Change::Removed, &changes[start..end]);
+        // an empty range starts at the line before it
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + (old_count > 0) as usize,
+            old_count,
+            new_start + (new_count > 0) as usize,
+            new_count
+        ));
+        for &(change, line) in &changes[start..end] {
+            let prefix = match change {
+                Change::Same => ' ',
+                Change::Removed => '-',
+                Change::Added => '+',
+            };
+            diff.push(prefix);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+
+    diff
+}
+
+/// Changes from the `old` lines to the `new` ones, along the longest common subsequence
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Change, &'a str)> {
+    // the lines both start and end with are set aside, which leaves little to compare when the
+    // readme barely changed
+    let prefix = old.iter().zip(new).take_while(|&(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|&(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // length of the longest common subsequence of `a[i..]` and `b[j..]`
+    let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes: Vec<(Change, &str)> =
+        old[..prefix].iter().map(|&line| (Change::Same, line)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            changes.push((Change::Same, a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            changes.push((Change::Removed, a[i]));
+            i += 1;
+        } else {
+            changes.push((Change::Added, b[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old[old.len() - suffix..].iter().map(|&line| (Change::Same, line)));
+
+    changes
+}
+
+fn normalize(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
+/// A heading and the lines up to the next heading
+struct Section<'a> {
+    title: Option<String>,
+    /// How many sections before this one have the same title
+    occurrence: usize,
+    /// Line of the heading, starting at 1
+    start: usize,
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    fn key(&self) -> (Option<&str>, usize) {
+        (self.title.as_deref(), self.occurrence)
+    }
+
+    fn describe(&self) -> String {
+        match self.title {
+            Some(ref title) => format!("section '{}'", title),
+            None => "text before the first heading".to_owned(),
+        }
+    }
+
+    /// Separate the text, without trailing blank lines, from the code blocks and their line
+    fn split_code(&self) -> (Vec<&'a str>, Vec<(usize, Vec<&'a str>)>) {
+        let mut text = Vec::new();
+        let mut blocks: Vec<(usize, Vec<&str>)> = Vec::new();
+        let mut fence: Option<Fence> = None;
+        for (i, &line) in self.lines.iter().enumerate() {
+            if let Some(ref open) = fence {
+                if open.is_closed_by(line) {
+                    fence = None;
+                } else {
+                    blocks.last_mut().unwrap().1.push(line);
+                }
+            } else if let Some(open) = Fence::open(line) {
+                blocks.push((self.start + i, Vec::new()));
+                fence = Some(open);
+            } else {
+                text.push(line);
+            }
+        }
+
+        while text.last().is_some_and(|line| line.trim().is_empty()) {
+            text.pop();
+        }
+
+        (text, blocks)
+    }
+}
+
+fn split(text: &str) -> Vec<Section<'_>> {
+    let mut result: Vec<Section> = vec![Section {
+        title: None,
+        occurrence: 0,
+        start: 1,
+        lines: Vec::new(),
+    }];
+
+    let headings = sections::headings(text.lines(), &Matchers::new());
+    for (i, (line, heading)) in text.lines().zip(headings).enumerate() {
+        match heading {
+            Some((_, title)) => {
+                let occurrence = result
+                    .iter()
+                    .filter(|section| section.title.as_ref() == Some(&title))
+                    .count();
+                result.push(Section {
+                    title: Some(title),
+                    occurrence,
+                    start: i + 1,
+                    lines: vec![line],
+                });
+            }
+            None => result.last_mut().unwrap().lines.push(line),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATED: &str = concat_lines!(
+        "# my_crate",
+        "",
+        "Intro",
+        "",
+        "## Examples",
+        "",
+        "```rust",
+        "run();",
+        "```",
+        "",
+        "## Features",
+        "",
+        "Fast",
+    );
+
+    #[test]
+    fn up_to_date() {
+        assert!(is_up_to_date(GENERATED, &GENERATED.replace('\n', "\r\n")));
+        assert!(compare_sections(GENERATED, GENERATED).is_empty());
+    }
+
+    #[test]
+    fn stale_code_block_and_text() {
+        let readme = GENERATED.replace("run();", "start();").replace("Fast", "Slow");
+        let expected = vec![
+            Difference {
+                line: Some(7),
+                message: "code block 1 of section 'Examples' is out of date".to_owned(),
+            },
+            Difference {
+                line: Some(11),
+                message: "section 'Features' is out of date".to_owned(),
+            },
+        ];
+
+        assert_eq!(expected, compare_sections(GENERATED, &readme));
+    }
+
+    #[test]
+    fn missing_and_extra_sections() {
+        let readme = GENERATED.replace("## Features", "## Old features");
+        let expected = vec![
+            Difference {
+                line: None,
+                message: "section 'Features' is missing".to_owned(),
+            },
+            Difference {
+                line: Some(11),
+                message: "section 'Old features' is not in the docs".to_owned(),
+            },
+        ];
+
+        assert_eq!(expected, compare_sections(GENERATED, &readme));
+    }
+
+    #[test]
+    fn diff_with_context() {
+        let published = GENERATED.replace("run();", "start();");
+        let expected = concat_lines!(
+            "--- published",
+            "+++ generated",
+            "@@ -5,7 +5,7 @@",
+            " ## Examples",
+            " ",
+            " ```rust",
+            "-start();",
+            "+run();",
+            " ```",
+            " ",
+            " ## Features",
+        );
+
+        assert_eq!(expected, unified_diff(&published, GENERATED, "published", "generated"));
+        assert_eq!("", unified_diff(GENERATED, GENERATED, "published", "generated"));
+    }
+}
@@ -0,0 +1,119 @@
+//! Combine the docs of the library and the binary of a crate into one readme
+//!
+//! Crates with both a library and a command line tool document each in its own entrypoint. The
+//! docs of each target are put under a heading, "As a library" and "As a CLI" unless the
+//! `combine-headings` setting says otherwise, and their own headings are moved one level down to
+//! fit under it.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use super::blocks::Fence;
+use super::extract;
+use cargo_info::{self, Cargo};
+
+/// Targets that can be combined, with their default heading
+const TARGETS: &[(&str, &str)] = &[("lib", "As a library"), ("bin", "As a CLI")];
+
+/// Docs of the given targets of the crate at `project_root`, each under its heading, as `//!`
+/// comments to generate the readme from
+pub fn combined_docs(
+    project_root: &Path,
+    targets: &[&str],
+    headings: &BTreeMap<String, String>,
+) -> Result<String, String> {
+    let cargo = cargo_info::get_cargo_info(project_root)?;
+
+    let mut docs = Vec::new();
+    for &target in targets {
+        let &(_, default_heading) =
+            TARGETS.iter().find(|&&(name, _)| name == target).ok_or_else(|| {
+                format!("Cannot combine '{}', only 'lib' and 'bin' can be combined", target)
+            })?;
+        let source = entrypoint(project_root, &cargo, target)?;
+        let file = File::open(&source)
+            .map_err(|e| format!("Could not open file '{}': {}", source.to_string_lossy(), e))?;
+        let lines = extract::extract_docs(file)
+            .map_err(|e| format!("Could not read file '{}': {}", source.to_string_lossy(), e))?;
+        if lines.iter().all(|line| line.text.trim().is_empty()) {
+            return Err(format!(
+                "No docs found in '{}' to combine",
+                source.strip_prefix(project_root).unwrap_or(&source).to_string_lossy()
+            ));
+        }
+
+        if !docs.is_empty() {
+            docs.push(String::new());
+        }
+        let heading = headings.get(target).map(|h| h.as_str()).unwrap_or(default_heading);
+        docs.push(format!("# {}", heading));
+        docs.push(String::new());
+        docs.extend(lower_headings(lines.into_iter().map(|line| line.text)));
+    }
+
+    Ok(docs
+        .iter()
+        .map(|line| if line.is_empty() { "//!".to_owned() } else { format!("//! {}", line) })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Entrypoint of the library, or of the binary named like the crate or the only one
+fn entrypoint(project_root: &Path, cargo: &Cargo, target: &str) -> Result<PathBuf, String> {
+    let path = if target == "lib" {
+        let path = cargo.lib.as_ref().and_then(|lib| lib.path.as_deref()).unwrap_or("src/lib.rs");
+        Some(path.to_owned())
+    } else if project_root.join("src/main.rs").is_file() {
+        Some("src/main.rs".to_owned())
+    } else {
+        let bins = cargo.bin.as_deref().unwrap_or(&[]);
+        let bin = bins
+            .iter()
+            .find(|bin| bin.name.as_ref() == Some(&cargo.package.name))
+            .or(if bins.len() == 1 { bins.first() } else { None });
+        bin.and_then(|bin| match (bin.path.as_ref(), bin.name.as_ref()) {
+            (Some(path), _) => Some(path.clone()),
+            (None, Some(name)) => Some(format!("src/bin/{}.rs", name)),
+            (None, None) => None,
+        })
+    };
+
+    match path.map(|path| project_root.join(path)) {
+        Some(ref path) if path.is_file() => Ok(path.clone()),
+        _ => Err(format!("No {} target found in the crate to combine", target)),
+    }
+}
+
+/// Move the headings down one level, outside of code blocks
+fn lower_headings<I: Iterator<Item = String>>(lines: I) -> Vec<String> {
+    let mut fence: Option<Fence> = None;
+    lines
+        .map(|line| {
+            if let Some(ref open) = fence {
+                if open.is_closed_by(&line) {
+                    fence = None;
+                }
+                return line;
+            }
+            fence = Fence::open(&line);
+            if fence.is_none() && line.starts_with('#') {
+                format!("#{}", line)
+            } else {
+                line
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lower_headings_outside_code() {
+        let lines = vec!["# Usage", "", "```sh", "# a comment", "```", "## Options"];
+        let expected = vec!["## Usage", "", "```sh", "# a comment", "```", "### Options"];
+        assert_eq!(expected, lower_headings(lines.into_iter().map(|l| l.to_owned())));
+    }
+}
@@ -2,83 +2,191 @@
 
 use std::io::{self, Read, BufRead, BufReader};
 
+/// A line of documentation and the line of the source file it was read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub text: String,
+    /// Line number in the source file, starting at 1
+    pub src_line: usize,
+}
+
+impl Line {
+    pub fn new<S: Into<String>>(text: S, src_line: usize) -> Self {
+        Line {
+            text: text.into(),
+            src_line,
+        }
+    }
+}
+
 /// Read the given `Read`er and return a `Vec` of the rustdoc lines found
-pub fn extract_docs<R: Read>(reader: R) -> io::Result<Vec<String>> {
+///
+/// Lines may end with `\n` or `\r\n`, the returned lines never contain the line terminator.
+pub fn extract_docs<R: Read>(reader: R) -> io::Result<Vec<Line>> {
     let mut reader = BufReader::new(reader);
 
     let mut line = String::new();
+    let mut src_line = 0;
+    // the dashes closing the manifest of a cargo script, while in it
+    let mut frontmatter: Option<String> = None;
+    // whether there was nothing but a shebang and blank lines so far
+    let mut start = true;
 
     while reader.read_line(&mut line)? > 0 {
-        if line.starts_with("//!") {
-            return extract_docs_singleline_style(line, reader);
-        }
-        if line.starts_with("/*!") {
-            return extract_docs_multiline_style(line, reader);
+        src_line += 1;
+        strip_line_terminator(&mut line);
+
+        if let Some(ref dashes) = frontmatter {
+            if line.trim_end() == dashes {
+                frontmatter = None;
+            }
+        } else if src_line == 1 && is_shebang(&line) {
+            line.clear();
+            continue;
+        } else if start && line.starts_with("---") {
+            frontmatter = Some(line.chars().take_while(|&c| c == '-').collect());
+        } else if line.starts_with("//!") {
+            return extract_docs_singleline_style(Line::new(line, src_line), reader);
+        } else if line.starts_with("/*!") {
+            return extract_docs_multiline_style(Line::new(line, src_line), reader);
         }
 
+        start = start && line.trim().is_empty();
         line.clear();
     }
 
     Ok(Vec::new())
 }
 
-fn extract_docs_singleline_style<R: Read>(first_line: String, reader: BufReader<R>) -> io::Result<Vec<String>> {
+/// Whether the first line of a file is a shebang, like `#!/usr/bin/env -S cargo +nightly -Zscript`
+///
+/// Like in rustc, `#!` followed by `[` starts an inner attribute, like `#![no_std]`, instead.
+fn is_shebang(line: &str) -> bool {
+    line.strip_prefix("#!").is_some_and(|rest| !rest.trim_start().starts_with('['))
+}
+
+fn extract_docs_singleline_style<R: Read>(first_line: Line, reader: BufReader<R>) -> io::Result<Vec<Line>> {
+    let mut src_line = first_line.src_line;
     let mut result = vec![normalize_line(first_line)];
 
+    let mut preamble = Preamble::default();
     for line in reader.lines() {
         let line = line?;
+        src_line += 1;
 
-        if line.starts_with("//!") {
-            result.push(normalize_line(line));
-        } else if line.trim().len() > 0 {
+        if preamble.skip(&line) {
+            continue;
+        }
+        if !line.starts_with("//!") {
             // doc ends, code starts
             break;
         }
+        result.push(normalize_line(Line::new(line, src_line)));
     }
 
     Ok(result)
 }
 
-fn extract_docs_multiline_style<R: Read>(first_line: String, reader: BufReader<R>) -> io::Result<Vec<String>> {
+/// The lines that can come between the crate doc comments, before the code of the crate
+///
+/// Rustdoc joins all the `//!` comments of the crate, and crates often have inner attributes
+/// like `#![no_std]` or `#![cfg_attr(...)]`, license headers and other comments in between.
+#[derive(Default)]
+struct Preamble {
+    /// Brackets left open by an inner attribute spanning several lines
+    brackets: usize,
+    /// Block comments left open, which can be nested
+    comments: usize,
+}
+
+impl Preamble {
+    /// Whether the line is blank, a comment or part of an inner attribute, rather than doc
+    /// comments or code
+    fn skip(&mut self, line: &str) -> bool {
+        let line = line.trim();
+        if self.comments > 0 || (line.starts_with("/*") && !line.starts_with("/*!")) {
+            let opened = self.comments + line.matches("/*").count();
+            self.comments = opened.saturating_sub(line.matches("*/").count());
+            return true;
+        }
+        if self.brackets > 0 || line.starts_with("#![") {
+            self.brackets = brackets(self.brackets, line);
+            return true;
+        }
+        line.is_empty() || (line.starts_with("//") && !line.starts_with("//!"))
+    }
+}
+
+/// Brackets left open at the end of the line, with `open` ones before it, outside of strings
+fn brackets(mut open: usize, line: &str) -> usize {
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '[' if !in_string => open += 1,
+            ']' if !in_string => open = open.saturating_sub(1),
+            _ => {}
+        }
+    }
+    open
+}
+
+fn extract_docs_multiline_style<R: Read>(first_line: Line, reader: BufReader<R>) -> io::Result<Vec<Line>> {
+    let mut src_line = first_line.src_line;
     let mut result = Vec::new();
-    if first_line.starts_with("/*!") && first_line.trim().len() > "/*!".len() {
+    if first_line.text.starts_with("/*!") && first_line.text.trim().len() > "/*!".len() {
         result.push(normalize_line(first_line));
     }
 
     let mut nesting: isize = 0;
 
     for line in reader.lines() {
-        let line = line?;
+        let mut line = line?;
+        src_line += 1;
         nesting += line.matches("/*").count() as isize;
 
         if let Some(pos) = line.rfind("*/") {
             nesting -= line.matches("*/").count() as isize;
             if nesting < 0 {
-                let mut line = line;
-                line.split_off(pos);
+                line.truncate(pos);
                 if !line.trim().is_empty() {
-                    result.push(line);
+                    result.push(Line::new(line, src_line));
                 }
                 break
             }
         }
 
-        result.push(line.trim_right().to_owned());
+        result.push(Line::new(line.trim_end(), src_line));
     }
 
     Ok(result)
 }
 
+/// Remove the `\n` or `\r\n` at the end of a line read with `read_line`
+///
+/// `lines()` already does it for the lines after the first one.
+fn strip_line_terminator(line: &mut String) {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+}
+
 /// Strip the "//!" or "/*!" from a line and a single whitespace
-fn normalize_line(mut line: String) -> String {
-    if line.trim() == "//!" || line.trim() == "/*!" {
-        line.clear();
-        line
+fn normalize_line(mut line: Line) -> Line {
+    if line.text.trim() == "//!" || line.text.trim() == "/*!" {
+        line.text.clear();
     } else {
         // if the first character after the comment mark is " ", remove it
-        let split_at = if line.find(" ") == Some(3) { 4 } else { 3 };
-        line.split_at(split_at).1.trim_right().to_owned()
+        let split_at = if line.text.find(' ') == Some(3) { 4 } else { 3 };
+        line.text = line.text.split_at(split_at).1.trim_end().to_owned();
     }
+    line
 }
 
 #[cfg(test)]
@@ -86,6 +194,10 @@ mod tests {
     use std::io::Cursor;
     use super::*;
 
+    fn texts(lines: &[Line]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
     const EXPECTED: &[&str] = &[
         "first line",
         "",
@@ -116,7 +228,7 @@ mod tests {
     fn extract_docs_singleline_style() {
         let reader = Cursor::new(INPUT_SINGLELINE.as_bytes());
         let result = extract_docs(reader).unwrap();
-        assert_eq!(result, EXPECTED);
+        assert_eq!(texts(&result), EXPECTED);
     }
 
     const INPUT_MULTILINE: &str = concat_lines!(
@@ -139,7 +251,21 @@ mod tests {
     fn extract_docs_multiline_style() {
         let reader = Cursor::new(INPUT_MULTILINE.as_bytes());
         let result = extract_docs(reader).unwrap();
-        assert_eq!(result, EXPECTED);
+        assert_eq!(texts(&result), EXPECTED);
+    }
+
+    #[test]
+    fn extract_docs_singleline_style_crlf() {
+        let input = INPUT_SINGLELINE.replace('\n', "\r\n");
+        let result = extract_docs(Cursor::new(input.as_bytes())).unwrap();
+        assert_eq!(texts(&result), EXPECTED);
+    }
+
+    #[test]
+    fn extract_docs_multiline_style_crlf() {
+        let input = INPUT_MULTILINE.replace('\n', "\r\n");
+        let result = extract_docs(Cursor::new(input.as_bytes())).unwrap();
+        assert_eq!(texts(&result), EXPECTED);
     }
 
     const INPUT_MIXED_SINGLELINE: &str = concat_lines!(
@@ -154,7 +280,7 @@ mod tests {
         let input = Cursor::new(INPUT_MIXED_SINGLELINE.as_bytes());
         let expected = ["singleline"];
         let result = extract_docs(input).unwrap();
-        assert_eq!(result, expected)
+        assert_eq!(texts(&result), expected)
     }
 
     const INPUT_MIXED_MULTILINE: &str = concat_lines!(
@@ -169,7 +295,7 @@ mod tests {
         let input = Cursor::new(INPUT_MIXED_MULTILINE.as_bytes());
         let expected = ["multiline"];
         let result = extract_docs(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(texts(&result), expected);
     }
 
     const INPUT_MULTILINE_NESTED_1: &str = concat_lines!(
@@ -195,7 +321,7 @@ mod tests {
     fn extract_docs_nested_level_1() {
         let input = Cursor::new(INPUT_MULTILINE_NESTED_1.as_bytes());
         let result = extract_docs(input).unwrap();
-        assert_eq!(result, EXPECTED_MULTILINE_NESTED_1);
+        assert_eq!(texts(&result), EXPECTED_MULTILINE_NESTED_1);
     }
 
     const INPUT_MULTILINE_NESTED_2: &str = concat_lines!(
@@ -229,6 +355,89 @@ mod tests {
     fn extract_docs_nested_level_2() {
         let input = Cursor::new(INPUT_MULTILINE_NESTED_2.as_bytes());
         let result = extract_docs(input).unwrap();
-        assert_eq!(result, EXPECTED_MULTILINE_NESTED_2);
+        assert_eq!(texts(&result), EXPECTED_MULTILINE_NESTED_2);
+    }
+
+    const INPUT_WITH_PREAMBLE: &str = concat_lines!(
+        "// regular comment",
+        "",
+        "//! first line",
+        "//!",
+        "//! second paragraph",
+        "",
+        "fn main() {}",
+    );
+
+    const INPUT_NO_STD_PREAMBLE: &str = concat_lines!(
+        "// Copyright the authors",
+        "#![no_std]",
+        "//! first line",
+        "#![cfg_attr(",
+        "    docsrs,",
+        "    doc(html_logo_url = \"https://example.com/[logo].svg\")",
+        ")]",
+        "//!",
+        "/* license",
+        "   header */",
+        "//! second paragraph",
+        "#![forbid(unsafe_code)]",
+        "",
+        "#[cfg(feature = \"alloc\")]",
+        "extern crate alloc;",
+        "//! not crate docs",
+    );
+
+    #[test]
+    fn extract_docs_between_attributes_and_comments() {
+        let input = Cursor::new(INPUT_NO_STD_PREAMBLE.as_bytes());
+        let result = extract_docs(input).unwrap();
+        assert_eq!(texts(&result), ["first line", "", "second paragraph"]);
+        let lines: Vec<_> = result.iter().map(|line| line.src_line).collect();
+        assert_eq!(lines, [3, 8, 11]);
+    }
+
+    const INPUT_CARGO_SCRIPT: &str = concat_lines!(
+        "#!/usr/bin/env -S cargo +nightly -Zscript",
+        "---cargo",
+        "[package]",
+        "description = \"\"\"",
+        "//! not crate docs",
+        "\"\"\"",
+        "---",
+        "",
+        "//! first line",
+        "#![allow(dead_code)]",
+        "//! second line",
+        "",
+        "fn main() {}",
+    );
+
+    #[test]
+    fn extract_docs_after_shebang_and_manifest() {
+        let input = Cursor::new(INPUT_CARGO_SCRIPT.as_bytes());
+        let result = extract_docs(input).unwrap();
+        assert_eq!(texts(&result), ["first line", "second line"]);
+        let lines: Vec<_> = result.iter().map(|line| line.src_line).collect();
+        assert_eq!(lines, [9, 11]);
+
+        assert!(is_shebang("#!/usr/bin/env run-cargo-script"));
+        assert!(!is_shebang("#![no_std]"));
+        assert!(!is_shebang("#! [no_std]"));
+    }
+
+    #[test]
+    fn extract_docs_keep_source_line_numbers() {
+        let input = Cursor::new(INPUT_WITH_PREAMBLE.as_bytes());
+        let result = extract_docs(input).unwrap();
+        let lines: Vec<_> = result.iter().map(|line| line.src_line).collect();
+        assert_eq!(lines, [3, 4, 5]);
+    }
+
+    #[test]
+    fn extract_docs_multiline_keep_source_line_numbers() {
+        let input = Cursor::new(INPUT_MULTILINE.as_bytes());
+        let result = extract_docs(input).unwrap();
+        let lines: Vec<_> = result.iter().map(|line| line.src_line).collect();
+        assert_eq!(lines, (2..11).collect::<Vec<_>>());
     }
 }
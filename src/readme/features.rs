@@ -0,0 +1,121 @@
+//! Add notes under examples that need features of the crate that are not enabled by default
+//!
+//! The features are read from the `cfg` attributes of the example, usually on hidden lines:
+//!
+//! ```rust,ignore
+//! # #[cfg(feature = "serde")] {
+//! let json = serde_json::to_string(&value)?;
+//! # }
+//! ```
+//!
+//! Only `feature = "..."` conditions, alone or in `all(...)`, are taken into account.
+
+use super::extract::Line;
+use super::blocks::Fence;
+use super::matchers::Matchers;
+
+pub const REGEX_CFG: &str = r"cfg\s*\((.*)\)";
+pub const REGEX_FEATURE: &str = r#"feature\s*=\s*"([^"]+)""#;
+
+/// Add a line telling which features are needed after every rust code block that needs some
+/// that are not in `default_features`
+pub fn add_feature_notes(
+    lines: Vec<Line>,
+    default_features: &[String],
+    matchers: &Matchers,
+) -> Vec<Line> {
+
+    let mut result = Vec::new();
+    let mut fence: Option<Fence> = None;
+    let mut in_rust = false;
+    let mut features: Vec<String> = Vec::new();
+
+    for line in lines {
+        let src_line = line.src_line;
+        if fence.is_none() {
+            fence = Fence::open(&line.text);
+            in_rust = fence.as_ref().is_some_and(|fence| matchers.code_rust.is_match(&fence.info));
+            features.clear();
+        } else if fence.as_ref().is_some_and(|fence| fence.is_closed_by(&line.text)) {
+            fence = None;
+            result.push(line);
+            if in_rust && !features.is_empty() {
+                result.push(Line::new("", src_line));
+                result.push(Line::new(note(&features), src_line));
+            }
+            in_rust = false;
+            continue;
+        } else if in_rust {
+            for caps in matchers.cfg.captures_iter(&line.text) {
+                let condition = &caps[1];
+                if condition.contains("any(") || condition.contains("not(") {
+                    continue;
+                }
+                for feature in matchers.feature.captures_iter(condition) {
+                    let feature = feature[1].to_owned();
+                    if !default_features.contains(&feature) && !features.contains(&feature) {
+                        features.push(feature);
+                    }
+                }
+            }
+        }
+        result.push(line);
+    }
+
+    result
+}
+
+fn note(features: &[String]) -> String {
+    let names: Vec<String> = features.iter().map(|name| format!("`{}`", name)).collect();
+    match names.split_last() {
+        Some((last, [])) => format!("Requires the {} feature.", last),
+        Some((last, rest)) => format!("Requires the {} and {} features.", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(input: &str) -> Vec<Line> {
+        input.lines().enumerate().map(|(i, x)| Line::new(x, i + 1)).collect()
+    }
+
+    fn texts(lines: Vec<Line>) -> Vec<String> {
+        lines.into_iter().map(|line| line.text).collect()
+    }
+
+    const INPUT: &str = concat_lines!(
+        "```",
+        "# #[cfg(all(feature = \"serde\", feature = \"std\"))] {",
+        "to_json();",
+        "# }",
+        "```",
+        "",
+        "```rust",
+        "#[cfg(any(feature = \"json\", feature = \"yaml\"))]",
+        "fn either() {}",
+        "```",
+        "",
+        "```toml",
+        "cfg(feature = \"not-rust\")",
+        "```",
+    );
+
+    #[test]
+    fn note_after_examples_needing_features() {
+        let mut expected: Vec<&str> = INPUT.lines().collect();
+        expected.insert(5, "");
+        expected.insert(6, "Requires the `serde` feature.");
+
+        let std = vec!["std".to_owned()];
+        assert_eq!(expected, texts(add_feature_notes(lines(INPUT), &std, &Matchers::new())));
+    }
+
+    #[test]
+    fn note_with_several_features() {
+        let features = ["a".to_owned(), "b".to_owned(), "c".to_owned()];
+        assert_eq!("Requires the `a`, `b` and `c` features.", note(&features));
+    }
+}
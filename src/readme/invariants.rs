@@ -0,0 +1,132 @@
+//! Invariants of the transformation of the docs
+//!
+//! Whatever the docs, transforming them must keep their structure: the same number of code blocks
+//! and headings, no code block left open unless it already was, and no hidden line left in the
+//! rust code blocks. This is checked in debug builds, which warn when it breaks, and by property
+//! tests on generated docs and the fuzzer, which panic, to catch changes of the transformation
+//! that break it.
+
+use super::blocks::{Block, Blocks};
+use super::matchers::Matchers;
+use super::transform::is_hidden_line;
+
+/// What must be the same before and after the transformation
+#[derive(Debug, PartialEq)]
+struct Structure {
+    code_blocks: usize,
+    headings: usize,
+    /// Whether the last code block is never closed
+    unclosed: bool,
+}
+
+/// Check that `output` is a transformation of `input` that keeps its structure
+pub fn check<'a, 'b, I, O>(input: I, output: O, matchers: &Matchers) -> Result<(), String>
+where
+    I: Iterator<Item = &'a str>,
+    O: Iterator<Item = &'b str> + Clone,
+{
+    let mut blocks = Blocks::new(matchers);
+    let mut in_rust = false;
+    for (i, line) in output.clone().enumerate() {
+        match blocks.parse(line) {
+            (_, _, Block::Open(fence)) => in_rust = fence.info == "rust",
+            (_, ref text, Block::Code) if in_rust && is_hidden_line(text) => {
+                return Err(format!("line {} is a hidden line of a rust code block", i + 1));
+            }
+            (_, _, Block::Code) => {}
+            _ => in_rust = false,
+        }
+    }
+
+    let before = structure(input, matchers);
+    let after = structure(output, matchers);
+    if before != after {
+        return Err(format!("the structure of the docs changed from {:?} to {:?}", before, after));
+    }
+
+    Ok(())
+}
+
+fn structure<'a, I: Iterator<Item = &'a str>>(lines: I, matchers: &Matchers) -> Structure {
+    let mut blocks = Blocks::new(matchers);
+    let mut structure = Structure {
+        code_blocks: 0,
+        headings: 0,
+        unclosed: false,
+    };
+    for line in lines {
+        match blocks.parse(line) {
+            (_, _, Block::Open(_)) => structure.code_blocks += 1,
+            (_, ref text, Block::Text) if matchers.heading.is_match(text) => {
+                structure.headings += 1
+            }
+            _ => {}
+        }
+    }
+    structure.unclosed = blocks.in_code();
+
+    structure
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::check;
+    use super::super::extract::Line;
+    use super::super::matchers::Matchers;
+    use super::super::transform::DocTransformer;
+
+    /// Lines of docs, mostly markup, which can be in block quotes and list items
+    fn doc_line() -> impl Strategy<Value = String> {
+        let content = prop_oneof![
+            "(`{3,5}|~{3,5})(rust|text|ignore|toml|)",
+            "#{1,7}( [a-z]{0,5})?",
+            "# [a-z ]{0,5}",
+            "[a-z #`~>-]{0,12}",
+            Just(String::new()),
+        ];
+        let container = prop_oneof![
+            Just(String::new()),
+            " {1,5}",
+            "(> ?){1,2}",
+            "( {0,3})(- |\\* |1\\. |10\\) )",
+        ];
+        (container, content).prop_map(|(container, content)| container + &content)
+    }
+
+    #[test]
+    fn structure_change_is_caught() {
+        let input = ["# Heading", "```", "# hidden", "```"];
+        let hidden_left = ["## Heading", "```rust", "# hidden", "```"];
+        let heading_lost = ["####### Heading", "```rust", "```"];
+        let matchers = Matchers::new();
+
+        assert!(check(input.iter().cloned(), hidden_left.iter().cloned(), &matchers).is_err());
+        assert!(check(input.iter().cloned(), heading_lost.iter().cloned(), &matchers).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn transform_keeps_structure(
+            docs in prop::collection::vec(doc_line(), 0..40),
+            indent_headings in any::<bool>(),
+        ) {
+            let input: Vec<Line> = docs
+                .iter()
+                .enumerate()
+                .map(|(i, line)| Line::new(line.as_str(), i + 1))
+                .collect();
+            let matchers = Matchers::new();
+            let transformer = DocTransformer::new(input, indent_headings, &matchers);
+            let output: Vec<Line> = transformer.collect();
+
+            let result = check(
+                docs.iter().map(|line| line.as_str()),
+                output.iter().map(|line| line.text.as_str()),
+                &matchers,
+            );
+            prop_assert_eq!(Ok(()), result);
+        }
+    }
+}
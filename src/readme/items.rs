@@ -0,0 +1,253 @@
+//! Link the public items of the crate mentioned in inline code to their page on docs.rs
+//!
+//! The items are read from a file, either the JSON output of rustdoc, see `rustdoc`, or a list
+//! with the kind and path of an item on each line:
+//!
+//! ```text
+//! # kind path, the path relative to the crate root
+//! struct client::Client
+//! fn run
+//! mod client
+//! ```
+//!
+//! Inline code that is an item name, like `` `Client` ``, its path, like `` `client::Client` ``,
+//! or a function call, like `` `run()` ``, becomes a link to the page of the item. Names shared
+//! by several items are left alone, as are code blocks and inline code that is already a link.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::blocks::Fence;
+use super::matchers::Matchers;
+use super::rustdoc::RustdocJson;
+
+pub const REGEX_INLINE_CODE: &str = r"`([A-Za-z_][\w:]*)(\(\))?`";
+
+/// Kinds of items, as rustdoc names them in the JSON output, and in the file names of their pages
+const KINDS: &[(&str, &str)] = &[
+    ("module", "mod"),
+    ("struct", "struct"),
+    ("enum", "enum"),
+    ("union", "union"),
+    ("trait", "trait"),
+    ("trait_alias", "traitalias"),
+    ("function", "fn"),
+    ("type_alias", "type"),
+    ("typedef", "type"),
+    ("constant", "constant"),
+    ("static", "static"),
+    ("macro", "macro"),
+    ("proc_attribute", "attr"),
+    ("proc_derive", "derive"),
+];
+
+/// An item, as its kind in the file names of the pages and its path, starting with the crate
+pub type Item = (&'static str, Vec<String>);
+
+/// Kind of an item in the file names of the pages, from the kind in the JSON output of rustdoc
+pub fn page_kind(json_kind: &str) -> Option<&'static str> {
+    KINDS.iter().find(|&&(name, _)| name == json_kind).map(|&(_, kind)| kind)
+}
+
+/// Url of the page of an item, in the docs of its crate at `root`, like `https://docs.rs/foo/1.0.0`
+pub fn item_url(root: &str, kind: &str, path: &[String]) -> String {
+    match kind {
+        "mod" => format!("{}/{}/index.html", root, path.join("/")),
+        _ => {
+            let (name, modules) = path.split_last().unwrap();
+            format!("{}/{}/{}.{}.html", root, modules.join("/"), kind, name)
+        }
+    }
+}
+
+/// Pages of the public items, by the names and paths they can be mentioned with
+pub struct Items {
+    /// Url and kind of each item, or `None` if the name is shared by several items
+    urls: HashMap<String, Option<(String, &'static str)>>,
+}
+
+impl Items {
+    /// Read the items from `file`, rustdoc JSON if its name ends with `.json`, a list otherwise
+    ///
+    /// The pages are in the docs at `root`, those of the crate whose library is `lib_name`.
+    pub fn read(file: &Path, root: &str, lib_name: &str) -> Result<Self, String> {
+        if file.extension().is_some_and(|ext| ext == "json") {
+            let rustdoc = RustdocJson::read(file, root)?;
+            return Ok(Items::new(root, rustdoc.items));
+        }
+
+        let mut content = String::new();
+        File::open(file)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| format!("Could not read file '{}': {}", file.to_string_lossy(), e))?;
+        let items = from_list(&content, lib_name)
+            .map_err(|e| format!("Invalid item list in '{}': {}", file.to_string_lossy(), e))?;
+
+        Ok(Items::new(root, items))
+    }
+
+    /// Items of the crate whose docs are at `root`
+    pub fn new(root: &str, items: Vec<Item>) -> Self {
+        let mut urls: HashMap<String, Option<(String, &'static str)>> = HashMap::new();
+        for (kind, path) in items {
+            let url = item_url(root, kind, &path);
+
+            // mentioned by their path from the crate root, or their name
+            let mut names = vec![path[1..].join("::")];
+            if path.len() > 2 {
+                names.push(path.last().unwrap().clone());
+            }
+            for name in names {
+                let entry = urls.entry(name).or_insert_with(|| Some((url.clone(), kind)));
+                if entry.as_ref().map(|(other, _)| other) != Some(&url) {
+                    *entry = None;
+                }
+            }
+        }
+
+        Items { urls }
+    }
+
+    /// Url of the page of the item mentioned as `name`, followed by `()` if `call` is true
+    fn url(&self, name: &str, call: bool) -> Option<&str> {
+        match self.urls.get(name) {
+            Some(Some((url, kind))) if !call || *kind == "fn" => Some(url),
+            _ => None,
+        }
+    }
+}
+
+/// Items listed as their kind and path from the root of the crate, one per line
+fn from_list(content: &str, lib_name: &str) -> Result<Vec<Item>, String> {
+    let mut items = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let (kind, path) = match (words.next(), words.next(), words.next()) {
+            (Some(kind), Some(path), None) => (kind, path),
+            _ => return Err(format!("line {} is not a kind and a path", i + 1)),
+        };
+        let kind = KINDS
+            .iter()
+            .map(|&(_, kind)| kind)
+            .find(|&known| known == kind)
+            .ok_or_else(|| format!("line {}: unknown kind of item '{}'", i + 1, kind))?;
+        let mut full_path = vec![lib_name.to_owned()];
+        full_path.extend(path.split("::").map(|s| s.to_owned()));
+        items.push((kind, full_path));
+    }
+
+    Ok(items)
+}
+
+/// Turn the inline code naming an item into a link to its page, outside of code blocks
+pub fn link_items(readme: &str, items: &Items, matchers: &Matchers) -> String {
+    let mut fence: Option<Fence> = None;
+    let lines: Vec<String> = readme
+        .split('\n')
+        .map(|line| {
+            if let Some(ref open) = fence {
+                if open.is_closed_by(line) {
+                    fence = None;
+                }
+                return line.to_owned();
+            }
+            fence = Fence::open(line);
+            if fence.is_some() {
+                return line.to_owned();
+            }
+            link_line(line, items, matchers)
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+fn link_line(line: &str, items: &Items, matchers: &Matchers) -> String {
+    let mut result = String::new();
+    let mut last = 0;
+    for caps in matchers.inline_code.captures_iter(line) {
+        let code = caps.get(0).unwrap();
+        // inline code that is already the text of a link
+        let in_link = line[..code.start()].ends_with('[') && line[code.end()..].starts_with("](");
+        let url = match items.url(&caps[1], caps.get(2).is_some()) {
+            Some(url) if !in_link => url,
+            _ => continue,
+        };
+
+        result.push_str(&line[last..code.start()]);
+        result.push_str(&format!("[{}]({})", code.as_str(), url));
+        last = code.end();
+    }
+    result.push_str(&line[last..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT: &str = "https://docs.rs/my-crate/1.0.0";
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn items_from_list() {
+        let list = concat_lines!(
+            "# items of my_crate",
+            "struct client::Client",
+            "",
+            "fn run",
+            "mod client",
+        );
+        let expected = vec![
+            ("struct", path(&["my_crate", "client", "Client"])),
+            ("fn", path(&["my_crate", "run"])),
+            ("mod", path(&["my_crate", "client"])),
+        ];
+        assert_eq!(Ok(expected), from_list(list, "my_crate"));
+        assert!(from_list("class Client", "my_crate").is_err());
+        assert!(from_list("struct", "my_crate").is_err());
+    }
+
+    #[test]
+    fn link_inline_code() {
+        let items = Items::new(
+            ROOT,
+            vec![
+                ("struct", path(&["my_crate", "client", "Client"])),
+                ("fn", path(&["my_crate", "run"])),
+                ("mod", path(&["my_crate", "client"])),
+                ("struct", path(&["my_crate", "a", "Error"])),
+                ("struct", path(&["my_crate", "b", "Error"])),
+            ],
+        );
+        let input = concat!(
+            "Create a `Client` with `client::Client::new`, then `run()`, not `Client()`.\n",
+            "See [`client`](https://example.com) and `Error`, `b::Error` or `other`.\n",
+            "```\n",
+            "`Client`\n",
+            "```",
+        );
+        let expected = concat!(
+            "Create a [`Client`](https://docs.rs/my-crate/1.0.0/my_crate/client/struct.Client.html) ",
+            "with `client::Client::new`, then ",
+            "[`run()`](https://docs.rs/my-crate/1.0.0/my_crate/fn.run.html), not `Client()`.\n",
+            "See [`client`](https://example.com) and `Error`, ",
+            "[`b::Error`](https://docs.rs/my-crate/1.0.0/my_crate/b/struct.Error.html) or `other`.\n",
+            "```\n",
+            "`Client`\n",
+            "```",
+        );
+        assert_eq!(expected, link_items(input, &items, &Matchers::new()));
+    }
+}
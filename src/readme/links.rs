@@ -0,0 +1,601 @@
+//! Rewrite the links of the generated readme
+//!
+//! Links to other sites can be replaced by their text, and inline links can be turned into
+//! reference links, with the destinations gathered at the end of the readme or of each section.
+//! Reference links whose definition is shared by the crates of a workspace, in
+//! `readme-links.toml`, get it added at the end of the readme.
+//!
+//! Relative links and images, which only work next to the files of the repository, can be made
+//! absolute, pointing to the forge hosting it.
+
+use std::collections::{BTreeMap, HashSet};
+
+use super::blocks::Fence;
+use super::matchers::Matchers;
+
+pub const REGEX_EXTERNAL_LINK: &str = r"(^|[^!])\[([^\[\]]*)\]\(\s*(https?:)?//[^)]*\)";
+
+/// Replace the links to other sites by their text, leaving images and code blocks untouched
+pub fn strip_external_links(readme: &str, matchers: &Matchers) -> String {
+
+    let mut fence: Option<Fence> = None;
+    let lines: Vec<String> = readme
+        .split('\n')
+        .map(|line| {
+            if let Some(ref open) = fence {
+                if open.is_closed_by(line) {
+                    fence = None;
+                }
+                return line.to_owned();
+            }
+            fence = Fence::open(line);
+            if fence.is_some() {
+                return line.to_owned();
+            }
+            matchers.external_link.replace_all(line, "$1$2").into_owned()
+        })
+        .collect();
+
+    lines.join("\n")
+}
+
+/// Turn the inline links and images into reference links, defined at the end of the readme, or
+/// at the end of each section, before the next heading, if `per_section` is set
+///
+/// Links are numbered in the order they appear, skipping the labels the readme already defines,
+/// and links to the same destination share their number. Code blocks and inline code are left
+/// untouched.
+pub fn reference_links(readme: &str, per_section: bool, matchers: &Matchers) -> String {
+    let mut references = References {
+        taken: readme
+            .lines()
+            .filter_map(|line| matchers.link_definition.captures(line))
+            .map(|caps| caps[2].to_lowercase())
+            .collect(),
+        next: 1,
+        pending: Vec::new(),
+    };
+
+    let mut lines = Vec::new();
+    let mut fence: Option<Fence> = None;
+    for line in readme.split('\n') {
+        if let Some(ref open) = fence {
+            if open.is_closed_by(line) {
+                fence = None;
+            }
+            lines.push(line.to_owned());
+            continue;
+        }
+        fence = Fence::open(line);
+        if fence.is_some() {
+            lines.push(line.to_owned());
+            continue;
+        }
+
+        if per_section && matchers.section_heading.is_match(line) {
+            references.define(&mut lines);
+        }
+        lines.push(reference_line(line, &mut references));
+    }
+    references.define(&mut lines);
+
+    lines.join("\n")
+}
+
+/// Make the relative destinations of the links, images and link definitions absolute
+///
+/// `url` gives the absolute destination of a relative one, and whether it is an image, or
+/// nothing to leave it as it is. Destinations with a scheme, like `https:` or `mailto:`, and
+/// anchors of the readme are not relative.
+pub fn absolute_links<F>(readme: &str, url: F, matchers: &Matchers) -> String
+where
+    F: Fn(&str, bool) -> Option<String>,
+{
+    let absolute = |destination: &str, image: bool| {
+        if is_relative(destination) {
+            url(destination, image)
+        } else {
+            None
+        }
+    };
+
+    let mut lines = Vec::new();
+    let mut fence: Option<Fence> = None;
+    for line in readme.split('\n') {
+        if let Some(ref open) = fence {
+            if open.is_closed_by(line) {
+                fence = None;
+            }
+            lines.push(line.to_owned());
+            continue;
+        }
+        fence = Fence::open(line);
+        if fence.is_some() {
+            lines.push(line.to_owned());
+            continue;
+        }
+
+        let definition = matchers.link_definition.captures(line);
+        let line = match definition.and_then(|caps| {
+            let image = is_image_file(&caps[3]);
+            absolute(&caps[3], image).map(|url| format!("{}{}{}", &caps[1], url, &caps[4]))
+        }) {
+            Some(line) => line,
+            None => absolute_line(line, &absolute),
+        };
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+fn absolute_line<F: Fn(&str, bool) -> Option<String>>(line: &str, absolute: &F) -> String {
+    let spans = code_spans(line);
+    let in_code = |i: usize| spans.iter().any(|&(start, end)| start <= i && i < end);
+
+    let mut result = String::new();
+    let mut last = 0;
+    let mut search = 0;
+    while let Some(found) = line[search..].find("](") {
+        let close = search + found;
+        search = close + 2;
+        let open = match opening_bracket(line, close, in_code) {
+            Some(open) if !in_code(close) => open,
+            _ => continue,
+        };
+        let (destination, end) = match destination(&line[close + 2..]) {
+            Some(destination) => destination,
+            None => continue,
+        };
+
+        // the url is followed by the title, if there is one
+        let (target, title) = match destination.find(char::is_whitespace) {
+            Some(i) => destination.split_at(i),
+            None => (destination.as_str(), ""),
+        };
+        let image = line[..open].ends_with('!') || is_image_file(target);
+        if let Some(url) = absolute(target, image) {
+            result.push_str(&line[last..close + 2]);
+            result.push_str(&url);
+            result.push_str(title);
+            last = close + 2 + end;
+            search = last;
+        }
+    }
+    result.push_str(&line[last..]);
+
+    result
+}
+
+/// Whether a destination is a path, not a url with a scheme or an anchor of the readme
+fn is_relative(destination: &str) -> bool {
+    let scheme_end = destination.find(':');
+    let path_start = destination.find(['/', '#', '?']);
+    let has_scheme = match (scheme_end, path_start) {
+        (Some(colon), Some(slash)) => colon < slash,
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+    !(has_scheme
+        || destination.is_empty()
+        || destination.starts_with(['#', '<'])
+        || destination.starts_with("//"))
+}
+
+fn is_image_file(destination: &str) -> bool {
+    let path = destination.split(['#', '?']).next().unwrap_or("").to_lowercase();
+    [".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp"].iter().any(|ext| path.ends_with(ext))
+}
+
+/// Add the definitions of `links`, by label, that the readme uses but does not define at its end
+///
+/// A label is used by a reference link, like `[text][label]` or `[label]`, outside of code.
+pub fn add_shared_links(
+    readme: &str,
+    links: &BTreeMap<String, String>,
+    matchers: &Matchers,
+) -> String {
+    let taken: HashSet<String> = readme
+        .lines()
+        .filter_map(|line| matchers.link_definition.captures(line))
+        .map(|caps| caps[2].to_lowercase())
+        .collect();
+
+    let mut used = HashSet::new();
+    let mut fence: Option<Fence> = None;
+    for line in readme.lines() {
+        if let Some(ref open) = fence {
+            if open.is_closed_by(line) {
+                fence = None;
+            }
+            continue;
+        }
+        fence = Fence::open(line);
+        if fence.is_none() && !matchers.link_definition.is_match(line) {
+            used.extend(reference_labels(line));
+        }
+    }
+
+    let mut references = References {
+        pending: links
+            .iter()
+            .filter(|&(label, _)| {
+                let label = label.to_lowercase();
+                used.contains(&label) && !taken.contains(&label)
+            })
+            .map(|(label, destination)| (label.clone(), destination.clone()))
+            .collect(),
+        taken,
+        next: 1,
+    };
+    let mut lines: Vec<String> = readme.split('\n').map(|line| line.to_owned()).collect();
+    references.define(&mut lines);
+
+    lines.join("\n")
+}
+
+/// Labels, in lowercase, of the brackets of a line that are not inline links or code
+fn reference_labels(line: &str) -> Vec<String> {
+    let spans = code_spans(line);
+    let in_code = |i: usize| spans.iter().any(|&(start, end)| start <= i && i < end);
+
+    let mut labels = Vec::new();
+    let mut open = None;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if in_code(i) => {}
+            '[' => open = Some(i),
+            ']' => {
+                if let Some(start) = open.take() {
+                    if !line[i + 1..].starts_with('(') && i > start + 1 {
+                        labels.push(line[start + 1..i].to_lowercase());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    labels
+}
+
+/// Labels of the reference links that are not defined yet
+struct References {
+    /// Labels defined in the readme already, in lowercase
+    taken: HashSet<String>,
+    next: usize,
+    /// Label and destination of each link, with its title if it has one
+    pending: Vec<(String, String)>,
+}
+
+impl References {
+    fn label(&mut self, destination: &str) -> String {
+        if let Some((label, _)) = self.pending.iter().find(|(_, other)| other == destination) {
+            return label.clone();
+        }
+        while self.taken.contains(&self.next.to_string()) {
+            self.next += 1;
+        }
+        let label = self.next.to_string();
+        self.next += 1;
+        self.pending.push((label.clone(), destination.to_owned()));
+        label
+    }
+
+    /// Add the definitions of the pending links after the lines, before their trailing blank lines
+    fn define(&mut self, lines: &mut Vec<String>) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let blank = lines.iter().rev().take_while(|line| line.trim().is_empty()).count();
+        let blank = lines.split_off(lines.len() - blank);
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(
+            self.pending
+                .drain(..)
+                .map(|(label, destination)| format!("[{}]: {}", label, destination)),
+        );
+        lines.extend(blank);
+    }
+}
+
+fn reference_line(line: &str, references: &mut References) -> String {
+    let spans = code_spans(line);
+    let in_code = |i: usize| spans.iter().any(|&(start, end)| start <= i && i < end);
+
+    let mut result = String::new();
+    let mut last = 0;
+    let mut search = 0;
+    while let Some(found) = line[search..].find("](") {
+        let close = search + found;
+        search = close + 2;
+        if in_code(close) || !has_opening_bracket(line, close, in_code) {
+            continue;
+        }
+        let (destination, end) = match destination(&line[close + 2..]) {
+            Some(destination) => destination,
+            None => continue,
+        };
+
+        result.push_str(&line[last..=close]);
+        result.push_str(&format!("[{}]", references.label(&destination)));
+        last = close + 2 + end + 1;
+        search = last;
+    }
+    result.push_str(&line[last..]);
+
+    result
+}
+
+/// Byte ranges of the inline code of a line, backticks included
+pub fn code_spans(line: &str) -> Vec<(usize, usize)> {
+    let bytes = line.as_bytes();
+    let run = |i: usize| bytes[i..].iter().take_while(|&&b| b == b'`').count();
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let len = run(i);
+        // the span ends at the next run of as many backticks
+        let mut j = i + len;
+        let mut end = None;
+        while j < bytes.len() {
+            if bytes[j] == b'`' {
+                let other = run(j);
+                if other == len {
+                    end = Some(j + other);
+                    break;
+                }
+                j += other;
+            } else {
+                j += 1;
+            }
+        }
+        match end {
+            Some(end) => {
+                spans.push((i, end));
+                i = end;
+            }
+            None => i += len,
+        }
+    }
+
+    spans
+}
+
+/// Whether the `]` at `close` ends the text of a link, started by a `[` before it
+fn has_opening_bracket<F: Fn(usize) -> bool>(line: &str, close: usize, in_code: F) -> bool {
+    opening_bracket(line, close, in_code).is_some()
+}
+
+/// Where the text of the link ended by the `]` at `close` starts, with its `[`
+fn opening_bracket<F: Fn(usize) -> bool>(line: &str, close: usize, in_code: F) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in line[..close].char_indices().rev() {
+        if in_code(i) {
+            continue;
+        }
+        match c {
+            ']' => depth += 1,
+            '[' if depth == 0 => return Some(i),
+            '[' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Destination of a link, with its title, and where its closing parenthesis is in `rest`
+fn destination(rest: &str) -> Option<(String, usize)> {
+    let mut depth = 0;
+    let end = rest.char_indices().find_map(|(i, c)| match c {
+        '(' => {
+            depth += 1;
+            None
+        }
+        ')' if depth == 0 => Some(i),
+        ')' => {
+            depth -= 1;
+            None
+        }
+        _ => None,
+    })?;
+
+    let inner = rest[..end].trim();
+    let (url, title) = match inner.find(char::is_whitespace) {
+        Some(i) => (&inner[..i], inner[i..].trim()),
+        None => (inner, ""),
+    };
+    let is_title = |title: &str| {
+        title.len() >= 2
+            && [('"', '"'), ('\'', '\''), ('(', ')')]
+                .iter()
+                .any(|&(open, close)| title.starts_with(open) && title.ends_with(close))
+    };
+    if url.is_empty() || !(title.is_empty() || is_title(title)) {
+        return None;
+    }
+
+    let destination = if title.is_empty() {
+        url.to_owned()
+    } else {
+        format!("{} {}", url, title)
+    };
+    Some((destination, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{absolute_links, add_shared_links, reference_links, strip_external_links};
+    use super::super::matchers::Matchers;
+
+    #[test]
+    fn strip_http_links() {
+        let input = "See [the docs](https://docs.rs/my_crate) and [the site](http://example.com).";
+        let expected = "See the docs and the site.";
+
+        assert_eq!(expected, strip_external_links(input, &Matchers::new()));
+    }
+
+    #[test]
+    fn keep_relative_links_and_images() {
+        let input = concat_lines!(
+            "[Changelog](CHANGELOG.md)",
+            "![logo](https://example.com/logo.png)",
+        );
+
+        assert_eq!(input, strip_external_links(input, &Matchers::new()));
+    }
+
+    #[test]
+    fn keep_code_blocks() {
+        let input = concat_lines!(
+            "```",
+            "[not a link](https://example.com)",
+            "```",
+            "[a link](https://example.com)",
+        );
+        let expected = concat_lines!(
+            "```",
+            "[not a link](https://example.com)",
+            "```",
+            "a link",
+        );
+
+        assert_eq!(expected, strip_external_links(input, &Matchers::new()));
+    }
+
+    #[test]
+    fn inline_links_to_references() {
+        let input = concat_lines!(
+            "[![ci](https://ci.example.com/badge.svg)](https://ci.example.com)",
+            "",
+            "See [the docs](https://docs.rs/my_crate \"Docs\") and `[not](a link)`,",
+            "[Wikipedia](https://en.wikipedia.org/wiki/Rust_(programming_language)) and",
+            "[the docs](https://docs.rs/my_crate \"Docs\") again.",
+            "",
+            "[1]: https://example.com",
+            "```",
+            "[code](https://example.com)",
+            "```",
+        );
+        let expected = concat_lines!(
+            "[![ci][2]][3]",
+            "",
+            "See [the docs][4] and `[not](a link)`,",
+            "[Wikipedia][5] and",
+            "[the docs][4] again.",
+            "",
+            "[1]: https://example.com",
+            "```",
+            "[code](https://example.com)",
+            "```",
+            "",
+            "[2]: https://ci.example.com/badge.svg",
+            "[3]: https://ci.example.com",
+            "[4]: https://docs.rs/my_crate \"Docs\"",
+            "[5]: https://en.wikipedia.org/wiki/Rust_(programming_language)",
+        );
+
+        assert_eq!(expected, reference_links(input, false, &Matchers::new()));
+    }
+
+    #[test]
+    fn references_per_section() {
+        let input = concat_lines!(
+            "Intro with [a link](https://example.com).",
+            "",
+            "## Usage",
+            "",
+            "[Again](https://example.com)",
+        );
+        let expected = concat_lines!(
+            "Intro with [a link][1].",
+            "",
+            "[1]: https://example.com",
+            "",
+            "## Usage",
+            "",
+            "[Again][2]",
+            "",
+            "[2]: https://example.com",
+        );
+
+        assert_eq!(expected, reference_links(input, true, &Matchers::new()));
+    }
+
+    #[test]
+    fn shared_links_used_and_not_defined() {
+        let input = concat_lines!(
+            "Read [the book][book], the [Nomicon] and [the docs](https://docs.rs).",
+            "",
+            "`[std]` and [Clippy][clippy].",
+            "```",
+            "[reference]",
+            "```",
+            "",
+            "[clippy]: https://example.com/clippy",
+        );
+        let expected = concat_lines!(
+            "Read [the book][book], the [Nomicon] and [the docs](https://docs.rs).",
+            "",
+            "`[std]` and [Clippy][clippy].",
+            "```",
+            "[reference]",
+            "```",
+            "",
+            "[clippy]: https://example.com/clippy",
+            "",
+            "[book]: https://doc.rust-lang.org/book/",
+            "[nomicon]: https://doc.rust-lang.org/nomicon/",
+        );
+
+        let mut links = BTreeMap::new();
+        for &(label, destination) in &[
+            ("book", "https://doc.rust-lang.org/book/"),
+            ("clippy", "https://doc.rust-lang.org/clippy/"),
+            ("nomicon", "https://doc.rust-lang.org/nomicon/"),
+            ("reference", "https://doc.rust-lang.org/reference/"),
+            ("std", "https://doc.rust-lang.org/std/"),
+        ] {
+            links.insert(label.to_owned(), destination.to_owned());
+        }
+
+        assert_eq!(expected, add_shared_links(input, &links, &Matchers::new()));
+    }
+
+    #[test]
+    fn absolute_relative_links() {
+        let input = concat_lines!(
+            "![logo](assets/logo.png \"Logo\") See [the guide](docs/guide.md#setup), [home](/).",
+            "[site](https://example.com), [mail](mailto:a@b.c), [top](#usage), `[x](y.md)`",
+            "[![badge](badge.svg)](../other)",
+            "```",
+            "[code](file.md)",
+            "```",
+            "[changes]: CHANGELOG.md",
+        );
+        let expected = concat_lines!(
+            "![logo](raw:assets/logo.png \"Logo\") See [the guide](page:docs/guide.md#setup), \
+             [home](page:/).",
+            "[site](https://example.com), [mail](mailto:a@b.c), [top](#usage), `[x](y.md)`",
+            "[![badge](raw:badge.svg)](page:../other)",
+            "```",
+            "[code](file.md)",
+            "```",
+            "[changes]: page:CHANGELOG.md",
+        );
+
+        let url = |path: &str, image: bool| {
+            Some(format!("{}:{}", if image { "raw" } else { "page" }, path))
+        };
+        assert_eq!(expected, absolute_links(input, url, &Matchers::new()));
+    }
+}
@@ -0,0 +1,61 @@
+//! Regexes used to transform the docs
+//!
+//! Compiling the regexes takes longer than running them on the docs of most crates, so they are
+//! compiled once into `Matchers`, which is passed along the transformation. When generating the
+//! readmes of several crates, the same `Matchers` is used for all of them. Cloning it shares the
+//! compiled regexes.
+
+use regex::Regex;
+
+use super::{features, items, links, rustdoc, sections, transform};
+use super::blocks;
+
+#[derive(Clone)]
+pub struct Matchers {
+    /// Is this code block rust?
+    pub code_rust: Regex,
+    /// Is this code block just text?
+    pub code_text: Regex,
+    /// Is this a heading, and what is its text?
+    pub heading: Regex,
+    /// Is this heading already at the deepest level markdown supports?
+    pub heading_max_level: Regex,
+    /// Does this line link to a rust path, which only rustdoc knows how to resolve?
+    pub intra_doc_link: Regex,
+    /// Does this line start a list item?
+    pub list_item: Regex,
+    /// Level and title of a heading, to split the docs into sections
+    pub section_heading: Regex,
+    /// Condition of a `cfg` attribute
+    pub cfg: Regex,
+    /// Feature in a `cfg` condition
+    pub feature: Regex,
+    /// Link to another site
+    pub external_link: Regex,
+    /// Inline code that may name an item of the crate
+    pub inline_code: Regex,
+    /// Text and destination of a link, to resolve intra-doc links
+    pub link: Regex,
+    /// Label and destination of the definition of a reference link
+    pub link_definition: Regex,
+}
+
+impl Matchers {
+    pub fn new() -> Self {
+        Matchers {
+            code_rust: Regex::new(transform::REGEX_CODE_RUST).unwrap(),
+            code_text: Regex::new(transform::REGEX_CODE_TEXT).unwrap(),
+            heading: Regex::new(transform::REGEX_HEADING).unwrap(),
+            heading_max_level: Regex::new(transform::REGEX_HEADING_MAX_LEVEL).unwrap(),
+            intra_doc_link: Regex::new(transform::REGEX_INTRA_DOC_LINK).unwrap(),
+            list_item: Regex::new(blocks::REGEX_LIST_ITEM).unwrap(),
+            section_heading: Regex::new(sections::REGEX_HEADING).unwrap(),
+            cfg: Regex::new(features::REGEX_CFG).unwrap(),
+            feature: Regex::new(features::REGEX_FEATURE).unwrap(),
+            external_link: Regex::new(links::REGEX_EXTERNAL_LINK).unwrap(),
+            inline_code: Regex::new(items::REGEX_INLINE_CODE).unwrap(),
+            link: Regex::new(rustdoc::REGEX_LINK).unwrap(),
+            link_definition: Regex::new(rustdoc::REGEX_LINK_DEFINITION).unwrap(),
+        }
+    }
+}
@@ -1,16 +1,182 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+mod anchors;
+mod blocks;
+mod breaks;
+mod check;
+mod combine;
 mod extract;
+mod features;
+mod invariants;
+mod items;
+mod links;
+mod matchers;
+mod release;
+mod rustdoc;
+mod sections;
+mod stats;
 mod transform;
 mod template;
 
+pub use self::anchors::heading_anchors;
+pub use self::check::{compare_sections, is_up_to_date, unified_diff, Difference};
+pub use self::combine::combined_docs;
+pub use self::stats::{doc_stats, DocStats};
+use self::extract::Line;
+use self::matchers::Matchers;
+use self::template::{Context, ContributorsCache, Delimiters, FileUrls, Template};
 use self::transform::DocTransform;
-use cargo_info;
+use cargo_info::{self, Cargo};
+use config::{Config, ReferenceLinks, Split};
+use toml;
+use workspace;
+
+/// Generated readme along with the warnings found while generating it
+pub struct Readme {
+    pub content: String,
+    pub warnings: Vec<Warning>,
+    /// Sections moved out of the readme with the `split` setting
+    pub split: Option<SplitDocs>,
+    /// Number of doc comment lines found, if none of them has any text
+    pub empty_docs: Option<usize>,
+}
+
+/// Sections of the docs that do not fit in a short readme, and the file they go to
+pub struct SplitDocs {
+    /// File to write them to, relative to the project root
+    pub file: String,
+    pub content: String,
+}
+
+/// A problem that does not prevent the readme from being generated
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// Line of the source file where the problem was found, starting at 1, if it was found in
+    /// the doc comments
+    pub src_line: Option<usize>,
+    pub message: String,
+}
+
+impl Warning {
+    /// Warning about the doc comments at the given line of the source file
+    pub fn new<S: Into<String>>(src_line: usize, message: S) -> Self {
+        Warning {
+            src_line: Some(src_line),
+            message: message.into(),
+        }
+    }
+
+    /// Warning about something other than the doc comments, like the template
+    pub fn without_line<S: Into<String>>(message: S) -> Self {
+        Warning {
+            src_line: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.src_line {
+            Some(src_line) => write!(f, "line {}: {}", src_line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// How the readme is generated
+#[derive(Clone)]
+pub struct Options {
+    pub add_title: bool,
+    pub add_license: bool,
+    pub indent_headings: bool,
+    /// Whether template tags are allowed to run commands, like `{{cli-help}}` does
+    pub allow_exec: bool,
+    /// Fail if the docs have fewer non blank lines than this
+    pub min_lines: usize,
+    /// Fail if the docs do not have top level sections with these titles
+    pub required_sections: Vec<String>,
+    /// Fail if the readme is larger than this, in bytes
+    pub max_output_bytes: usize,
+    /// Settings from `Cargo.toml`, see `Cargo::config`
+    pub config: Config,
+    /// Restrict the template, for templates that are not trusted
+    ///
+    /// No command is run, whatever `allow_exec` is, files outside of the project root cannot be
+    /// read and the readme cannot be larger than `MAX_SANDBOXED_SIZE`.
+    pub sandbox: bool,
+    /// Output the docs as they are extracted, with no template, title or license, and none of
+    /// the transformations and settings applied
+    pub raw: bool,
+    /// Version of the crate to render instead of the one of `Cargo.toml`, for the release about
+    /// to be made
+    pub version: Option<String>,
+    /// Time of the dates of the template, in seconds since the Unix epoch, instead of the
+    /// current time, for readmes that are the same on every run
+    pub date: Option<u64>,
+    /// Warn about the tags of the template that depend on more than the sources, like the git
+    /// history
+    pub reproducible: bool,
+    /// Crate information to use instead of the one of `Cargo.toml`, like the manifest embedded
+    /// in a cargo script
+    pub cargo: Option<Cargo>,
+    /// Values of tags of the template that are not built in, like numbers fetched by CI
+    pub vars: BTreeMap<String, String>,
+    /// Allow tags to query crates.io for the data of the published crate
+    pub crates_io: bool,
+    /// Allow tags to query docs.rs for the status of the docs build, and warn if it failed
+    pub docs_rs: bool,
+}
+
+/// Largest readme that can be generated with `Options::sandbox`, in bytes
+pub const MAX_SANDBOXED_SIZE: usize = 1024 * 1024;
+
+/// Default of `Options::max_output_bytes`
+///
+/// Far larger than any readme written by hand, it stops templates including files in a loop or
+/// huge generated docs from filling the disk.
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+
+/// File of reference link definitions shared by the crates of a workspace, by label
+///
+/// It is read from the project root, or else from the root of the workspace.
+pub const SHARED_LINKS_FILE: &str = "readme-links.toml";
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            add_title: true,
+            add_license: true,
+            indent_headings: true,
+            allow_exec: true,
+            min_lines: 0,
+            required_sections: Vec::new(),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            config: Config::default(),
+            sandbox: false,
+            raw: false,
+            version: None,
+            date: None,
+            reproducible: false,
+            cargo: None,
+            vars: BTreeMap::new(),
+            crates_io: false,
+            docs_rs: false,
+        }
+    }
+}
 
 /// Generates readme data from `source` file
 ///
-/// Optionally, a template can be used to render the output
+/// Optionally, a template can be used to render the output. Template tags that need to run
+/// commands, like `{{cli-help}}`, fail: they can be allowed with `Options::allow_exec` and
+/// `generate_readme_with_options`.
 pub fn generate_readme<T: Read>(
     project_root: &Path,
     source: &mut T,
@@ -19,42 +185,575 @@ pub fn generate_readme<T: Read>(
     add_license: bool,
     indent_headings: bool,
 ) -> Result<String, String> {
+    let config = cargo_info::get_cargo_info(project_root)?.config(None)?;
+    let options = Options {
+        add_title,
+        add_license,
+        indent_headings,
+        allow_exec: false,
+        config,
+        ..Options::default()
+    };
+
+    generate_readme_with_options(project_root, source, template, &options)
+        .map(|readme| readme.content)
+}
+
+/// Generates readme data from `source` file, with the settings in `options`
+///
+/// Unlike `generate_readme`, the readme comes with the warnings found while generating it and
+/// the sections moved to another file.
+pub fn generate_readme_with_options<T: Read>(
+    project_root: &Path,
+    source: &mut T,
+    template: Option<&mut T>,
+    options: &Options,
+) -> Result<Readme, String> {
+    Generator::new().generate(project_root, source, template, options)
+}
+
+/// Generates the readmes of several crates, reusing what does not depend on the crate
+///
+/// The regexes transforming the docs are compiled once, and templates are parsed once for all
+/// the crates using the same one, instead of again for every readme.
+pub struct Generator {
+    matchers: Matchers,
+    /// Parsed templates, by their content and delimiters
+    templates: HashMap<(String, Delimiters), Template>,
+    /// Contributors of the git repositories, listed once for all the crates in each
+    contributors: ContributorsCache,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator::new()
+    }
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Generator {
+            matchers: Matchers::new(),
+            templates: HashMap::new(),
+            contributors: ContributorsCache::new(),
+        }
+    }
+
+    /// Generates readme data from `source` file, with the settings in `options`
+    pub fn generate<T: Read>(
+        &mut self,
+        project_root: &Path,
+        source: &mut T,
+        template: Option<&mut T>,
+        options: &Options,
+    ) -> Result<Readme, String> {
+        let matchers = &self.matchers;
+        let Options { add_license, allow_exec, ref config, sandbox, .. } = *options;
+
+        // get cargo info from Cargo.toml, with the version of the release about to be made
+        let mut cargo = match options.cargo {
+            Some(ref cargo) => cargo.clone(),
+            None => cargo_info::get_cargo_info(project_root)?,
+        };
+        if let Some(ref version) = options.version {
+            cargo.package.version = Some(toml::Value::String(version.clone()));
+        }
+
+        // get template from file, parsing it unless it was already
+        let template = match template {
+            Some(template) => {
+                let key = (get_template_string(template)?, delimiters(config)?);
+                match self.templates.entry(key) {
+                    Entry::Occupied(entry) => Some(&*entry.into_mut()),
+                    Entry::Vacant(entry) => {
+                        let (ref content, ref delimiters) = *entry.key();
+                        let parsed = Template::parse(content, delimiters);
+                        Some(&*entry.insert(parsed))
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // the docs of the crate as rustdoc sees them, with the intra-doc links resolved
+        let root = format!(
+            "https://docs.rs/{}/{}",
+            cargo.package.name,
+            cargo.version().unwrap_or("latest")
+        );
+        let rustdoc = match config.rustdoc_json {
+            Some(ref file) => Some(rustdoc::RustdocJson::read(
+                &project_path(project_root, file, sandbox)?,
+                &root,
+            )?),
+            None => None,
+        };
+        let docs = match rustdoc {
+            Some(ref rustdoc) => rustdoc::resolve_links(rustdoc.lines(), &rustdoc.links, matchers),
+            None => extract::extract_docs(source).map_err(|e| format!("{}", e))?,
+        };
+        let empty_docs = if docs.iter().all(|line| line.text.trim().is_empty()) {
+            Some(docs.len())
+        } else {
+            None
+        };
+        if options.raw {
+            let content = docs.into_iter().map(|line| line.text).collect::<Vec<_>>().join("\n");
+            return Ok(Readme { content, warnings: Vec::new(), split: None, empty_docs });
+        }
+
+        let mut context = Context {
+            project_root,
+            cargo: &cargo,
+            config,
+            allow_exec: allow_exec && !sandbox,
+            sandbox,
+            warnings: Vec::new(),
+            contributors: &mut self.contributors,
+            date: options.date.unwrap_or_else(now),
+            reproducible: options.reproducible,
+            vars: &options.vars,
+            crates_io: options.crates_io,
+            docs_rs: options.docs_rs,
+        };
+
+        // a template extending another one is rendered as its parent, with its blocks, so the
+        // tags of its parents count as its own
+        let resolved = match template {
+            Some(template) => template.resolve(&mut context)?,
+            None => None,
+        };
+        let template = resolved.as_ref().or(template);
+
+        // the title the docs start with, which replaces the crate name if asked to, and is left
+        // out when it is the crate name, since the readme has it as title already
+        let mut docs = docs;
+        let mut warnings = Vec::new();
+        let mut title_line = None;
+        let crate_title = options.add_title && template.is_none_or(|t| t.has_tag("crate"));
+        match doc_title(&docs, matchers) {
+            Some((i, _)) if config.title_from_h1 => title_line = Some(docs.remove(i)),
+            Some((i, ref title))
+                if crate_title
+                    && config.dedupe_title.unwrap_or(true)
+                    && is_crate_name(title, &cargo.package.name) =>
+            {
+                docs.remove(i);
+            }
+            Some((i, ref title)) if crate_title => warnings.push(Warning::new(
+                docs[i].src_line,
+                format!(
+                    "The docs start with the title '{}' and the crate name is added as title \
+                     too, use `--set-title-from-h1` to only keep the one of the docs",
+                    title
+                ),
+            )),
+            None if config.title_from_h1 => warnings.push(Warning::without_line(
+                "The title should be taken from the docs, but they do not start with a `# Title` \
+                 heading, the crate name is used instead",
+            )),
+            _ => {}
+        }
+        let add_title = options.add_title && title_line.is_none();
+        let default_features = cargo.default_features();
+        let (readme, mut transform_warnings) =
+            transform_docs(docs, options, &default_features, matchers);
+        warnings.append(&mut transform_warnings);
+
+        check_content(&readme, options.min_lines, &options.required_sections, matchers)?;
+
+        // link the items of the crate mentioned in inline code to their page on docs.rs
+        let items = match (config.item_links.as_ref(), rustdoc) {
+            (Some(file), _) => Some(items::Items::read(
+                &project_path(project_root, file, sandbox)?,
+                &root,
+                &cargo.lib_name(),
+            )?),
+            (None, Some(rustdoc)) => Some(items::Items::new(&root, rustdoc.items)),
+            (None, None) => None,
+        };
+        let readme = match items {
+            Some(items) => items::link_items(&readme, &items, matchers),
+            None => readme,
+        };
+
+        // number the headings and move the sections that do not fit in a short readme to their
+        // own file, keeping the numbers of the sections that are moved
+        let (readme, split) = if config.number_headings || config.split.is_some() {
+            let mut document = sections::Document::parse(&readme, matchers);
+            if config.number_headings {
+                document.number_headings(matchers);
+            }
+            match config.split {
+                Some(ref split) => split_docs(document, split),
+                None => (document.render(), None),
+            }
+        } else {
+            (readme, None)
+        };
+
+        // add the files listed in `prepend` and `append` around the docs
+        let readme = add_static_sections(project_root, readme, config, sandbox)?;
+        let readme = match title_line {
+            Some(title) if readme.is_empty() => title.text,
+            Some(title) => format!("{}\n\n{}", title.text, readme),
+            None => readme,
+        };
+
+        if add_license && cargo.package.license.is_none() {
+            return Err("License not found in Cargo.toml".to_owned());
+        }
+
+        let mut content = template::render(template, readme, &mut context, add_title, add_license)?;
+        if config.strip_external_links {
+            content = links::strip_external_links(&content, matchers);
+        }
+        if config.absolute_links {
+            let urls = FileUrls::new(project_root, &cargo, config)
+                .map_err(|e| format!("`absolute-links` is set but {}", e))?;
+            let url = |path: &str, image: bool| urls.url(path, image);
+            content = links::absolute_links(&content, url, matchers);
+        }
+        if let Some(placement) = config.reference_links {
+            let per_section = placement == ReferenceLinks::Section;
+            content = links::reference_links(&content, per_section, matchers);
+        }
+        let shared_links = shared_links(project_root, sandbox)?;
+        if !shared_links.is_empty() {
+            content = links::add_shared_links(&content, &shared_links, matchers);
+        }
+        if config.sembr {
+            content = breaks::one_sentence_per_line(&content, matchers);
+        } else if let Some(max) = config.max_line_length {
+            content = breaks::break_long_lines(&content, max, matchers);
+        }
+        warnings.append(&mut context.warnings);
+        if let (true, Some(version)) = (config.warn_unreleased, cargo.version()) {
+            let name = &cargo.package.name;
+            let allow_exec = allow_exec && !sandbox;
+            warnings.extend(release::unreleased_warning(
+                project_root,
+                name,
+                version,
+                &content,
+                allow_exec,
+            ));
+        }
+
+        if sandbox && content.len() > MAX_SANDBOXED_SIZE {
+            return Err(format!(
+                "The readme is larger than {} bytes, which is not allowed with `--sandbox`",
+                MAX_SANDBOXED_SIZE
+            ));
+        }
+
+        if content.len() > options.max_output_bytes {
+            return Err(format!(
+                "The readme is {} bytes, larger than the limit of {} bytes, which can be raised \
+                 with `--max-output-bytes`",
+                content.len(),
+                options.max_output_bytes
+            ));
+        }
+
+        Ok(Readme { content, warnings, split, empty_docs })
+    }
+}
+
+/// Transform docs, as extracted from the doc comments, into the markdown of the readme
+///
+/// This is the part of `generate_readme_with_options` that reads no file: "Rustdoc-only"
+/// sections are removed, code blocks and headings are rewritten and sections are reordered, as
+/// set in `options`. Feature notes, if enabled, are added for every feature since the default
+/// ones are not known. It accepts any input, which makes it the entrypoint for fuzzing.
+pub fn transform_str(docs: &str, options: &Options) -> String {
+    let lines = docs
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Line::new(line, i + 1))
+        .collect();
+    transform_docs(lines, options, &[], &Matchers::new()).0
+}
 
-    let readme = extract::extract_docs(source)
-        .map_err(|e| format!("{}", e))?
+/// Transform the extracted docs, returning the markdown and the problems found in the docs
+fn transform_docs(
+    docs: Vec<Line>,
+    options: &Options,
+    default_features: &[String],
+    matchers: &Matchers,
+) -> (String, Vec<Warning>) {
+    let config = &options.config;
+
+    let mut docs = sections::remove_rustdoc_only(docs, matchers);
+    if config.feature_notes {
+        docs = features::add_feature_notes(docs, default_features, matchers);
+    }
+    // the structure of the docs is checked against the readme in debug builds
+    let input: Option<Vec<String>> = if cfg!(debug_assertions) {
+        Some(docs.iter().map(|line| line.text.clone()).collect())
+    } else {
+        None
+    };
+
+    let mut transformer = docs
         .into_iter()
-        .transform_doc(indent_headings)
+        .transform_doc(options.indent_headings, matchers)
+        .rename_headings(config.rename_headings.clone())
+        .prefix_headings(config.heading_prefixes.clone())
+        .fence_label(
+            config.code_fence_label.as_ref().map_or("rust", |label| label.as_str()),
+            config.keep_fence_attributes,
+        );
+
+    let readme = transformer
+        .by_ref()
         .fold(String::new(), |mut acc, x| {
             if !acc.is_empty() {
                 acc.push('\n');
             }
-            acc.push_str(&x);
+            acc.push_str(&x.text);
             acc
         });
 
-    // get template from file
-    let template = if let Some(template) = template {
-        Some(get_template_string(template)?)
+    // a broken invariant is a bug of the transformation, not of the docs, so only the tests and
+    // the fuzzer stop on it
+    let mut warnings = transformer.warnings().to_vec();
+    if let Some(input) = input {
+        let input = input.iter().map(|line| line.as_str());
+        if let Err(e) = invariants::check(input, readme.lines(), matchers) {
+            if cfg!(any(test, fuzzing)) {
+                panic!("The transformation of the docs broke an invariant: {}", e);
+            }
+            warnings.push(Warning::without_line(format!(
+                "The transformation of the docs broke an invariant, the readme may be wrong: {}",
+                e
+            )));
+        }
+    }
+
+    // move the sections listed in `section-order` to the top
+    let readme = if config.section_order.is_empty() {
+        readme
+    } else {
+        let mut document = sections::Document::parse(&readme, matchers);
+        document.reorder(&config.section_order);
+        document.render()
+    };
+
+    (readme, warnings)
+}
+
+/// Index and text of the `# Title` heading the docs start with, if they start with one
+fn doc_title(docs: &[Line], matchers: &Matchers) -> Option<(usize, String)> {
+    let (i, line) = docs.iter().enumerate().find(|(_, line)| !line.text.trim().is_empty())?;
+    let caps = matchers.section_heading.captures(&line.text)?;
+    if &caps[1] == "#" {
+        Some((i, caps[2].to_owned()))
     } else {
         None
+    }
+}
+
+/// Whether a title is the name of the crate, ignoring case, backticks and `-` versus `_`
+fn is_crate_name(title: &str, crate_name: &str) -> bool {
+    let normalize = |name: &str| name.trim_matches('`').replace('_', "-").to_lowercase();
+    normalize(title) == normalize(crate_name)
+}
+
+/// Make sure the docs are not missing content, like after being moved by mistake
+fn check_content(
+    docs: &str,
+    min_lines: usize,
+    required_sections: &[String],
+    matchers: &Matchers,
+) -> Result<(), String> {
+    let lines = docs.lines().filter(|line| !line.trim().is_empty()).count();
+    if lines < min_lines {
+        return Err(format!(
+            "Docs are too short: found {} lines, at least {} are required",
+            lines, min_lines
+        ));
+    }
+
+    if required_sections.is_empty() {
+        return Ok(());
+    }
+
+    let document = sections::Document::parse(docs, matchers);
+    let missing: Vec<&str> = required_sections
+        .iter()
+        .filter(|required| {
+            !document
+                .sections
+                .iter()
+                .any(|section| section.title.trim().eq_ignore_ascii_case(required.trim()))
+        })
+        .map(|required| required.as_str())
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(format!("Docs are missing required sections: {}", missing.join(", ")));
+    }
+
+    Ok(())
+}
+
+/// Keep the summary of the docs, as set in `split`, and move the other sections out of it
+///
+/// The summary ends with a link to the file the other sections go to, unless there are none.
+fn split_docs(mut summary: sections::Document, split: &Split) -> (String, Option<SplitDocs>) {
+    let count = split.sections.unwrap_or(1);
+    let moved = summary.split_off(|i, section| {
+        if split.summary.is_empty() {
+            i < count
+        } else {
+            split
+                .summary
+                .iter()
+                .any(|title| title.trim().eq_ignore_ascii_case(section.title.trim()))
+        }
+    });
+
+    if moved.sections.is_empty() {
+        return (summary.render(), None);
+    }
+
+    let link = format!("[Read more]({})", split.file.replace(' ', "%20"));
+    let summary = summary.render();
+    let summary = if summary.is_empty() { link } else { format!("{}\n\n{}", summary, link) };
+    let split = SplitDocs {
+        file: split.file.clone(),
+        content: moved.render(),
     };
 
-    // get cargo info from Cargo.toml
-    let cargo = cargo_info::get_cargo_info(project_root)?;
-    if add_license && cargo.package.license.is_none() {
-        return Err("License not found in Cargo.toml".to_owned());
+    (summary, Some(split))
+}
+
+/// Surround the readme with the content of the files in `prepend` and `append`
+fn add_static_sections(
+    project_root: &Path,
+    readme: String,
+    config: &Config,
+    sandbox: bool,
+) -> Result<String, String> {
+    let mut sections = Vec::new();
+    for path in &config.prepend {
+        sections.push(read_section(project_root, path, sandbox)?);
+    }
+    sections.push(readme);
+    for path in &config.append {
+        sections.push(read_section(project_root, path, sandbox)?);
+    }
+
+    sections.retain(|section| !section.trim().is_empty());
+    Ok(sections.join("\n\n"))
+}
+
+/// Reference link definitions of `SHARED_LINKS_FILE`, if the crate or its workspace has one
+///
+/// In a sandbox, the file of a workspace whose root is above the project root is refused.
+fn shared_links(project_root: &Path, sandbox: bool) -> Result<BTreeMap<String, String>, String> {
+    let dirs = Some(project_root.to_path_buf())
+        .into_iter()
+        .chain(workspace::find_workspace_root(project_root));
+    let path = match dirs.map(|dir| dir.join(SHARED_LINKS_FILE)).find(|path| path.is_file()) {
+        Some(path) => project_path(project_root, &path.to_string_lossy(), sandbox)?,
+        None => return Ok(BTreeMap::new()),
+    };
+
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+    toml::from_str(&content)
+        .map_err(|e| format!("Invalid link definitions in '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Read a file to be added to the readme, relative to the project root
+fn read_section(project_root: &Path, path: &str, sandbox: bool) -> Result<String, String> {
+    let path = project_path(project_root, path, sandbox)?;
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+
+    Ok(content.trim_end().to_owned())
+}
+
+/// Path of a file given relative to the project root
+///
+/// In a sandbox, the file must be inside the project root once symbolic links are resolved.
+fn project_path(project_root: &Path, path: &str, sandbox: bool) -> Result<PathBuf, String> {
+    let joined = project_root.join(path);
+    if !sandbox {
+        return Ok(joined);
+    }
+
+    // a file that does not exist cannot be read anyway, let the caller report it
+    let resolved = match joined.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => return Ok(joined),
+    };
+    let root = project_root.canonicalize().map_err(|e| format!("{}", e))?;
+    if resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "'{}' is outside of the project root, which is not allowed with `--sandbox`",
+            path
+        ))
+    }
+}
+
+/// Delimiters of the template tags set in `template-delimiters`, or the default ones
+fn delimiters(config: &Config) -> Result<Delimiters, String> {
+    match config.template_delimiters {
+        Some((ref open, ref close)) if open.is_empty() || close.is_empty() => {
+            Err("The template delimiters cannot be empty".to_owned())
+        }
+        Some((ref open, ref close)) => Ok(Delimiters {
+            open: open.clone(),
+            close: close.clone(),
+        }),
+        None => Ok(Delimiters::default()),
     }
+}
 
-    template::render(template, readme, cargo, add_title, add_license)
+/// Current time, in seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0)
 }
 
 /// Load a template String from a file
 fn get_template_string<T: Read>(template: &mut T) -> Result<String, String> {
     let mut template_string = String::new();
-    match template.read_to_string(&mut template_string) {
-        Err(e) => return Err(format!("Error: {}", e)),
-        _ => {}
+    if let Err(e) = template.read_to_string(&mut template_string) {
+        return Err(format!("Error: {}", e));
     }
 
     Ok(template_string)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform_malformed_docs() {
+        let long_line = "`~>#- ".repeat(10_000);
+        let inputs = [
+            "```\n# hidden at the end",
+            "```\n#",
+            "> ```\n> # hidden\nout of the quote\n```",
+            "1.\n-\n   ```\n  # ended with the item",
+            "~~~é\n~~~~~\n````\n``` `x`",
+            "#\n######\n####### é\n\t#\t\n>\n> >\n>>>>>>",
+            &long_line,
+        ];
+
+        for input in &inputs {
+            transform_str(input, &Options::default());
+        }
+        assert_eq!("```rust", transform_str("```\n# hidden at the end", &Options::default()));
+    }
+}
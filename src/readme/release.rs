@@ -0,0 +1,170 @@
+//! Warn when the readme mentions a version of the crate that is not released yet
+//!
+//! Instructions like `cargo install my-crate --version 1.3.0` do not work until 1.3.0 is
+//! published. With the `warn-unreleased` setting, the version of `Cargo.toml` is compared with
+//! the latest release, which is the first version heading of the `CHANGELOG.md` of the crate,
+//! like `## [1.2.0] - 2024-05-01`, or without a changelog, the latest git tag. In a repository
+//! with several crates, only the tags naming the crate count, if some do, like `my-crate-v1.2.0`.
+//! There is a warning if the version of the package is newer and the readme mentions it.
+//!
+//! Git is only run when running commands is allowed.
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use regex::Regex;
+
+use super::Warning;
+
+const CHANGELOG: &str = "CHANGELOG.md";
+const REGEX_VERSION: &str = r"(\d+)\.(\d+)\.(\d+)(?:-([0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*))?";
+
+/// A version, with its pre-release identifiers
+#[derive(Debug, PartialEq)]
+struct Version {
+    numbers: (u64, u64, u64),
+    pre: Option<String>,
+}
+
+impl Version {
+    /// The first version in `text`
+    fn find(text: &str, re: &Regex) -> Option<Version> {
+        let caps = re.captures(text)?;
+        let number = |i: usize| caps[i].parse().ok();
+        Some(Version {
+            numbers: (number(1)?, number(2)?, number(3)?),
+            pre: caps.get(4).map(|pre| pre.as_str().to_owned()),
+        })
+    }
+
+    /// A pre-release comes before the release of the same numbers
+    fn cmp(&self, other: &Version) -> Ordering {
+        self.numbers.cmp(&other.numbers).then_with(|| match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a.cmp(b),
+        })
+    }
+}
+
+/// Warning if `readme` mentions `version`, the version of the package, and it is newer than the
+/// latest release
+pub fn unreleased_warning(
+    project_root: &Path,
+    name: &str,
+    version: &str,
+    readme: &str,
+    allow_exec: bool,
+) -> Option<Warning> {
+    let re = Regex::new(REGEX_VERSION).unwrap();
+    if !re.find_iter(readme).any(|found| found.as_str() == version) {
+        return None;
+    }
+
+    let current = Version::find(version, &re)?;
+    let (latest, source) = match changelog_release(project_root, &re) {
+        Some(latest) => (latest, CHANGELOG),
+        None if allow_exec => (tag_release(project_root, name, &re)?, "the git tags"),
+        None => return None,
+    };
+    if current.cmp(&latest) != Ordering::Greater {
+        return None;
+    }
+
+    Some(Warning::without_line(format!(
+        "The readme mentions version {}, which is not released yet according to {}, where the \
+         latest release is {}",
+        version,
+        source,
+        latest_text(&latest)
+    )))
+}
+
+fn latest_text(version: &Version) -> String {
+    let (major, minor, patch) = version.numbers;
+    match version.pre {
+        Some(ref pre) => format!("{}.{}.{}-{}", major, minor, patch, pre),
+        None => format!("{}.{}.{}", major, minor, patch),
+    }
+}
+
+/// Version of the first heading of the changelog that has one, `Unreleased` having none
+fn changelog_release(project_root: &Path, re: &Regex) -> Option<Version> {
+    let mut changelog = String::new();
+    File::open(project_root.join(CHANGELOG))
+        .and_then(|mut f| f.read_to_string(&mut changelog))
+        .ok()?;
+    changelog_version(&changelog, re)
+}
+
+fn changelog_version(changelog: &str, re: &Regex) -> Option<Version> {
+    changelog
+        .lines()
+        .filter(|line| line.starts_with('#'))
+        .filter_map(|line| Version::find(line, re))
+        .next()
+}
+
+/// Latest version of the git tags, the ones naming the crate if there are some
+fn tag_release(project_root: &Path, name: &str, re: &Regex) -> Option<Version> {
+    let output = Command::new("git")
+        .args(["tag", "--list"])
+        .current_dir(project_root)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    latest_tag(&String::from_utf8_lossy(&output.stdout), name, re)
+}
+
+fn latest_tag(tags: &str, name: &str, re: &Regex) -> Option<Version> {
+    let tags: Vec<&str> = tags.lines().map(|tag| tag.trim()).collect();
+    let named: Vec<&str> = tags.iter().cloned().filter(|tag| tag.contains(name)).collect();
+    let tags = if named.is_empty() { tags } else { named };
+    tags.iter()
+        .filter_map(|tag| Version::find(tag, re))
+        .max_by(|a, b| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_latest_release() {
+        let re = Regex::new(REGEX_VERSION).unwrap();
+        let changelog = concat_lines!(
+            "# Changelog",
+            "",
+            "## [Unreleased]",
+            "",
+            "- Something 3.0.0 will have",
+            "",
+            "## [1.2.0] - 2024-05-01",
+            "",
+            "## [1.1.0] - 2024-01-01",
+        );
+        assert_eq!(Version::find("1.2.0", &re), changelog_version(changelog, &re));
+
+        let tags = concat_lines!("v1.10.0", "v1.9.0", "v2.0.0-rc.1");
+        assert_eq!(Version::find("2.0.0-rc.1", &re), latest_tag(tags, "my-crate", &re));
+        let tags = concat_lines!("v3.0.0", "my-crate-v1.2.0", "my-crate-v1.1.0");
+        assert_eq!(Version::find("1.2.0", &re), latest_tag(tags, "my-crate", &re));
+    }
+
+    #[test]
+    fn pre_release_comes_before_release() {
+        let re = Regex::new(REGEX_VERSION).unwrap();
+        let version = |text| Version::find(text, &re).unwrap();
+        assert_eq!(Ordering::Less, version("2.0.0-rc.1").cmp(&version("2.0.0")));
+        assert_eq!(Ordering::Greater, version("1.10.0").cmp(&version("1.9.3")));
+        assert_eq!(Ordering::Equal, version("1.2.0").cmp(&version("v1.2.0")));
+    }
+}
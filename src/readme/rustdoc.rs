@@ -0,0 +1,284 @@
+//! Read the docs of the crate from the JSON output of rustdoc
+//!
+//! rustdoc, run with `--output-format json`, has already done what can only be guessed from the
+//! source: `#[doc]` attributes, `cfg_attr` and `include_str!` are resolved into the docs of the
+//! crate root, the public items are known with their path, and the intra-doc links are resolved
+//! to the items they point to. The links are then rewritten to the pages of the items, and the
+//! items are used to link the inline code naming them, see `items`.
+//!
+//! The ids of the items are numbers in recent versions of the format and strings in older ones,
+//! both are accepted.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::blocks::Fence;
+use super::extract::Line;
+use super::items::{item_url, page_kind, Item};
+use super::matchers::Matchers;
+
+/// A link, with its text and, unless it is a shortcut link, its destination
+pub const REGEX_LINK: &str = r"\[([^\[\]]+)\](\(([^()\s]+)\))?";
+/// Definition of a reference link
+pub const REGEX_LINK_DEFINITION: &str = r"^(\s*\[([^\[\]]+)\]:\s*)(\S+)(.*)$";
+
+/// What rustdoc knows about the crate
+pub struct RustdocJson {
+    /// Docs of the crate root
+    pub docs: String,
+    /// Public items of the crate
+    pub items: Vec<Item>,
+    /// Urls of the intra-doc links in the docs of the crate root, by the link as it is written
+    pub links: HashMap<String, String>,
+}
+
+impl RustdocJson {
+    /// Read the output of rustdoc from `file`, for the crate whose docs are at `root`
+    pub fn read(file: &Path, root: &str) -> Result<Self, String> {
+        let mut content = String::new();
+        File::open(file)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| format!("Could not read file '{}': {}", file.to_string_lossy(), e))?;
+
+        RustdocJson::parse(&content, root)
+            .map_err(|e| format!("Invalid rustdoc JSON in '{}': {}", file.to_string_lossy(), e))
+    }
+
+    fn parse(content: &str, root: &str) -> Result<Self, String> {
+        let json: Value = ::serde_json::from_str(content).map_err(|e| format!("{}", e))?;
+        let index = json
+            .get("index")
+            .and_then(|index| index.as_object())
+            .ok_or("no `index` found")?;
+        let paths = json
+            .get("paths")
+            .and_then(|paths| paths.as_object())
+            .ok_or("no `paths` found")?;
+        let crate_root = json
+            .get("root")
+            .and_then(id_key)
+            .and_then(|id| index.get(&id))
+            .ok_or("the crate root is not in `index`")?;
+
+        // url of the docs of each crate, this one being 0
+        let mut roots = HashMap::new();
+        roots.insert(0, root.to_owned());
+        if let Some(crates) = json.get("external_crates").and_then(|crates| crates.as_object()) {
+            for (id, external) in crates {
+                let name = external.get("name").and_then(|name| name.as_str()).unwrap_or("");
+                let url = match external.get("html_root_url").and_then(|url| url.as_str()) {
+                    Some(url) => url.trim_end_matches('/').to_owned(),
+                    None => format!("https://docs.rs/{}/latest", name),
+                };
+                if let Ok(id) = id.parse::<u64>() {
+                    roots.insert(id, url);
+                }
+            }
+        }
+
+        let mut items = Vec::new();
+        let mut urls = HashMap::new();
+        for (id, summary) in paths {
+            let crate_id = summary.get("crate_id").and_then(|id| id.as_u64());
+            let kind = summary.get("kind").and_then(|kind| kind.as_str()).and_then(page_kind);
+            let path: Vec<String> = summary
+                .get("path")
+                .and_then(|path| path.as_array())
+                .map(|path| path.iter().filter_map(|s| s.as_str()).map(|s| s.to_owned()).collect())
+                .unwrap_or_default();
+            let (crate_id, kind) = match (crate_id, kind) {
+                (Some(crate_id), Some(kind)) if path.len() > 1 => (crate_id, kind),
+                _ => continue,
+            };
+
+            if let Some(root) = roots.get(&crate_id) {
+                urls.insert(id.clone(), item_url(root, kind, &path));
+            }
+            let public = index
+                .get(id)
+                .and_then(|item| item.get("visibility"))
+                .is_none_or(|visibility| visibility == "public");
+            if crate_id == 0 && public {
+                items.push((kind, path));
+            }
+        }
+
+        // intra-doc links to items without a page, like the crate itself, stay unresolved
+        let links = crate_root
+            .get("links")
+            .and_then(|links| links.as_object())
+            .map(|links| {
+                links
+                    .iter()
+                    .filter_map(|(link, id)| {
+                        let url = id_key(id).and_then(|id| urls.get(&id))?;
+                        Some((link.clone(), url.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(RustdocJson {
+            docs: crate_root.get("docs").and_then(|docs| docs.as_str()).unwrap_or("").to_owned(),
+            items,
+            links,
+        })
+    }
+
+    /// Lines of the docs of the crate root
+    pub fn lines(&self) -> Vec<Line> {
+        self.docs.lines().enumerate().map(|(i, line)| Line::new(line, i + 1)).collect()
+    }
+}
+
+/// Id of an item, as used for the keys of the objects
+fn id_key(id: &Value) -> Option<String> {
+    match *id {
+        Value::String(ref id) => Some(id.clone()),
+        Value::Number(ref id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Replace the intra-doc links resolved by rustdoc with links to the pages of the items
+///
+/// Inline links, like `[text](path)`, shortcut links, like `` [`Item`] ``, and the definitions of
+/// reference links are rewritten, outside of code blocks.
+pub fn resolve_links(
+    lines: Vec<Line>,
+    links: &HashMap<String, String>,
+    matchers: &Matchers,
+) -> Vec<Line> {
+    let mut fence: Option<Fence> = None;
+    lines
+        .into_iter()
+        .map(|mut line| {
+            if let Some(ref open) = fence {
+                if open.is_closed_by(&line.text) {
+                    fence = None;
+                }
+                return line;
+            }
+            fence = Fence::open(&line.text);
+            if fence.is_none() {
+                line.text = resolve_line(&line.text, links, matchers);
+            }
+            line
+        })
+        .collect()
+}
+
+fn resolve_line(line: &str, links: &HashMap<String, String>, matchers: &Matchers) -> String {
+    let url = |link: &str| links.get(link).or_else(|| links.get(link.trim_matches('`')));
+
+    if let Some(caps) = matchers.link_definition.captures(line) {
+        return match url(&caps[2]) {
+            Some(url) => format!("{}{}{}", &caps[1], url, &caps[4]),
+            None => line.to_owned(),
+        };
+    }
+
+    let mut result = String::new();
+    let mut last = 0;
+    for caps in matchers.link.captures_iter(line) {
+        let link = caps.get(0).unwrap();
+        let rest = &line[link.end()..];
+        let resolved = match caps.get(3) {
+            Some(dest) => url(dest.as_str()).map(|url| format!("[{}]({})", &caps[1], url)),
+            // a reference link, `[text][label]`, is resolved by its definition
+            None if rest.starts_with('[') || rest.starts_with(':') => None,
+            None if line[..link.start()].ends_with(']') => None,
+            None => url(&caps[1]).map(|url| format!("[{}]({})", &caps[1], url)),
+        };
+        if let Some(resolved) = resolved {
+            result.push_str(&line[last..link.start()]);
+            result.push_str(&resolved);
+            last = link.end();
+        }
+    }
+    result.push_str(&line[last..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT: &str = "https://docs.rs/my-crate/1.0.0";
+
+    const JSON: &str = r#"{
+        "root": 0,
+        "index": {
+            "0": {
+                "docs": "Start with [`Client`], see [run](crate::run) and [std][`String`].\n\n[`String`]: String",
+                "links": { "`Client`": 1, "crate::run": 4, "String": 3, "crate": 0 }
+            },
+            "1": { "visibility": "public" },
+            "2": { "visibility": "crate" }
+        },
+        "paths": {
+            "0": { "crate_id": 0, "path": ["my_crate"], "kind": "module" },
+            "1": { "crate_id": 0, "path": ["my_crate", "client", "Client"], "kind": "struct" },
+            "2": { "crate_id": 0, "path": ["my_crate", "Private"], "kind": "struct" },
+            "3": { "crate_id": 1, "path": ["std", "string", "String"], "kind": "struct" },
+            "4": { "crate_id": 0, "path": ["my_crate", "run"], "kind": "function" }
+        },
+        "external_crates": {
+            "1": { "name": "std", "html_root_url": "https://doc.rust-lang.org/nightly/" }
+        }
+    }"#;
+
+    fn path(segments: &[&str]) -> Vec<String> {
+        segments.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_rustdoc_json() {
+        let mut rustdoc = RustdocJson::parse(JSON, ROOT).unwrap();
+        rustdoc.items.sort();
+        let expected = vec![
+            ("fn", path(&["my_crate", "run"])),
+            ("struct", path(&["my_crate", "client", "Client"])),
+        ];
+        assert_eq!(expected, rustdoc.items);
+        assert_eq!(3, rustdoc.links.len());
+        assert_eq!(
+            Some("https://doc.rust-lang.org/nightly/std/string/struct.String.html"),
+            rustdoc.links.get("String").map(|url| url.as_str())
+        );
+        assert!(RustdocJson::parse("{}", ROOT).is_err());
+    }
+
+    #[test]
+    fn resolve_intra_doc_links() {
+        let rustdoc = RustdocJson::parse(JSON, ROOT).unwrap();
+        let mut lines = rustdoc.lines();
+        lines.extend(vec![
+            Line::new("```", 4),
+            Line::new("[`Client`]", 5),
+            Line::new("```", 6),
+        ]);
+        let resolved: Vec<String> = resolve_links(lines, &rustdoc.links, &Matchers::new())
+            .into_iter()
+            .map(|line| line.text)
+            .collect();
+
+        let expected = vec![
+            concat!(
+                "Start with [`Client`](https://docs.rs/my-crate/1.0.0/my_crate/client/struct.Client.html), ",
+                "see [run](https://docs.rs/my-crate/1.0.0/my_crate/fn.run.html) and [std][`String`].",
+            ),
+            "",
+            "[`String`]: https://doc.rust-lang.org/nightly/std/string/struct.String.html",
+            "```",
+            "[`Client`]",
+            "```",
+        ];
+        assert_eq!(expected, resolved);
+    }
+}
@@ -0,0 +1,356 @@
+//! Split the docs into sections, to rearrange them as a whole
+//!
+//! A section starts at a heading of the top level, the lowest level found in the docs, and runs
+//! until the next one. Deeper headings belong to the section they are in. Text before the first
+//! heading is kept apart as the preamble. Lines inside code blocks are never headings.
+//!
+//! Sections titled "Rustdoc-only", at any level, are meant for docs.rs and are removed along
+//! with their subsections.
+//!
+//! Headings can be numbered after their position in the sections, like `1.` and `1.2.`, once
+//! the sections are in their final order.
+
+use std::iter;
+use std::slice;
+
+use super::extract::Line;
+use super::blocks::Fence;
+use super::matchers::Matchers;
+
+pub const REGEX_HEADING: &str = r"^(#{1,6})\s+(.*?)\s*#*\s*$";
+const RUSTDOC_ONLY: &str = "rustdoc-only";
+
+/// The docs, split into sections
+#[derive(Debug, PartialEq)]
+pub struct Document {
+    pub preamble: Vec<String>,
+    pub sections: Vec<Section>,
+}
+
+/// A top level heading and the lines following it
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    /// Text of the heading, without the `#`s
+    pub title: String,
+    /// The heading line, as written
+    pub heading: String,
+    pub body: Vec<String>,
+}
+
+impl Document {
+    pub fn parse(text: &str, matchers: &Matchers) -> Self {
+        let headings = headings(text.lines(), matchers);
+        let top_level = headings.iter().filter_map(|h| h.as_ref().map(|h| h.0)).min();
+
+        let mut document = Document {
+            preamble: Vec::new(),
+            sections: Vec::new(),
+        };
+
+        for (line, heading) in text.lines().zip(headings) {
+            match heading {
+                Some((level, title)) if Some(level) == top_level => {
+                    document.sections.push(Section {
+                        title,
+                        heading: line.to_owned(),
+                        body: Vec::new(),
+                    });
+                }
+                _ => match document.sections.last_mut() {
+                    Some(section) => section.body.push(line.to_owned()),
+                    None => document.preamble.push(line.to_owned()),
+                },
+            }
+        }
+
+        document
+    }
+
+    /// Move the sections with the given titles to the top, in that order
+    ///
+    /// Titles are compared ignoring case. Sections that are not listed keep their relative order
+    /// after the listed ones.
+    pub fn reorder(&mut self, order: &[String]) {
+        let rank = |section: &Section| {
+            order
+                .iter()
+                .position(|title| title.trim().eq_ignore_ascii_case(section.title.trim()))
+                .unwrap_or(order.len())
+        };
+
+        // stable sort keeps the original order among equal ranks
+        self.sections.sort_by_key(|section| rank(section));
+    }
+
+    /// Move the sections for which `keep`, given their position, is false to a new document
+    pub fn split_off<F: FnMut(usize, &Section) -> bool>(&mut self, mut keep: F) -> Document {
+        let mut moved = Document {
+            preamble: Vec::new(),
+            sections: Vec::new(),
+        };
+        let mut kept = Vec::new();
+        for (i, section) in self.sections.drain(..).enumerate() {
+            if keep(i, &section) {
+                kept.push(section);
+            } else {
+                moved.sections.push(section);
+            }
+        }
+        self.sections = kept;
+
+        moved
+    }
+
+    /// Number the headings, `1.` for the top level, `1.1.` for the level below and so on
+    ///
+    /// The titles of the sections stay as they are. A heading deeper than the one before it by
+    /// more than a level gets a `0` for the levels in between.
+    pub fn number_headings(&mut self, matchers: &Matchers) {
+        let top_level = match self.sections.first() {
+            Some(section) => match headings(iter::once(section.heading.as_str()), matchers).pop() {
+                Some(Some((level, _))) => level,
+                _ => return,
+            },
+            None => return,
+        };
+
+        let mut counters = Vec::new();
+        number_lines(&mut self.preamble, top_level, &mut counters, matchers);
+        for section in &mut self.sections {
+            number_lines(slice::from_mut(&mut section.heading), top_level, &mut counters, matchers);
+            number_lines(&mut section.body, top_level, &mut counters, matchers);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines: Vec<&str> = self.preamble.iter().map(|line| line.as_str()).collect();
+        for section in &self.sections {
+            // keep a blank line between sections that were not separated before being moved
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push("");
+            }
+            lines.push(&section.heading);
+            lines.extend(section.body.iter().map(|line| line.as_str()));
+        }
+
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Remove the sections titled "Rustdoc-only", with everything up to the next heading of the same
+/// or a higher level
+///
+/// This is done on the extracted docs, before they are transformed, so problems in the removed
+/// sections are not reported.
+pub fn remove_rustdoc_only(lines: Vec<Line>, matchers: &Matchers) -> Vec<Line> {
+    let headings = headings(lines.iter().map(|line| line.text.as_str()), matchers);
+    let mut removing: Option<usize> = None;
+    lines
+        .into_iter()
+        .zip(headings)
+        .filter(|(_, heading)| {
+            if let Some((level, title)) = heading {
+                let level = *level;
+                if removing.is_some_and(|removed_level| level <= removed_level) {
+                    removing = None;
+                }
+                if removing.is_none() && title.eq_ignore_ascii_case(RUSTDOC_ONLY) {
+                    removing = Some(level);
+                }
+            }
+            removing.is_none()
+        })
+        .map(|(line, _)| line)
+        .collect()
+}
+
+/// Number the headings of `lines`, continuing from `counters`, the numbers of the last heading
+fn number_lines(
+    lines: &mut [String],
+    top_level: usize,
+    counters: &mut Vec<usize>,
+    matchers: &Matchers,
+) {
+    let headings = headings(lines.iter().map(|line| line.as_str()), matchers);
+    for (line, heading) in lines.iter_mut().zip(headings) {
+        if let Some((level, title)) = heading {
+            let depth = level - top_level + 1;
+            counters.resize(depth, 0);
+            counters[depth - 1] += 1;
+            let number: String = counters.iter().map(|n| format!("{}.", n)).collect();
+            *line = format!("{} {} {}", "#".repeat(level), number, title);
+        }
+    }
+}
+
+/// Level and text of the lines that are headings, outside of code blocks
+pub fn headings<'a, I: Iterator<Item = &'a str>>(
+    lines: I,
+    matchers: &Matchers,
+) -> Vec<Option<(usize, String)>> {
+    let mut fence: Option<Fence> = None;
+    lines
+        .map(|line| {
+            if let Some(ref open) = fence {
+                if open.is_closed_by(line) {
+                    fence = None;
+                }
+                return None;
+            }
+            fence = Fence::open(line);
+            if fence.is_some() {
+                return None;
+            }
+            matchers
+                .section_heading
+                .captures(line)
+                .map(|caps| (caps[1].len(), caps[2].to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = concat_lines!(
+        "Intro",
+        "",
+        "## Examples",
+        "",
+        "```rust",
+        "## not a heading",
+        "```",
+        "",
+        "### Details",
+        "",
+        "## Installation",
+        "",
+        "cargo add",
+        "",
+        "## License",
+        "",
+        "MIT",
+    );
+
+    #[test]
+    fn parse_top_level_sections() {
+        let document = Document::parse(INPUT, &Matchers::new());
+        let titles: Vec<&str> = document.sections.iter().map(|s| s.title.as_str()).collect();
+
+        assert_eq!(vec!["Intro", ""], document.preamble);
+        assert_eq!(vec!["Examples", "Installation", "License"], titles);
+    }
+
+    #[test]
+    fn reorder_sections() {
+        let mut document = Document::parse(INPUT, &Matchers::new());
+        document.reorder(&["installation".to_owned(), "Missing".to_owned()]);
+
+        let expected = concat!(
+            "Intro\n",
+            "\n",
+            "## Installation\n",
+            "\n",
+            "cargo add\n",
+            "\n",
+            "## Examples\n",
+            "\n",
+            "```rust\n",
+            "## not a heading\n",
+            "```\n",
+            "\n",
+            "### Details\n",
+            "\n",
+            "## License\n",
+            "\n",
+            "MIT",
+        );
+        assert_eq!(expected, document.render());
+    }
+
+    #[test]
+    fn split_off_sections() {
+        let mut document = Document::parse(INPUT, &Matchers::new());
+        let moved = document.split_off(|i, section| i == 0 || section.title == "License");
+
+        let kept: Vec<&str> = document.sections.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(vec!["Examples", "License"], kept);
+        assert_eq!(vec!["Intro", ""], document.preamble);
+        assert_eq!("## Installation\n\ncargo add", moved.render());
+    }
+
+    #[test]
+    fn number_all_headings() {
+        let input = concat!(
+            "Intro\n",
+            "### Before\n",
+            "\n",
+            "## Usage ##\n",
+            "```\n",
+            "## not a heading\n",
+            "```\n",
+            "### Details\n",
+            "#### More\n",
+            "### Errors\n",
+            "\n",
+            "## License\n",
+            "##### Deep",
+        );
+        let expected = concat!(
+            "Intro\n",
+            "### 0.1. Before\n",
+            "\n",
+            "## 1. Usage\n",
+            "```\n",
+            "## not a heading\n",
+            "```\n",
+            "### 1.1. Details\n",
+            "#### 1.1.1. More\n",
+            "### 1.2. Errors\n",
+            "\n",
+            "## 2. License\n",
+            "##### 2.0.0.1. Deep",
+        );
+
+        let mut document = Document::parse(input, &Matchers::new());
+        document.number_headings(&Matchers::new());
+        let titles: Vec<&str> = document.sections.iter().map(|s| s.title.as_str()).collect();
+
+        assert_eq!(expected, document.render());
+        assert_eq!(vec!["Usage", "License"], titles);
+    }
+
+    #[test]
+    fn remove_rustdoc_only_sections() {
+        let input = concat_lines!(
+            "Intro",
+            "## Usage",
+            "### Rustdoc-only",
+            "See [`Client`](crate::Client).",
+            "#### Methods",
+            "### Errors",
+            "## Rustdoc-only",
+            "```",
+            "# Rustdoc-only",
+            "```",
+        );
+        let input = input.lines().enumerate().map(|(i, x)| Line::new(x, i + 1)).collect();
+        let expected = vec![
+            Line::new("Intro", 1),
+            Line::new("## Usage", 2),
+            Line::new("### Errors", 6),
+        ];
+        assert_eq!(expected, remove_rustdoc_only(input, &Matchers::new()));
+    }
+
+    #[test]
+    fn render_unchanged() {
+        let document = Document::parse(INPUT, &Matchers::new());
+        assert_eq!(INPUT.trim_end(), document.render());
+    }
+}
@@ -0,0 +1,139 @@
+//! Measure the crate docs, for doc coverage dashboards
+//!
+//! The stats are those of the doc comments as written, before any transformation. Examples are
+//! the rust code blocks, the ones rustdoc runs as doc tests, and words are counted outside of
+//! code blocks.
+
+use std::io::Read;
+
+use super::blocks::{Block, Blocks};
+use super::extract;
+use super::matchers::Matchers;
+use super::sections::headings;
+
+/// Words read in a minute, to estimate the reading time
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Size of the crate docs
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct DocStats {
+    /// Doc comment lines, blank ones included
+    pub lines: usize,
+    /// Headings, at any level
+    pub sections: usize,
+    /// Rust code blocks
+    pub examples: usize,
+    /// Rust code blocks marked `ignore`
+    pub ignored_examples: usize,
+    /// Rust code blocks marked `no_run`
+    pub no_run_examples: usize,
+    /// Words outside of code blocks
+    pub words: usize,
+    /// Words before the first heading
+    pub intro_words: usize,
+}
+
+impl DocStats {
+    /// Estimated time to read the docs, in minutes, rounded up
+    pub fn reading_minutes(&self) -> usize {
+        self.words.div_ceil(WORDS_PER_MINUTE)
+    }
+}
+
+/// Stats of the crate docs in `source`
+pub fn doc_stats<R: Read>(source: R) -> Result<DocStats, String> {
+    let docs = extract::extract_docs(source).map_err(|e| format!("{}", e))?;
+    let matchers = Matchers::new();
+
+    let headings = headings(docs.iter().map(|line| line.text.as_str()), &matchers);
+    let mut stats = DocStats {
+        lines: docs.len(),
+        sections: headings.iter().filter(|heading| heading.is_some()).count(),
+        ..DocStats::default()
+    };
+
+    let mut blocks = Blocks::new(&matchers);
+    let mut intro = true;
+    for (line, heading) in docs.iter().zip(&headings) {
+        intro = intro && heading.is_none();
+        match blocks.parse(&line.text).2 {
+            Block::Open(ref fence) if matchers.code_rust.is_match(&fence.info) => {
+                stats.examples += 1;
+                let attributes: Vec<&str> = fence.info.split(',').map(|a| a.trim()).collect();
+                if attributes.contains(&"ignore") {
+                    stats.ignored_examples += 1;
+                }
+                if attributes.contains(&"no_run") {
+                    stats.no_run_examples += 1;
+                }
+            }
+            Block::Text => {
+                let words = line
+                    .text
+                    .split_whitespace()
+                    .filter(|word| word.chars().any(|c| c.is_alphanumeric()))
+                    .count();
+                stats.words += words;
+                if intro {
+                    stats.intro_words += words;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_docs() {
+        let source = concat_lines!(
+            "//! Parse things",
+            "//!",
+            "//! # Examples",
+            "//!",
+            "//! ```",
+            "//! let words = not_counted();",
+            "//! ```",
+            "//!",
+            "//! ```no_run",
+            "//! server::start();",
+            "//! ```",
+            "//!",
+            "//! ```rust,ignore",
+            "//! broken();",
+            "//! ```",
+            "//!",
+            "//! ```text",
+            "//! output",
+            "//! ```",
+            "//!",
+            "//! ## Details - more",
+            "",
+            "fn main() {}",
+        );
+        let expected = DocStats {
+            lines: 21,
+            sections: 2,
+            examples: 3,
+            ignored_examples: 1,
+            no_run_examples: 1,
+            words: 5,
+            intro_words: 2,
+        };
+        assert_eq!(Ok(expected), doc_stats(source.as_bytes()));
+    }
+
+    #[test]
+    fn reading_time() {
+        let stats = |words| DocStats { words, ..DocStats::default() };
+        assert_eq!(0, stats(0).reading_minutes());
+        assert_eq!(1, stats(1).reading_minutes());
+        assert_eq!(1, stats(200).reading_minutes());
+        assert_eq!(2, stats(201).reading_minutes());
+    }
+}
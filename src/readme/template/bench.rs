@@ -0,0 +1,132 @@
+//! Render the `{{include-bench "target/criterion"}}` tag
+//!
+//! Summarizes the results of Criterion benchmarks as a markdown table, so performance numbers in
+//! the readme come from the last benchmark run. Criterion stores each benchmark result in a
+//! `new` directory, containing `benchmark.json` with its id and `estimates.json` with the
+//! measurements, in nanoseconds.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use super::Context;
+use super::parser::Tag;
+
+#[derive(Deserialize)]
+struct Benchmark {
+    full_id: String,
+}
+
+#[derive(Deserialize)]
+struct Estimates {
+    mean: Estimate,
+    median: Estimate,
+    std_dev: Estimate,
+}
+
+#[derive(Deserialize)]
+struct Estimate {
+    point_estimate: f64,
+}
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    let dir = tag
+        .arg(0)
+        .ok_or_else(|| format!("Missing criterion directory in `{}`", tag.source))?;
+    let dir = context.path(dir)?;
+
+    let mut results = Vec::new();
+    for result_dir in find_results(&dir)? {
+        let benchmark: Benchmark = read_json(&result_dir.join("benchmark.json"))?;
+        let estimates: Estimates = read_json(&result_dir.join("estimates.json"))?;
+        results.push((benchmark.full_id, estimates));
+    }
+
+    if results.is_empty() {
+        return Err(format!(
+            "No benchmark results found in '{}', run `cargo bench` first",
+            dir.to_string_lossy()
+        ));
+    }
+
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut lines = vec![
+        "| Benchmark | Mean | Median | Std. dev. |".to_owned(),
+        "|-----------|------|--------|-----------|".to_owned(),
+    ];
+    for (id, estimates) in results {
+        lines.push(format!(
+            "| {} | {} | {} | {} |",
+            id,
+            format_time(estimates.mean.point_estimate),
+            format_time(estimates.median.point_estimate),
+            format_time(estimates.std_dev.point_estimate)
+        ));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Find the directories holding the latest result of each benchmark
+fn find_results(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Could not read directory '{}': {}", dir.to_string_lossy(), e))?;
+
+    let mut results = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| format!("{}", e))?.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        if path.file_name().is_some_and(|name| name == "new") {
+            if path.join("benchmark.json").is_file() {
+                results.push(path);
+            }
+        } else if path.file_name().is_some_and(|name| name != "report") {
+            results.extend(find_results(&path)?);
+        }
+    }
+
+    Ok(results)
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let mut json = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut json))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Could not parse '{}': {}", path.to_string_lossy(), e))
+}
+
+/// Format a duration in nanoseconds using the most readable unit
+fn format_time(ns: f64) -> String {
+    let units = [("ns", 1.0), ("µs", 1e3), ("ms", 1e6), ("s", 1e9)];
+    let &(unit, scale) = units
+        .iter()
+        .rev()
+        .find(|&&(_, scale)| ns >= scale)
+        .unwrap_or(&units[0]);
+
+    format!("{:.2} {}", ns / scale, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_time;
+
+    #[test]
+    fn format_time_units() {
+        assert_eq!(format_time(0.5), "0.50 ns");
+        assert_eq!(format_time(12.345), "12.35 ns");
+        assert_eq!(format_time(1_500.0), "1.50 µs");
+        assert_eq!(format_time(2_000_000.0), "2.00 ms");
+        assert_eq!(format_time(3_250_000_000.0), "3.25 s");
+    }
+}
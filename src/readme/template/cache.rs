@@ -0,0 +1,80 @@
+//! Cache the data that tags fetch over the network, like the crate as published to crates.io
+//!
+//! The data is cached for an hour in `target/cargo-readme/`, at the root of the workspace, so
+//! regenerating the readme does not query the service every time. When the service cannot be
+//! reached, an older cache is used instead, with a warning.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json;
+
+use workspace;
+
+use super::super::Warning;
+use super::Context;
+
+const CACHE_DIR: &str = "target/cargo-readme";
+const CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Data of the crate fetched from `service`, cached in the directory `name`, from the cache if it
+/// is recent enough or the service cannot be reached
+pub fn cached<T, F>(context: &mut Context, name: &str, service: &str, fetch: F) -> Result<T, String>
+where
+    T: DeserializeOwned + Serialize,
+    F: FnOnce(&str) -> Result<T, String>,
+{
+    let krate = &context.cargo.package.name;
+    let root = workspace::find_workspace_root(context.project_root)
+        .unwrap_or_else(|| context.project_root.to_path_buf());
+    let cache = root.join(CACHE_DIR).join(name).join(format!("{}.json", krate));
+
+    let cached = read_cache(&cache);
+    let cached = match cached {
+        Some((data, age)) if age < CACHE_MAX_AGE => return Ok(data),
+        cached => cached.map(|(data, _)| data),
+    };
+
+    match fetch(krate) {
+        Ok(data) => {
+            // the cache only saves requests, the readme does not need it
+            let _ = write_cache(&cache, &data);
+            Ok(data)
+        }
+        Err(e) => {
+            let data = cached.ok_or(e.clone())?;
+            let warning = format!(
+                "{}, using the data of {} cached in '{}'",
+                e,
+                service,
+                cache.to_string_lossy()
+            );
+            if !context.warnings.iter().any(|w| w.message == warning) {
+                context.warnings.push(Warning::without_line(warning));
+            }
+            Ok(data)
+        }
+    }
+}
+
+/// Cached data, with its age
+fn read_cache<T: DeserializeOwned>(path: &Path) -> Option<(T, Duration)> {
+    let age = fs::metadata(path).ok()?.modified().ok()?.elapsed().unwrap_or_default();
+    let mut content = String::new();
+    File::open(path).ok()?.read_to_string(&mut content).ok()?;
+    Some((serde_json::from_str(&content).ok()?, age))
+}
+
+fn write_cache<T: Serialize>(path: &Path, data: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}", e))?;
+    }
+    let json = serde_json::to_string(data).map_err(|e| format!("{}", e))?;
+    File::create(path)
+        .and_then(|mut file| file.write_all(json.as_bytes()))
+        .map_err(|e| format!("{}", e))
+}
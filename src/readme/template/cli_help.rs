@@ -0,0 +1,113 @@
+//! Render the `{{cli-help}}` tag
+//!
+//! Runs the crate binary with `--help` and embeds the output in a text code block, so the usage
+//! shown in the readme is always the current one. A different command can be given as argument:
+//! `{{cli-help "target/debug/my-tool help subcommand"}}`.
+//!
+//! The command runs from an empty temporary directory, so it cannot read or write files relative
+//! to the project, and it is never run if command execution is disabled.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use tempdir::TempDir;
+
+use super::Context;
+use super::parser::Tag;
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    if !context.allow_exec {
+        return Err(format!(
+            "`{}` needs to run a command, but running commands was disabled with `--no-exec` or \
+             `--sandbox`",
+            tag.source
+        ));
+    }
+
+    let (program, args) = match tag.arg(0) {
+        Some(command) => {
+            let mut parts = command.split_whitespace().map(|part| part.to_owned());
+            let program = parts
+                .next()
+                .ok_or_else(|| format!("Empty command given to `{}`", tag.source))?;
+            (resolve_program(context.project_root, &program), parts.collect())
+        }
+        None => (find_binary(context)?, vec!["--help".to_owned()]),
+    };
+
+    let sandbox = TempDir::new("cargo-readme")
+        .map_err(|e| format!("Could not create directory to run `{}`: {}", tag.source, e))?;
+
+    let output = Command::new(&program)
+        .args(&args)
+        .current_dir(sandbox.path())
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run '{}': {}", program.to_string_lossy(), e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "'{}' failed with {}: {}",
+            program.to_string_lossy(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // some programs print their help to stderr
+    let help = if output.stdout.is_empty() {
+        output.stderr
+    } else {
+        output.stdout
+    };
+
+    Ok(format!(
+        "```text\n{}\n```",
+        String::from_utf8_lossy(&help).trim_end()
+    ))
+}
+
+/// Programs given as a path are relative to the project root, others are looked up in `PATH`
+fn resolve_program(project_root: &Path, program: &str) -> PathBuf {
+    if program.contains('/') || program.contains('\\') {
+        project_root.join(program)
+    } else {
+        PathBuf::from(program)
+    }
+}
+
+/// Find the built binary of the crate in the target directory, preferring release builds
+fn find_binary(context: &Context) -> Result<PathBuf, String> {
+    let cargo = context.cargo;
+    let name = match cargo.bin {
+        Some(ref bins) if bins.len() == 1 => bins[0].name.clone(),
+        Some(ref bins) if bins.len() > 1 => {
+            return Err(
+                "Multiple binaries found, pass the command to run: `{{cli-help \"...\"}}`"
+                    .to_owned(),
+            )
+        }
+        _ => None,
+    };
+    let name = name.unwrap_or_else(|| cargo.package.name.clone());
+
+    let target_dir = match env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => context.project_root.join(dir),
+        None => context.project_root.join("target"),
+    };
+
+    let file_name = format!("{}{}", name, env::consts::EXE_SUFFIX);
+    ["release", "debug"]
+        .iter()
+        .map(|profile| target_dir.join(profile).join(&file_name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            format!(
+                "Could not find binary '{}' in '{}', build it first or pass the command to run: \
+                 `{{{{cli-help \"...\"}}}}`",
+                file_name,
+                target_dir.to_string_lossy()
+            )
+        })
+}
@@ -0,0 +1,198 @@
+//! Render the `{{cli-reference "cli.json"}}` tag
+//!
+//! Builds the Commands and Options sections of a command line tool from a json description of
+//! its interface, for when running the binary while generating the readme is not possible. The
+//! file is expected to look like this, which is easy to dump from `clap` or similar libraries:
+//!
+//! ```json
+//! {
+//!   "name": "my-tool",
+//!   "args": [
+//!     { "short": "o", "long": "output", "value_name": "FILE", "help": "Where to write" },
+//!     { "name": "INPUT", "help": "File to read" }
+//!   ],
+//!   "subcommands": [
+//!     { "name": "init", "about": "Create a new project", "args": [], "subcommands": [] }
+//!   ]
+//! }
+//! ```
+//!
+//! Named arguments:
+//! - `command="init"` describe a subcommand instead of the top level command
+//! - `level=3` heading level of the sections
+
+use std::fs::File;
+use std::io::Read;
+
+use serde_json;
+
+use super::Context;
+use super::parser::Tag;
+
+#[derive(Deserialize)]
+struct Command {
+    name: String,
+    #[serde(default)]
+    about: Option<String>,
+    #[serde(default)]
+    args: Vec<Argument>,
+    #[serde(default)]
+    subcommands: Vec<Command>,
+}
+
+#[derive(Deserialize)]
+struct Argument {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    short: Option<String>,
+    #[serde(default)]
+    long: Option<String>,
+    #[serde(default)]
+    value_name: Option<String>,
+    #[serde(default)]
+    help: Option<String>,
+}
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    let path = tag
+        .arg(0)
+        .ok_or_else(|| format!("Missing json file in `{}`", tag.source))?;
+    let path = context.path(path)?;
+
+    let mut json = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut json))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+
+    let root: Command = serde_json::from_str(&json)
+        .map_err(|e| format!("Could not parse '{}': {}", path.to_string_lossy(), e))?;
+
+    let command = match tag.named_arg("command") {
+        Some(name) => find_command(&root, name)
+            .ok_or_else(|| format!("Command '{}' not found in '{}'", name, path.to_string_lossy()))?,
+        None => &root,
+    };
+
+    let level = match tag.named_arg("level") {
+        Some(level) => level
+            .parse::<usize>()
+            .ok()
+            .filter(|level| (1..=6).contains(level))
+            .ok_or_else(|| format!("Invalid heading level in `{}`", tag.source))?,
+        None => 3,
+    };
+
+    Ok(render_command(command, level))
+}
+
+/// Find a subcommand by its name, or by its path like "remote add"
+fn find_command<'a>(root: &'a Command, name: &str) -> Option<&'a Command> {
+    name.split_whitespace().try_fold(root, |command, name| {
+        command.subcommands.iter().find(|sub| sub.name == name)
+    })
+}
+
+fn render_command(command: &Command, level: usize) -> String {
+    let heading = "#".repeat(level);
+    let mut sections = Vec::new();
+
+    if !command.subcommands.is_empty() {
+        let mut lines = vec![format!("{} Commands", heading), String::new()];
+        push_commands(&mut lines, command, "");
+        sections.push(lines.join("\n"));
+    }
+
+    if !command.args.is_empty() {
+        let mut lines = vec![format!("{} Options", heading), String::new()];
+        for arg in &command.args {
+            let help = arg.help.as_ref().map(|help| help.trim()).unwrap_or("");
+            if help.is_empty() {
+                lines.push(format!("- `{}`", usage(arg)));
+            } else {
+                lines.push(format!("- `{}`: {}", usage(arg), help));
+            }
+        }
+        sections.push(lines.join("\n"));
+    }
+
+    sections.join("\n\n")
+}
+
+/// List subcommands, nested ones prefixed by their parents
+fn push_commands(lines: &mut Vec<String>, command: &Command, prefix: &str) {
+    for sub in &command.subcommands {
+        let name = format!("{}{}", prefix, sub.name);
+        match sub.about {
+            Some(ref about) if !about.trim().is_empty() => {
+                lines.push(format!("- `{}`: {}", name, about.trim()))
+            }
+            _ => lines.push(format!("- `{}`", name)),
+        }
+        push_commands(lines, sub, &format!("{} ", name));
+    }
+}
+
+/// Usage of an argument, like `-o, --output <FILE>` or `<INPUT>`
+fn usage(arg: &Argument) -> String {
+    let mut flags = Vec::new();
+    if let Some(ref short) = arg.short {
+        flags.push(format!("-{}", short));
+    }
+    if let Some(ref long) = arg.long {
+        flags.push(format!("--{}", long));
+    }
+
+    let value = arg.value_name.as_ref().or(arg.name.as_ref());
+    match value {
+        Some(value) if flags.is_empty() => format!("<{}>", value),
+        Some(value) if arg.value_name.is_some() => format!("{} <{}>", flags.join(", "), value),
+        _ => flags.join(", "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLI_JSON: &str = r#"{
+        "name": "tool",
+        "args": [
+            { "short": "o", "long": "output", "value_name": "FILE", "help": "Where to write" },
+            { "long": "verbose" },
+            { "name": "INPUT", "help": "File to read" }
+        ],
+        "subcommands": [
+            { "name": "remote", "about": "Manage remotes", "subcommands": [
+                { "name": "add", "about": "Add a remote", "args": [{ "name": "URL" }] }
+            ] }
+        ]
+    }"#;
+
+    #[test]
+    fn render_commands_and_options() {
+        let root: Command = serde_json::from_str(CLI_JSON).unwrap();
+        let expected = concat_lines!(
+            "### Commands",
+            "",
+            "- `remote`: Manage remotes",
+            "- `remote add`: Add a remote",
+            "",
+            "### Options",
+            "",
+            "- `-o, --output <FILE>`: Where to write",
+            "- `--verbose`",
+            "- `<INPUT>`: File to read",
+        );
+
+        assert_eq!(render_command(&root, 3), expected.trim_end());
+    }
+
+    #[test]
+    fn render_subcommand() {
+        let root: Command = serde_json::from_str(CLI_JSON).unwrap();
+        let command = find_command(&root, "remote add").unwrap();
+
+        assert_eq!(render_command(command, 2), "## Options\n\n- `<URL>`");
+    }
+}
@@ -0,0 +1,123 @@
+//! Render the `{{code "path"}}` tag
+//!
+//! Embeds a file, or some of its lines, as a fenced code block, so examples like configuration
+//! files can be maintained in the repository instead of copied in the readme.
+//!
+//! Named arguments:
+//! - `lang="toml"` language of the code block, guessed from the file extension by default
+//! - `lines="1-40"` lines to include, starting at 1; either end of the range can be left out
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::Context;
+use super::parser::Tag;
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    let file = tag
+        .arg(0)
+        .ok_or_else(|| format!("Missing file in `{}`", tag.source))?;
+
+    let path = context.path(file)?;
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+
+    let lines: Vec<&str> = content.lines().collect();
+    let (start, end) = match tag.named_arg("lines") {
+        Some(range) => parse_range(range, lines.len())
+            .ok_or_else(|| format!("Invalid line range in `{}`", tag.source))?,
+        None => (1, lines.len()),
+    };
+
+    let lang = tag.named_arg("lang").unwrap_or_else(|| guess_lang(&path));
+    let code = if lines.is_empty() {
+        String::new()
+    } else {
+        lines[start - 1..end].join("\n")
+    };
+
+    Ok(fenced(lang, &code))
+}
+
+/// Wrap the code in a fence longer than any backtick run it contains
+pub fn fenced(lang: &str, code: &str) -> String {
+    let longest_run = code
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    if code.is_empty() {
+        format!("{}{}\n{}", fence, lang, fence)
+    } else {
+        format!("{}{}\n{}\n{}", fence, lang, code, fence)
+    }
+}
+
+/// Parse a range like `1-40`, `5-`, `-10` or `7` into inclusive line numbers
+///
+/// The end is clamped to the number of lines. Returns `None` if the range is malformed or
+/// starts after the end of the file.
+fn parse_range(range: &str, len: usize) -> Option<(usize, usize)> {
+    let (start, end) = match range.find('-') {
+        Some(dash) => (range[..dash].trim(), range[dash + 1..].trim()),
+        None => (range.trim(), range.trim()),
+    };
+
+    let start = if start.is_empty() { 1 } else { start.parse().ok()? };
+    let end = if end.is_empty() { len } else { end.parse::<usize>().ok()?.min(len) };
+
+    if start == 0 || start > end {
+        return None;
+    }
+
+    Some((start, end))
+}
+
+fn guess_lang(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "rs" => "rust",
+        "toml" => "toml",
+        "json" => "json",
+        "yml" | "yaml" => "yaml",
+        "sh" | "bash" => "sh",
+        "py" => "python",
+        "js" => "javascript",
+        "html" => "html",
+        "md" => "markdown",
+        _ => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_ranges() {
+        assert_eq!(Some((1, 40)), parse_range("1-40", 100));
+        assert_eq!(Some((5, 100)), parse_range("5-", 100));
+        assert_eq!(Some((1, 10)), parse_range("-10", 100));
+        assert_eq!(Some((7, 7)), parse_range("7", 100));
+        assert_eq!(Some((90, 100)), parse_range("90-120", 100));
+        assert_eq!(None, parse_range("0-3", 100));
+        assert_eq!(None, parse_range("20-10", 100));
+        assert_eq!(None, parse_range("120", 100));
+        assert_eq!(None, parse_range("a-b", 100));
+    }
+
+    #[test]
+    fn fence_longer_than_content() {
+        assert_eq!("```toml\na = 1\n```", fenced("toml", "a = 1"));
+        assert_eq!("````md\n```\ncode\n```\n````", fenced("md", "```\ncode\n```"));
+    }
+}
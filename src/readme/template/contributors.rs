@@ -0,0 +1,161 @@
+//! Render the `{{contributors}}` tag
+//!
+//! Lists the authors of the commits of the git repository, the most active first, as given by
+//! `git shortlog -sn`, for an automatic credits section. Bots are left out: the authors whose
+//! name ends with `[bot]`, and the ones matching the `contributors-ignore` setting, where `*`
+//! matches any text, like `"renovate*"`. The list stops after 100 contributors, or the number
+//! given with `limit`, like `{{contributors limit=20}}`.
+//!
+//! Git runs once per repository, the crates of a workspace sharing the list. It fails if
+//! command execution is disabled.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::Context;
+use super::parser::Tag;
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Authors of the commits of each git repository, with their number of commits, the most
+/// active first
+pub type ContributorsCache = HashMap<PathBuf, Vec<(usize, String)>>;
+
+pub fn render(tag: &Tag, context: &mut Context) -> Result<String, String> {
+    if !context.allow_exec {
+        return Err(format!(
+            "`{}` needs to run git, but running commands was disabled with `--no-exec` or \
+             `--sandbox`",
+            tag.source
+        ));
+    }
+
+    let limit = match tag.named_arg("limit") {
+        Some(limit) => limit
+            .parse()
+            .map_err(|_| format!("Invalid limit '{}' in `{}`", limit, tag.source))?,
+        None => DEFAULT_LIMIT,
+    };
+
+    let repository = repository_root(context.project_root);
+    if !context.contributors.contains_key(&repository) {
+        let contributors = shortlog(&repository, tag)?;
+        context.contributors.insert(repository.clone(), contributors);
+    }
+
+    let ignore = &context.config.contributors_ignore;
+    let lines: Vec<String> = context.contributors[&repository]
+        .iter()
+        .map(|(_, name)| name)
+        .filter(|name| !is_bot(name, ignore))
+        .take(limit)
+        .map(|name| format!("- {}", name))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Root of the git repository the crate is in, or the crate itself if it is not in one
+fn repository_root(project_root: &Path) -> PathBuf {
+    let root = project_root.canonicalize().unwrap_or_else(|_| project_root.into());
+    root.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or(root)
+}
+
+fn shortlog(repository: &Path, tag: &Tag) -> Result<Vec<(usize, String)>, String> {
+    // without a revision, shortlog reads a log from stdin
+    let args = ["shortlog", "-sn", "--no-merges", "HEAD"];
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repository)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run git for `{}`: {}", tag.source, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "`git {}` failed for `{}`: {}",
+            args.join(" "),
+            tag.source,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(parse_shortlog(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Number of commits and name of each author, from lines like `    42\tJane Doe`
+///
+/// Authors with as many commits are sorted by name, for the same list on every run.
+fn parse_shortlog(shortlog: &str) -> Vec<(usize, String)> {
+    let mut contributors: Vec<(usize, String)> = shortlog
+        .lines()
+        .filter_map(|line| {
+            let (count, name) = line.trim().split_once('\t')?;
+            Some((count.trim().parse().ok()?, name.trim().to_owned()))
+        })
+        .collect();
+    contributors.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    contributors
+}
+
+/// Whether the author is a bot, or matches one of the `ignore` patterns, ignoring case
+fn is_bot(name: &str, ignore: &[String]) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with("[bot]") || ignore.iter().any(|pattern| matches(&pattern.to_lowercase(), &name))
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any text
+fn matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let mut rest = match text.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i + 1 == parts.len() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_git_shortlog() {
+        let shortlog =
+            "    42\tJane Doe\n     3\tdependabot[bot]\n     3\tAda\n     1\tJohn\tSmith\n";
+        assert_eq!(
+            vec![
+                (42, "Jane Doe".to_owned()),
+                (3, "Ada".to_owned()),
+                (3, "dependabot[bot]".to_owned()),
+                (1, "John\tSmith".to_owned()),
+            ],
+            parse_shortlog(shortlog)
+        );
+    }
+
+    #[test]
+    fn ignore_bots() {
+        let ignore = vec!["renovate*".to_owned(), "*ci user".to_owned(), "exact".to_owned()];
+        assert!(is_bot("dependabot[bot]", &ignore));
+        assert!(is_bot("Renovate Bot", &ignore));
+        assert!(is_bot("GitLab CI User", &ignore));
+        assert!(is_bot("Exact", &ignore));
+        assert!(!is_bot("Exactly Human", &ignore));
+        assert!(!is_bot("Jane Doe", &ignore));
+    }
+}
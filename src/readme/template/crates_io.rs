@@ -0,0 +1,37 @@
+//! Render the `{{published-version}}`, `{{total-downloads}}` and `{{owners}}` tags
+//!
+//! They show the crate as published to crates.io: its latest version, leaving out pre-releases,
+//! for "latest release" callouts, the downloads of all its versions, which
+//! `{{total-downloads | humanize}}` rounds, and the logins of its owners. crates.io is only
+//! queried with `--crates-io`, with `curl`, so it fails if command execution is disabled.
+//!
+//! The data is cached for an hour in `target/cargo-readme/crates-io/`, see `cache`.
+
+use package;
+
+use super::cache;
+use super::Context;
+use super::parser::Tag;
+
+pub fn render(tag: &Tag, context: &mut Context) -> Result<String, String> {
+    if !context.crates_io {
+        return Err(format!(
+            "`{}` needs the data of the crate on crates.io, fetch it with `--crates-io`",
+            tag.source
+        ));
+    }
+    if !context.allow_exec {
+        return Err(format!(
+            "`{}` needs to run curl, but running commands was disabled with `--no-exec` or \
+             `--sandbox`",
+            tag.source
+        ));
+    }
+
+    let published = cache::cached(context, "crates-io", "crates.io", package::published)?;
+    Ok(match tag.name.as_str() {
+        "published-version" => published.version,
+        "total-downloads" => published.downloads.to_string(),
+        _ => published.owners.join(", "),
+    })
+}
@@ -0,0 +1,107 @@
+//! Render the `{{date}}` and `{{year}}` tags
+//!
+//! `{{year}}` is the current year, for copyright lines, and `{{date}}` the current date, as
+//! `2024-05-01`, or in the format given like `{{date "%B %e, %Y"}}`, for "last updated"
+//! footers. Dates are in UTC. When `SOURCE_DATE_EPOCH` is set, as reproducible builds do, they
+//! are taken from it instead, so the readme is the same on every run.
+//!
+//! The format can use `%Y` for the year, `%y` for its last two digits, `%m` for the month,
+//! `%B` and `%b` for its name, `%d` for the day, `%e` for the day without a leading zero, `%H`,
+//! `%M` and `%S` for the time, and `%%` for `%`.
+
+use super::Context;
+use super::parser::Tag;
+
+const DEFAULT_FORMAT: &str = "%Y-%m-%d";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+
+pub fn date(tag: &Tag, context: &Context) -> Result<String, String> {
+    format(context.date, tag.arg(0).unwrap_or(DEFAULT_FORMAT))
+        .map_err(|spec| format!("Unknown format `{}` in `{}`", spec, tag.source))
+}
+
+pub fn year(context: &Context) -> String {
+    civil_date(context.date / SECONDS_PER_DAY).0.to_string()
+}
+
+/// Format the time, in seconds since the Unix epoch, failing with the unknown specifier
+fn format(time: u64, format: &str) -> Result<String, String> {
+    let (year, month, day) = civil_date(time / SECONDS_PER_DAY);
+    let seconds = time % SECONDS_PER_DAY;
+
+    let mut result = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        let text = match chars.next() {
+            Some('Y') => year.to_string(),
+            Some('y') => format!("{:02}", year % 100),
+            Some('m') => format!("{:02}", month),
+            Some('B') => MONTHS[month as usize - 1].to_owned(),
+            Some('b') => MONTHS[month as usize - 1][..3].to_owned(),
+            Some('d') => format!("{:02}", day),
+            Some('e') => day.to_string(),
+            Some('H') => format!("{:02}", seconds / 3600),
+            Some('M') => format!("{:02}", seconds / 60 % 60),
+            Some('S') => format!("{:02}", seconds % 60),
+            Some('%') => "%".to_owned(),
+            Some(spec) => return Err(format!("%{}", spec)),
+            None => return Err("%".to_owned()),
+        };
+        result.push_str(&text);
+    }
+
+    Ok(result)
+}
+
+/// Year, month and day of the given number of days since 1970-01-01
+///
+/// See Howard Hinnant's `civil_from_days`, counting in eras of 400 years from March 0000, so
+/// that leap days come last.
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_from_days() {
+        assert_eq!((1970, 1, 1), civil_date(0));
+        assert_eq!((2000, 2, 29), civil_date(11_016));
+        assert_eq!((2000, 3, 1), civil_date(11_017));
+        assert_eq!((2024, 12, 31), civil_date(20_088));
+    }
+
+    #[test]
+    fn format_dates() {
+        // 2024-05-01 08:05:09
+        let time = 1_714_550_709;
+        assert_eq!(Ok("2024-05-01".to_owned()), format(time, DEFAULT_FORMAT));
+        assert_eq!(Ok("May 1, 2024".to_owned()), format(time, "%B %e, %Y"));
+        assert_eq!(
+            Ok("1 May 24 08:05:09 100%".to_owned()),
+            format(time, "%e %b %y %H:%M:%S 100%%")
+        );
+        assert_eq!(Err("%Q".to_owned()), format(time, "%Q"));
+        assert_eq!(Err("%".to_owned()), format(time, "100%"));
+    }
+}
@@ -0,0 +1,100 @@
+//! Render the `{{demo}}` tag
+//!
+//! Renders the media listed in the `demo` setting, one per paragraph, so demo sections look the
+//! same across crates. Images and animations are embedded, asciinema recordings are embedded
+//! through their preview image and other media are linked. Local files that do not exist are
+//! reported, so a renamed gif does not silently break the readme.
+
+use config::Media;
+use super::super::Warning;
+use super::Context;
+
+const IMAGE_EXTENSIONS: &[&str] = &[".gif", ".png", ".jpg", ".jpeg", ".svg", ".webp"];
+const ASCIINEMA_URL: &str = "https://asciinema.org/a/";
+
+pub fn render(context: &mut Context) -> String {
+    if context.config.demo.is_empty() {
+        context.warnings.push(Warning::without_line(
+            "`{{demo}}` was found in template but no demo media is configured",
+        ));
+    }
+
+    let mut paragraphs = Vec::new();
+    for media in &context.config.demo {
+        if !is_url(&media.path) && !context.project_root.join(&media.path).exists() {
+            context.warnings.push(Warning::without_line(format!(
+                "demo media '{}' does not exist",
+                media.path
+            )));
+        }
+        paragraphs.push(render_media(media));
+    }
+
+    paragraphs.join("\n\n")
+}
+
+fn render_media(media: &Media) -> String {
+    let alt = media.alt.as_deref().unwrap_or("Demo");
+    let path = media.path.as_str();
+    let lowercase = path.to_lowercase();
+
+    let (embed, link) = if path.starts_with(ASCIINEMA_URL) {
+        let url = path.trim_end_matches('/');
+        (Some(format!("![{}]({}.svg)", alt, url)), Some(url))
+    } else if IMAGE_EXTENSIONS.iter().any(|ext| lowercase.ends_with(ext)) {
+        (Some(format!("![{}]({})", alt, path)), None)
+    } else {
+        (None, Some(path))
+    };
+
+    match (embed, media.link.as_deref().or(link)) {
+        (Some(embed), Some(link)) => format!("[{}]({})", embed, link),
+        (Some(embed), None) => embed,
+        (None, link) => format!("[{}]({})", alt, link.unwrap_or(path)),
+    }
+}
+
+fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use config::Media;
+    use super::render_media;
+
+    fn media(path: &str, alt: Option<&str>, link: Option<&str>) -> Media {
+        Media {
+            path: path.to_owned(),
+            alt: alt.map(|alt| alt.to_owned()),
+            link: link.map(|link| link.to_owned()),
+        }
+    }
+
+    #[test]
+    fn render_image() {
+        let result = render_media(&media("assets/demo.GIF", Some("Usage"), None));
+        assert_eq!(result, "![Usage](assets/demo.GIF)");
+    }
+
+    #[test]
+    fn render_linked_image() {
+        let result = render_media(&media("demo.svg", None, Some("https://example.com")));
+        assert_eq!(result, "[![Demo](demo.svg)](https://example.com)");
+    }
+
+    #[test]
+    fn render_asciinema() {
+        let result = render_media(&media("https://asciinema.org/a/1234", None, None));
+        assert_eq!(
+            result,
+            "[![Demo](https://asciinema.org/a/1234.svg)](https://asciinema.org/a/1234)"
+        );
+    }
+
+    #[test]
+    fn render_other_media() {
+        let result = render_media(&media("demo.cast", Some("Recording"), None));
+        assert_eq!(result, "[Recording](demo.cast)");
+    }
+}
@@ -0,0 +1,54 @@
+//! Render the `{{docsrs-ok}}` tag, and warn when the docs of the crate failed to build on docs.rs
+//!
+//! With `--docs-rs`, the status of the docs.rs build of the latest published version is fetched
+//! with `curl`, so it fails if command execution is disabled. `{{docsrs-ok}}` is `true` if the
+//! docs were built and `false` otherwise, and a failed build gives a warning, as the links of the
+//! readme to the docs lead nowhere until a new version builds.
+//!
+//! The status is cached for an hour in `target/cargo-readme/docs-rs/`, see `cache`.
+
+use package::{self, DocsBuild};
+
+use super::super::Warning;
+use super::cache;
+use super::Context;
+use super::parser::Tag;
+
+pub fn render(tag: &Tag, context: &mut Context) -> Result<String, String> {
+    if !context.docs_rs {
+        return Err(format!(
+            "`{}` needs the status of the docs build on docs.rs, fetch it with `--docs-rs`",
+            tag.source
+        ));
+    }
+
+    Ok(docs_build(context)?.ok.to_string())
+}
+
+/// Warn if the last docs build of the crate failed, when `--docs-rs` is given
+pub fn check(context: &mut Context) -> Result<(), String> {
+    if !context.docs_rs {
+        return Ok(());
+    }
+
+    let build = docs_build(context)?;
+    if !build.ok {
+        let warning = format!(
+            "The docs.rs build of {} {} failed, links to its docs on docs.rs are broken",
+            context.cargo.package.name, build.version
+        );
+        context.warnings.push(Warning::without_line(warning));
+    }
+    Ok(())
+}
+
+fn docs_build(context: &mut Context) -> Result<DocsBuild, String> {
+    if !context.allow_exec {
+        return Err(
+            "`--docs-rs` needs to run curl, but running commands was disabled with `--no-exec` \
+             or `--sandbox`"
+                .to_owned(),
+        );
+    }
+    cache::cached(context, "docs-rs", "docs.rs", package::docs_build)
+}
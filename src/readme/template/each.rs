@@ -0,0 +1,125 @@
+//! Arrays of `Cargo.toml` that `{{#each}}` blocks iterate over
+//!
+//! A block can go over `authors`, `keywords` and `categories`, or over any array under
+//! `[package.metadata]`, named by its path like `metadata.credits`. Inside the block, `{{this}}`
+//! is the current item and `{{this.name}}` a field of it, when the items are tables.
+//!
+//! ```text
+//! {{#each metadata.credits}}
+//! - [{{this.name}}]({{this.url}})
+//! {{/each}}
+//! ```
+
+use toml::Value;
+
+use cargo_info::Cargo;
+
+use super::parser::Tag;
+
+/// Items of the array named by the `{{#each}}` tag, none if the array is not set
+pub fn items(tag: &Tag, cargo: &Cargo) -> Result<Vec<Value>, String> {
+    let name = tag
+        .arg(0)
+        .ok_or_else(|| format!("Missing array in `{}`", tag.source))?;
+
+    let package = &cargo.package;
+    let value = match name {
+        "authors" => package.authors.clone(),
+        "keywords" => package.keywords.clone(),
+        "categories" => package.categories.clone(),
+        _ => {
+            let path = name.strip_prefix("metadata.").ok_or_else(|| {
+                format!(
+                    "Unknown array '{}' in `{}`, expected authors, keywords, categories or an \
+                     array under `metadata.`",
+                    name, tag.source
+                )
+            })?;
+            metadata(cargo, path)
+        }
+    };
+
+    match value {
+        None => Ok(Vec::new()),
+        Some(Value::Array(items)) => Ok(items),
+        Some(Value::Table(ref table)) if table.contains_key("workspace") => Err(format!(
+            "`{}` was found in template but '{}' is inherited from the workspace",
+            tag.source, name
+        )),
+        Some(_) => Err(format!("'{}' in `{}` is not an array", name, tag.source)),
+    }
+}
+
+/// Value at the dotted `path` under `[package.metadata]`
+fn metadata(cargo: &Cargo, path: &str) -> Option<Value> {
+    let metadata = cargo.package.metadata.as_ref()?;
+    let mut keys = path.split('.');
+    let first = keys.next()?;
+    let root = match first {
+        "readme" => metadata.readme.as_ref(),
+        _ => metadata.other.get(first),
+    };
+    keys.try_fold(root?, |value, key| value.get(key)).cloned()
+}
+
+/// Text of the `{{this}}` tag, or of `{{this.field}}`, for the current item
+pub fn render_item(tag: &Tag, item: &Value) -> Result<String, String> {
+    let value = match tag.name.strip_prefix("this.") {
+        Some(path) => path
+            .split('.')
+            .try_fold(item, |value, key| value.get(key))
+            .ok_or_else(|| format!("The item has no field for `{}`", tag.source))?,
+        None => item,
+    };
+
+    match *value {
+        Value::String(ref s) => Ok(s.clone()),
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::Float(f) => Ok(f.to_string()),
+        Value::Boolean(b) => Ok(b.to_string()),
+        Value::Datetime(ref d) => Ok(d.to_string()),
+        Value::Array(_) | Value::Table(_) => {
+            Err(format!("`{}` is a table or an array, not text", tag.source))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use toml;
+
+    use super::super::parser::{parse, Delimiters, Node};
+    use super::*;
+
+    fn tag(source: &str) -> Tag {
+        match parse(source, &Delimiters::default()).pop() {
+            Some(Node::Tag(tag)) => tag,
+            _ => panic!("not a tag: {}", source),
+        }
+    }
+
+    #[test]
+    fn arrays_of_cargo_toml() {
+        let cargo: Cargo = toml::from_str(concat_lines!(
+            "[package]",
+            "name = \"my-crate\"",
+            "keywords = [\"cli\", \"readme\"]",
+            "authors = { workspace = true }",
+            "",
+            "[package.metadata]",
+            "credits = [{ name = \"Ferris\", url = \"https://rustacean.net\" }]",
+        ))
+        .unwrap();
+
+        let keywords = items(&tag("{{each keywords}}"), &cargo).unwrap();
+        assert_eq!(vec![Value::from("cli"), Value::from("readme")], keywords);
+        assert_eq!(Ok(Vec::new()), items(&tag("{{each categories}}"), &cargo));
+        assert!(items(&tag("{{each authors}}"), &cargo).is_err());
+        assert!(items(&tag("{{each license}}"), &cargo).is_err());
+
+        let credits = items(&tag("{{each metadata.credits}}"), &cargo).unwrap();
+        assert_eq!(Ok("Ferris".to_owned()), render_item(&tag("{{this.name}}"), &credits[0]));
+        assert!(render_item(&tag("{{this}}"), &credits[0]).is_err());
+        assert!(render_item(&tag("{{this.email}}"), &credits[0]).is_err());
+    }
+}
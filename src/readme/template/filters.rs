@@ -0,0 +1,155 @@
+//! Apply the filters of a tag to its value
+//!
+//! Available filters:
+//! - `replace "from" "to"` replace every occurrence of `from` by `to`
+//! - `lower` and `upper` change the case
+//! - `major-minor` keep the first two numbers of a version, `1.2.3` becomes `1.2`
+//! - `spdx-short` shorten a license expression, `MIT OR Apache-2.0` becomes `MIT/Apache-2.0`
+//! - `humanize` round a count, `1234567` becomes `1.2M`, for download counts
+//! - `bytes` write a size in bytes with binary units, `1536` becomes `1.5 KiB`
+
+use super::parser::{Filter, Tag};
+
+const COUNT_UNITS: [&str; 5] = ["", "k", "M", "G", "T"];
+const BYTE_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+pub fn apply(value: String, tag: &Tag) -> Result<String, String> {
+    tag.filters.iter().try_fold(value, |value, filter| {
+        apply_filter(value, filter).map_err(|e| format!("{} in `{}`", e, tag.source))
+    })
+}
+
+fn apply_filter(value: String, filter: &Filter) -> Result<String, String> {
+    let args: Vec<&str> = filter.args.iter().map(|arg| arg.as_str()).collect();
+
+    match (filter.name.as_str(), args.as_slice()) {
+        ("replace", [from, to]) => Ok(value.replace(from, to)),
+        ("lower", []) => Ok(value.to_lowercase()),
+        ("upper", []) => Ok(value.to_uppercase()),
+        ("major-minor", []) => Ok(major_minor(&value)),
+        ("spdx-short", []) => Ok(spdx_short(&value)),
+        ("humanize", []) => {
+            let (number, unit) = scale(parse_number(&value, filter)?, 1000.0, &COUNT_UNITS);
+            Ok(format!("{}{}", number, unit))
+        }
+        ("bytes", []) => {
+            let (number, unit) = scale(parse_number(&value, filter)?, 1024.0, &BYTE_UNITS);
+            Ok(format!("{} {}", number, unit))
+        }
+        ("replace", _)
+        | ("lower", _)
+        | ("upper", _)
+        | ("major-minor", _)
+        | ("spdx-short", _)
+        | ("humanize", _)
+        | ("bytes", _) => Err(format!("Wrong number of arguments for filter '{}'", filter.name)),
+        _ => Err(format!("Unknown filter '{}'", filter.name)),
+    }
+}
+
+fn major_minor(version: &str) -> String {
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    core.split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+fn spdx_short(license: &str) -> String {
+    license
+        .split_whitespace()
+        .map(|word| match word {
+            "OR" | "or" => "/",
+            "AND" | "and" => "+",
+            word => word,
+        })
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+fn parse_number(value: &str, filter: &Filter) -> Result<f64, String> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|number: &f64| number.is_finite() && *number >= 0.0)
+        .ok_or_else(|| format!("Filter '{}' needs a number, not '{}'", filter.name, value))
+}
+
+/// The number divided by `base` until it is below it, and the unit it is then in
+fn scale(mut number: f64, base: f64, units: &[&'static str]) -> (String, &'static str) {
+    let mut unit = 0;
+    // 999950 is 1M rather than 1000k once rounded
+    while round(number) >= base && unit + 1 < units.len() {
+        number /= base;
+        unit += 1;
+    }
+    (round(number).to_string(), units[unit])
+}
+
+/// Round to one decimal below 100, where it still means something, and to units above
+fn round(number: f64) -> f64 {
+    if number < 100.0 {
+        (number * 10.0).round() / 10.0
+    } else {
+        number.round()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::{parse, Delimiters, Node};
+    use super::apply;
+
+    fn render(template: &str, value: &str) -> Result<String, String> {
+        match parse(template, &Delimiters::default()).pop() {
+            Some(Node::Tag(tag)) => apply(value.to_owned(), &tag),
+            _ => panic!("not a tag: {}", template),
+        }
+    }
+
+    #[test]
+    fn replace_and_change_case() {
+        assert_eq!(Ok("my_crate".to_owned()), render(r#"{{crate | replace "-" "_"}}"#, "my-crate"));
+        assert_eq!(Ok("MY-CRATE".to_owned()), render("{{crate | upper}}", "my-crate"));
+    }
+
+    #[test]
+    fn major_minor_version() {
+        assert_eq!(Ok("1.2".to_owned()), render("{{version | major-minor}}", "1.2.3"));
+        assert_eq!(Ok("0.4".to_owned()), render("{{version | major-minor}}", "0.4.0-beta.1"));
+    }
+
+    #[test]
+    fn spdx_short_license() {
+        let result = render("{{license | spdx-short}}", "MIT OR Apache-2.0");
+        assert_eq!(Ok("MIT/Apache-2.0".to_owned()), result);
+    }
+
+    #[test]
+    fn humanize_counts() {
+        assert_eq!(Ok("999".to_owned()), render("{{downloads | humanize}}", "999"));
+        assert_eq!(Ok("1.2k".to_owned()), render("{{downloads | humanize}}", "1234"));
+        assert_eq!(Ok("45k".to_owned()), render("{{downloads | humanize}}", "45012"));
+        assert_eq!(Ok("123k".to_owned()), render("{{downloads | humanize}}", "123456"));
+        assert_eq!(Ok("1M".to_owned()), render("{{downloads | humanize}}", "999950"));
+        assert_eq!(Ok("1.2M".to_owned()), render("{{downloads | humanize}}", " 1234567\n"));
+        assert_eq!(
+            Err("Filter 'humanize' needs a number, not 'many' in `{{downloads | humanize}}`"
+                .to_owned()),
+            render("{{downloads | humanize}}", "many")
+        );
+    }
+
+    #[test]
+    fn sizes_in_bytes() {
+        assert_eq!(Ok("512 B".to_owned()), render("{{size | bytes}}", "512"));
+        assert_eq!(Ok("1.5 KiB".to_owned()), render("{{size | bytes}}", "1536"));
+        assert_eq!(Ok("10 MiB".to_owned()), render("{{size | bytes}}", "10485760"));
+    }
+
+    #[test]
+    fn unknown_filter() {
+        assert_eq!(
+            Err("Unknown filter 'reverse' in `{{crate | reverse}}`".to_owned()),
+            render("{{crate | reverse}}", "my-crate")
+        );
+    }
+}
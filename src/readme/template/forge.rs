@@ -0,0 +1,255 @@
+//! Render the `{{ci-badge}}` and `{{source-url}}` tags
+//!
+//! Both point to the repository of the `repository` field of `Cargo.toml`, the way its forge
+//! lays out its pages: GitHub, GitLab, sourcehut and Codeberg are told apart by their host, and
+//! a self-hosted forge is set with the `forge` setting. Links point to the `main` branch, or the
+//! one of the `default-branch` setting.
+//!
+//! - `{{ci-badge}}` is the status badge of the CI of the forge, GitHub Actions running
+//!   `ci.yml` or the workflow given with `workflow="test.yml"`, GitLab pipelines, builds.sr.ht
+//!   or Woodpecker on Codeberg. The `ci-badge` setting replaces it for any other CI.
+//! - `{{source-url "examples/basic.rs"}}` is the url of the page of a file of the crate, for
+//!   links like `[the example]({{source-url "examples/basic.rs"}})`.
+//!
+//! With the `absolute-links` setting, the relative links of the readme point to the same pages,
+//! and its images to the raw content of the files, which is served from another url on each
+//! forge.
+
+use std::path::Path;
+
+use cargo_info::Cargo;
+use config::{Config, Forge};
+use workspace;
+
+use super::Context;
+use super::parser::Tag;
+
+const DEFAULT_BRANCH: &str = "main";
+const DEFAULT_WORKFLOW: &str = "ci.yml";
+
+/// A repository on a forge
+#[derive(Debug, PartialEq)]
+struct Repository {
+    forge: Forge,
+    /// Url of the home page of the repository, without a trailing `/` or `.git`
+    url: String,
+    /// Path of the repository on the forge, like `owner/repo`
+    path: String,
+}
+
+/// Urls of the files of the repository of a crate
+pub struct FileUrls {
+    repository: Repository,
+    branch: String,
+    /// Directory of the crate in the repository, empty if it is at its root
+    dir: String,
+}
+
+impl FileUrls {
+    /// Urls of the files of the repository set in `Cargo.toml`, on the forge hosting it
+    pub fn new(project_root: &Path, cargo: &Cargo, config: &Config) -> Result<Self, String> {
+        let url = cargo.repository().ok_or("no repository was provided")?;
+        let repository = parse_repository(url, config.forge).ok_or_else(|| {
+            format!(
+                "the forge hosting '{}' is not known, set it with `forge = \"gitlab\"`, or \
+                 \"github\", \"sourcehut\" or \"codeberg\"",
+                url
+            )
+        })?;
+
+        Ok(FileUrls {
+            repository,
+            branch: config.default_branch.as_deref().unwrap_or(DEFAULT_BRANCH).to_owned(),
+            dir: crate_dir(project_root).unwrap_or_default(),
+        })
+    }
+
+    /// Url of the file at `path`, relative to the crate, or to the repository if it starts with
+    /// `/`, with its page on the forge or its raw content
+    ///
+    /// There is none if the path goes out of the repository.
+    pub fn url(&self, path: &str, raw: bool) -> Option<String> {
+        let end = path.find(['#', '?']).unwrap_or(path.len());
+        let (path, suffix) = path.split_at(end);
+        let file = match path.strip_prefix('/') {
+            Some(path) => normalize("", path)?,
+            None => normalize(&self.dir, path)?,
+        };
+
+        let (url, path, branch) = (&self.repository.url, &self.repository.path, &self.branch);
+        let url = match (self.repository.forge, raw) {
+            (Forge::Github, false) => format!("{}/blob/{}/{}", url, branch, file),
+            (Forge::Github, true) => {
+                format!("https://raw.githubusercontent.com/{}/{}/{}", path, branch, file)
+            }
+            (Forge::Gitlab, false) => format!("{}/-/blob/{}/{}", url, branch, file),
+            (Forge::Gitlab, true) => format!("{}/-/raw/{}/{}", url, branch, file),
+            (Forge::Sourcehut, false) => format!("{}/tree/{}/item/{}", url, branch, file),
+            (Forge::Sourcehut, true) => format!("{}/blob/{}/{}", url, branch, file),
+            (Forge::Codeberg, false) => format!("{}/src/branch/{}/{}", url, branch, file),
+            (Forge::Codeberg, true) => format!("{}/raw/branch/{}/{}", url, branch, file),
+        };
+        Some(format!("{}{}", url, suffix))
+    }
+}
+
+pub fn ci_badge(tag: &Tag, context: &Context) -> Result<String, String> {
+    if let Some(ref badge) = context.config.ci_badge {
+        return Ok(format!("[![CI]({})]({})", badge.image, badge.link));
+    }
+
+    let urls = file_urls(tag, context)?;
+    let (url, path, branch) = (&urls.repository.url, &urls.repository.path, &urls.branch);
+    let (image, link) = match urls.repository.forge {
+        Forge::Github => {
+            let workflow = tag.named_arg("workflow").unwrap_or(DEFAULT_WORKFLOW);
+            let link = format!("{}/actions/workflows/{}", url, workflow);
+            (format!("{}/badge.svg", link), link)
+        }
+        Forge::Gitlab => {
+            (format!("{}/badges/{}/pipeline.svg", url, branch), format!("{}/-/pipelines", url))
+        }
+        Forge::Sourcehut => {
+            let link = format!("https://builds.sr.ht/{}/commits/{}", path, branch);
+            (format!("{}.svg", link), link)
+        }
+        Forge::Codeberg => (
+            format!("https://ci.codeberg.org/api/badges/{}/status.svg", path),
+            format!("https://ci.codeberg.org/{}", path),
+        ),
+    };
+
+    Ok(format!("[![CI]({})]({})", image, link))
+}
+
+pub fn source_url(tag: &Tag, context: &Context) -> Result<String, String> {
+    let file = tag
+        .arg(0)
+        .ok_or_else(|| format!("`{}` needs the path of a file of the crate", tag.source))?;
+    file_urls(tag, context)?
+        .url(file, false)
+        .ok_or_else(|| format!("'{}' of `{}` is out of the repository", file, tag.source))
+}
+
+fn file_urls(tag: &Tag, context: &Context) -> Result<FileUrls, String> {
+    FileUrls::new(context.project_root, context.cargo, context.config)
+        .map_err(|e| format!("`{}` was found in template but {}", tag.source, e))
+}
+
+/// The repository at `url`, on `forge` if it is given, or on the forge its host belongs to
+fn parse_repository(url: &str, forge: Option<Forge>) -> Option<Repository> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let (host, path) = url.split_once("://")?.1.split_once('/')?;
+
+    let forge = match forge {
+        Some(forge) => forge,
+        None if host == "github.com" => Forge::Github,
+        None if host == "gitlab.com" || host.starts_with("gitlab.") => Forge::Gitlab,
+        None if host == "git.sr.ht" => Forge::Sourcehut,
+        None if host == "codeberg.org" => Forge::Codeberg,
+        None => return None,
+    };
+
+    Some(Repository {
+        forge,
+        url: url.to_owned(),
+        path: path.to_owned(),
+    })
+}
+
+/// Directory of the crate in its git repository, if it is not at its root
+fn crate_dir(project_root: &Path) -> Option<String> {
+    let root = project_root.canonicalize().ok()?;
+    let repository = root.ancestors().find(|dir| dir.join(".git").exists())?;
+    Some(workspace::relative_path(repository, &root)).filter(|dir| dir != ".")
+}
+
+/// `path` from the directory `dir` of the repository, as a path from its root, without `.` and
+/// `..`, if it stays in the repository
+fn normalize(dir: &str, path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = dir.split('/').filter(|part| !part.is_empty()).collect();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    // directories keep their trailing `/`
+    let slash = if path.ends_with('/') { "/" } else { "" };
+    Some(format!("{}{}", parts.join("/"), slash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_forge() {
+        let forge = |url| parse_repository(url, None).map(|repository| repository.forge);
+        assert_eq!(Some(Forge::Github), forge("https://github.com/owner/repo"));
+        assert_eq!(Some(Forge::Gitlab), forge("https://gitlab.com/group/subgroup/repo"));
+        assert_eq!(Some(Forge::Sourcehut), forge("https://git.sr.ht/~user/repo"));
+        assert_eq!(Some(Forge::Codeberg), forge("https://codeberg.org/owner/repo/"));
+        assert_eq!(None, forge("https://git.example.com/owner/repo"));
+        assert_eq!(None, forge("github.com/owner/repo"));
+
+        assert_eq!(
+            Some(Repository {
+                forge: Forge::Gitlab,
+                url: "https://git.example.com/owner/repo".to_owned(),
+                path: "owner/repo".to_owned(),
+            }),
+            parse_repository("https://git.example.com/owner/repo.git", Some(Forge::Gitlab))
+        );
+    }
+
+    #[test]
+    fn file_urls_of_each_forge() {
+        let urls = |url| FileUrls {
+            repository: parse_repository(url, None).unwrap(),
+            branch: "main".to_owned(),
+            dir: "crates/core".to_owned(),
+        };
+
+        let github = urls("https://github.com/owner/repo");
+        assert_eq!(
+            Some("https://github.com/owner/repo/blob/main/crates/cli/README.md#usage".to_owned()),
+            github.url("../cli/README.md#usage", false)
+        );
+        assert_eq!(
+            Some("https://raw.githubusercontent.com/owner/repo/main/assets/logo.png".to_owned()),
+            github.url("/assets/logo.png", true)
+        );
+        assert_eq!(None, github.url("../../../outside.md", false));
+
+        let gitlab = urls("https://gitlab.com/group/repo");
+        assert_eq!(
+            Some("https://gitlab.com/group/repo/-/raw/main/crates/core/logo.png".to_owned()),
+            gitlab.url("./logo.png", true)
+        );
+
+        let sourcehut = urls("https://git.sr.ht/~user/repo");
+        assert_eq!(
+            Some("https://git.sr.ht/~user/repo/tree/main/item/crates/core/src/".to_owned()),
+            sourcehut.url("src/", false)
+        );
+        assert_eq!(
+            Some("https://git.sr.ht/~user/repo/blob/main/crates/core/logo.png".to_owned()),
+            sourcehut.url("logo.png", true)
+        );
+
+        let codeberg = urls("https://codeberg.org/owner/repo");
+        assert_eq!(
+            Some("https://codeberg.org/owner/repo/src/branch/main/crates/core/NEWS.md".to_owned()),
+            codeberg.url("NEWS.md", false)
+        );
+        assert_eq!(
+            Some("https://codeberg.org/owner/repo/raw/branch/main/crates/core/logo.png".to_owned()),
+            codeberg.url("logo.png", true)
+        );
+    }
+}
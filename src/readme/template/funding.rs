@@ -0,0 +1,203 @@
+//! Render the `{{funding}}` tag and the funding footer
+//!
+//! Lists the sponsor links of `.github/FUNDING.yml`, the file GitHub reads to show the "Sponsor"
+//! button, so the readme of every crate of an organization points to the same places. The file
+//! is looked for in the crate, then in the directories above it up to the root of the git
+//! repository, where it usually is.
+
+use std::fs::File;
+use std::io::Read;
+
+use super::super::Warning;
+use super::Context;
+
+const FUNDING_FILE: &str = ".github/FUNDING.yml";
+
+/// Title of the section appended with the `funding-footer` setting
+const FOOTER_TITLE: &str = "## Support this project";
+
+const LFX_CROWDFUNDING: &str = "https://crowdfunding.lfx.linuxfoundation.org/projects/";
+
+/// Name and url of the page of an account, for each platform GitHub supports
+const PLATFORMS: &[(&str, &str, &str)] = &[
+    ("github", "GitHub Sponsors", "https://github.com/sponsors/"),
+    ("patreon", "Patreon", "https://www.patreon.com/"),
+    ("open_collective", "Open Collective", "https://opencollective.com/"),
+    ("ko_fi", "Ko-fi", "https://ko-fi.com/"),
+    ("tidelift", "Tidelift", "https://tidelift.com/funding/github/"),
+    ("community_bridge", "LFX Crowdfunding", LFX_CROWDFUNDING),
+    ("lfx_crowdfunding", "LFX Crowdfunding", LFX_CROWDFUNDING),
+    ("liberapay", "Liberapay", "https://liberapay.com/"),
+    ("issuehunt", "IssueHunt", "https://issuehunt.io/r/"),
+    ("polar", "Polar", "https://polar.sh/"),
+    ("buy_me_a_coffee", "Buy Me a Coffee", "https://www.buymeacoffee.com/"),
+    ("thanks_dev", "thanks.dev", "https://thanks.dev/"),
+];
+
+pub fn render(context: &mut Context) -> String {
+    match links(context) {
+        Ok(links) => links,
+        Err(e) => {
+            context.warnings.push(Warning::without_line(format!(
+                "`{{{{funding}}}}` was found in template but {}",
+                e
+            )));
+            String::new()
+        }
+    }
+}
+
+/// Section with the sponsor links, to append to the readme, if there are any
+pub fn footer(context: &mut Context) -> Option<String> {
+    match links(context) {
+        Ok(links) => Some(format!("{}\n\n{}", FOOTER_TITLE, links)),
+        Err(e) => {
+            context
+                .warnings
+                .push(Warning::without_line(format!("`funding-footer` is set but {}", e)));
+            None
+        }
+    }
+}
+
+/// List of the sponsor links of the crate
+fn links(context: &Context) -> Result<String, String> {
+    let file = context
+        .find_repo_file(&[FUNDING_FILE])
+        .ok_or_else(|| format!("no `{}` was found", FUNDING_FILE))?;
+    let mut content = String::new();
+    File::open(&file)
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .map_err(|e| format!("'{}' could not be read: {}", file.to_string_lossy(), e))?;
+
+    let links = render_links(parse(&content));
+    if links.is_empty() {
+        return Err(format!("'{}' has no sponsor links", file.to_string_lossy()));
+    }
+
+    Ok(links.join("\n"))
+}
+
+/// Platforms and accounts of a `FUNDING.yml`, in the order they are listed
+///
+/// Only the subset of YAML used by these files is understood: a key per platform, with one
+/// account, a list of accounts between brackets, or a list of accounts on the following lines.
+fn parse(content: &str) -> Vec<(String, Vec<String>)> {
+    let mut platforms: Vec<(String, Vec<String>)> = Vec::new();
+    for line in content.lines() {
+        let line = strip_comment(line);
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let item = line.trim_start();
+        if let Some(account) = item.strip_prefix("- ") {
+            if let Some(&mut (_, ref mut accounts)) = platforms.last_mut() {
+                accounts.extend(scalar(account));
+            }
+            continue;
+        }
+
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        let accounts = match value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+            Some(list) => list.split(',').filter_map(scalar).collect(),
+            None => scalar(value).into_iter().collect(),
+        };
+        platforms.push((key.to_owned(), accounts));
+    }
+
+    platforms
+}
+
+/// Line without its comment, which starts with a `#` at the start of the line or after a space
+fn strip_comment(line: &str) -> &str {
+    if line.trim_start().starts_with('#') {
+        return "";
+    }
+    match line.find(" #") {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+/// A value without its quotes, unless it is empty or null
+fn scalar(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches('"').trim_matches('\'').trim();
+    if value.is_empty() || value == "~" || value == "null" {
+        None
+    } else {
+        Some(value.to_owned())
+    }
+}
+
+/// A list item linking to each account, leaving out the platforms that are not known
+fn render_links(platforms: Vec<(String, Vec<String>)>) -> Vec<String> {
+    platforms
+        .into_iter()
+        .flat_map(|(platform, accounts)| {
+            accounts.into_iter().filter_map(move |account| render_link(&platform, &account))
+        })
+        .collect()
+}
+
+fn render_link(platform: &str, account: &str) -> Option<String> {
+    if platform == "custom" {
+        let url = if account.contains("://") {
+            account.to_owned()
+        } else {
+            format!("https://{}", account)
+        };
+        return Some(format!("- <{}>", url));
+    }
+
+    let &(_, name, url) = PLATFORMS.iter().find(|&&(key, _, _)| key == platform)?;
+    Some(format!("- [{} ({})]({}{})", name, account, url, account))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FUNDING: &str = concat_lines!(
+        "# These are supported funding model platforms",
+        "",
+        "github: [octocat, 'surftocat']",
+        "patreon: octocat # the main one",
+        "open_collective: # none yet",
+        "ko_fi:",
+        "  - octocat",
+        "custom: [\"https://www.paypal.me/octocat\", octocat.com]",
+    );
+
+    #[test]
+    fn parse_funding_file() {
+        let expected = vec![
+            ("github".to_owned(), vec!["octocat".to_owned(), "surftocat".to_owned()]),
+            ("patreon".to_owned(), vec!["octocat".to_owned()]),
+            ("open_collective".to_owned(), vec![]),
+            ("ko_fi".to_owned(), vec!["octocat".to_owned()]),
+            (
+                "custom".to_owned(),
+                vec!["https://www.paypal.me/octocat".to_owned(), "octocat.com".to_owned()],
+            ),
+        ];
+        assert_eq!(expected, parse(FUNDING));
+    }
+
+    #[test]
+    fn link_accounts() {
+        let links = render_links(parse(FUNDING));
+        let expected = vec![
+            "- [GitHub Sponsors (octocat)](https://github.com/sponsors/octocat)",
+            "- [GitHub Sponsors (surftocat)](https://github.com/sponsors/surftocat)",
+            "- [Patreon (octocat)](https://www.patreon.com/octocat)",
+            "- [Ko-fi (octocat)](https://ko-fi.com/octocat)",
+            "- <https://www.paypal.me/octocat>",
+            "- <https://octocat.com>",
+        ];
+        assert_eq!(expected, links);
+    }
+}
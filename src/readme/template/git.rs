@@ -0,0 +1,45 @@
+//! Render the `{{git-sha}}` and `{{git-tag}}` tags
+//!
+//! Record which commit the readme was generated from, for readmes distributed apart from the
+//! repository. `{{git-sha}}` is the commit checked out in the project root and `{{git-tag}}` the
+//! output of `git describe --tags`, like `v1.2.0`, or `v1.2.0-3-g1a2b3c4` three commits later.
+//!
+//! Both run `git`, so they fail if command execution is disabled.
+
+use std::process::{Command, Stdio};
+
+use super::Context;
+use super::parser::Tag;
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    if !context.allow_exec {
+        return Err(format!(
+            "`{}` needs to run git, but running commands was disabled with `--no-exec` or \
+             `--sandbox`",
+            tag.source
+        ));
+    }
+
+    let args: &[&str] = match tag.name.as_str() {
+        "git-sha" => &["rev-parse", "HEAD"],
+        _ => &["describe", "--tags"],
+    };
+
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(context.project_root)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Could not run git for `{}`: {}", tag.source, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "`git {}` failed for `{}`: {}",
+            args.join(" "),
+            tag.source,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
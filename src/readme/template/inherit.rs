@@ -0,0 +1,131 @@
+//! Render a template as the parent template it extends
+//!
+//! A template with `{{extends "base.tpl"}}` is rendered as its parent, a file relative to the
+//! project root, with the `{{block name}}...{{/block}}` blocks of the parent replaced by the
+//! blocks of the same name in the child. Blocks the child does not have keep the content of the
+//! parent. Parents can extend other templates in turn, so a workspace can share a base template
+//! that each crate only overrides a few sections of.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+use super::super::Warning;
+use super::parser::{self, Node, Tag, EXTENDS};
+use super::Context;
+
+/// Most templates a template can extend through its parents, which also stops loops
+const MAX_DEPTH: usize = 16;
+
+/// Nodes of the template once merged with its parents, or `None` if it extends no template
+pub fn resolve(nodes: &[Node], context: &mut Context) -> Result<Option<Vec<Node>>, String> {
+    let mut parent = match extends(nodes)? {
+        Some(parent) => parent.to_owned(),
+        None => return Ok(None),
+    };
+    warn_ignored(nodes, context);
+
+    // the parents are written with the same delimiters as the child
+    let delimiters = super::super::delimiters(context.config)?;
+
+    // blocks of the children, the closest child winning
+    let mut overrides = HashMap::new();
+    collect_blocks(nodes, &mut overrides)?;
+
+    for _ in 0..MAX_DEPTH {
+        let path = context.path(&parent)?;
+        let mut content = String::new();
+        File::open(&path)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| {
+                format!("Could not read template '{}': {}", path.to_string_lossy(), e)
+            })?;
+        let nodes = parser::parse(content.trim_end_matches('\n'), &delimiters);
+
+        match extends(&nodes)? {
+            Some(grandparent) => {
+                collect_blocks(&nodes, &mut overrides)?;
+                parent = grandparent.to_owned();
+            }
+            None => return Ok(Some(replace_blocks(nodes, &overrides, &mut Vec::new()))),
+        }
+    }
+
+    Err(format!(
+        "Templates extend more than {} other templates, or extend each other in a loop",
+        MAX_DEPTH
+    ))
+}
+
+/// Template extended by the one made of `nodes`, if any
+fn extends(nodes: &[Node]) -> Result<Option<&str>, String> {
+    let tag = nodes.iter().find_map(|node| match *node {
+        Node::Tag(ref tag) if tag.name == EXTENDS => Some(tag),
+        _ => None,
+    });
+    match tag {
+        Some(tag) => tag
+            .arg(0)
+            .map(Some)
+            .ok_or_else(|| format!("`{}` needs the path of the template to extend", tag.source)),
+        None => Ok(None),
+    }
+}
+
+/// Warn about the text of a child template that is outside of blocks, which is not rendered
+fn warn_ignored(nodes: &[Node], context: &mut Context) {
+    let ignored = nodes.iter().any(|node| match *node {
+        Node::Text(ref text) => !text.trim().is_empty(),
+        Node::Tag(ref tag) => tag.name != EXTENDS,
+        Node::Each(..) => true,
+        Node::Block(..) => false,
+    });
+    if ignored {
+        context.warnings.push(Warning::without_line(
+            "The template extends another one, only its `{{block}}` tags are rendered",
+        ));
+    }
+}
+
+fn block_name(tag: &Tag) -> Result<&str, String> {
+    tag.arg(0).ok_or_else(|| format!("`{}` needs a name", tag.source))
+}
+
+/// Add the top level blocks of a template that are not already overridden
+fn collect_blocks(nodes: &[Node], overrides: &mut HashMap<String, Vec<Node>>) -> Result<(), String> {
+    for node in nodes {
+        if let Node::Block(ref tag, ref body) = *node {
+            overrides.entry(block_name(tag)?.to_owned()).or_insert_with(|| body.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Replace the blocks of the parent with the overrides, including blocks nested in others
+///
+/// `replacing` has the names of the blocks being replaced, so a block that has itself in its
+/// override keeps the content of the parent there instead of repeating forever.
+fn replace_blocks(
+    nodes: Vec<Node>,
+    overrides: &HashMap<String, Vec<Node>>,
+    replacing: &mut Vec<String>,
+) -> Vec<Node> {
+    nodes
+        .into_iter()
+        .map(|node| match node {
+            Node::Block(tag, body) => {
+                let name = tag.arg(0).unwrap_or("").to_owned();
+                let body = match overrides.get(&name) {
+                    Some(body) if !replacing.contains(&name) => body.clone(),
+                    _ => body,
+                };
+                replacing.push(name);
+                let body = replace_blocks(body, overrides, replacing);
+                replacing.pop();
+                Node::Block(tag, body)
+            }
+            Node::Each(tag, body) => Node::Each(tag, replace_blocks(body, overrides, replacing)),
+            node => node,
+        })
+        .collect()
+}
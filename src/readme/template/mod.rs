@@ -0,0 +1,698 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use cargo_info::Cargo;
+use config::Config;
+use super::Warning;
+
+mod parser;
+mod bench;
+mod cache;
+mod cli_help;
+mod cli_reference;
+mod code;
+mod contributors;
+mod crates_io;
+mod date;
+mod demo;
+mod docs_rs;
+mod each;
+mod filters;
+mod forge;
+mod funding;
+mod git;
+mod inherit;
+mod policy;
+mod snippet;
+mod toolchain;
+mod workspace;
+
+use toml;
+
+use super::blocks::Fence;
+use self::parser::{Node, Tag};
+pub use self::contributors::ContributorsCache;
+pub use self::forge::FileUrls;
+pub use self::parser::Delimiters;
+
+/// License added after the docs without a template, unless the `license-text` setting is set
+const LICENSE_TEXT: &str = "License: {{license}}";
+/// Line of the docs replaced by the title when there is no template, instead of adding it first
+const TITLE_MARKER: &str = "<!-- readme:title -->";
+/// Line of the docs replaced by the license when there is no template, instead of adding it last
+const LICENSE_MARKER: &str = "<!-- readme:license -->";
+
+/// Information available to template tags besides the readme, title and license
+pub struct Context<'a> {
+    pub project_root: &'a Path,
+    pub cargo: &'a Cargo,
+    pub config: &'a Config,
+    /// Whether tags are allowed to run commands, like `{{cli-help}}` does
+    pub allow_exec: bool,
+    /// Whether tags can only read files inside the project root
+    pub sandbox: bool,
+    /// Problems found while rendering that do not prevent the template from being rendered
+    pub warnings: Vec<Warning>,
+    /// Contributors of the git repositories listed so far, shared by the crates in each
+    pub contributors: &'a mut ContributorsCache,
+    /// Time of `{{date}}` and `{{year}}`, in seconds since the Unix epoch
+    pub date: u64,
+    /// Whether to warn about the tags that may not render the same from the sources alone
+    pub reproducible: bool,
+    /// Values of the tags given on the command line, by name
+    pub vars: &'a BTreeMap<String, String>,
+    /// Whether tags are allowed to query crates.io for the data of the published crate
+    pub crates_io: bool,
+    /// Whether tags are allowed to query docs.rs for the status of the docs build
+    pub docs_rs: bool,
+}
+
+impl<'a> Context<'a> {
+    /// Path of a file given to a tag, relative to the project root
+    pub fn path(&self, path: &str) -> Result<PathBuf, String> {
+        super::project_path(self.project_root, path, self.sandbox)
+    }
+
+    /// First of the given files found in the crate, or in the directories above it up to the
+    /// root of the git repository, for the files usually kept there, like `SECURITY.md`
+    ///
+    /// In a sandbox, the files are only looked for in the crate.
+    pub fn find_repo_file(&self, candidates: &[&str]) -> Option<PathBuf> {
+        let root = self.project_root.canonicalize().unwrap_or_else(|_| self.project_root.into());
+        for dir in root.ancestors() {
+            let found = candidates.iter().map(|file| dir.join(file)).find(|path| path.is_file());
+            if found.is_some() {
+                return found;
+            }
+            if self.sandbox || dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+}
+
+/// A parsed template, which can be rendered for several crates
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parse a template whose tags are written between the given delimiters
+    pub fn parse(template: &str, delimiters: &Delimiters) -> Self {
+        Template {
+            nodes: parser::parse(template.trim_end_matches('\n'), delimiters),
+        }
+    }
+
+    /// Whether the template has the given tag, not counting the parents it extends, see `resolve`
+    pub fn has_tag(&self, name: &str) -> bool {
+        has_tag(&self.nodes, name)
+    }
+
+    /// The template merged with the parents it extends, or `None` if it extends no template
+    pub fn resolve(&self, context: &mut Context) -> Result<Option<Template>, String> {
+        Ok(inherit::resolve(&self.nodes, context)?.map(|nodes| Template { nodes }))
+    }
+}
+
+/// Renders the template
+///
+/// This is not a full template engine, it just processes a few tags. A template extending another
+/// one must be resolved first, see `Template::resolve`.
+pub fn render(
+    template: Option<&Template>,
+    mut readme: String,
+    context: &mut Context,
+    add_title: bool,
+    add_license: bool,
+) -> Result<String, String> {
+    let title = context.cargo.package.name.as_ref();
+    let license = context.cargo.package.license.as_ref();
+
+    let nodes = template.map(|template| template.nodes.as_slice());
+
+    // source archives, which distributions build from, usually have no git history
+    if context.reproducible {
+        let tags = [
+            ("git-sha", "the git history"),
+            ("git-tag", "the git history"),
+            ("contributors", "the git history"),
+            ("published-version", "crates.io"),
+            ("total-downloads", "crates.io"),
+            ("owners", "crates.io"),
+            ("docsrs-ok", "docs.rs"),
+        ];
+        let nodes = nodes.unwrap_or_default();
+        for (name, source) in tags.iter().filter(|(name, _)| has_tag(nodes, name)) {
+            context.warnings.push(Warning::without_line(format!(
+                "`{{{{{}}}}}` depends on {}, the readme cannot be reproduced from the sources \
+                 alone",
+                name, source
+            )));
+        }
+    }
+
+    docs_rs::check(context)?;
+
+    let mut rendered = match nodes {
+        Some(nodes) => {
+
+            if has_tag(nodes, "license") && !add_license {
+                return Err(
+                    "`{{license}}` was found in template but should not be rendered".to_owned(),
+                );
+            }
+
+            if has_tag(nodes, "crate") && !add_title {
+                return Err(
+                    "`{{crate}}` was found in template but title should not be rendered"
+                        .to_owned(),
+                );
+            }
+
+            // the template places the title and license, not the markers of the docs
+            for marker in &[TITLE_MARKER, LICENSE_MARKER] {
+                readme = place_at_marker(&readme, marker, None).unwrap_or(readme);
+            }
+
+            let title = if add_title { Some(title) } else { None };
+            let license = if add_license {
+                Some(license.unwrap().as_ref())
+            } else {
+                None
+            };
+            process_template(nodes, readme, title, license, context)?
+        }
+        None => {
+            // the docs can mark where the title and license go, instead of the start and the end
+            let heading = format!("# {}", title);
+            let heading = Some(heading.as_str()).filter(|_| add_title);
+            readme = match place_at_marker(&readme, TITLE_MARKER, heading) {
+                Some(placed) => placed,
+                None if add_title => prepend_title(readme, title),
+                None => readme,
+            };
+
+            let text = context.config.license_text.as_deref().unwrap_or(LICENSE_TEXT);
+            let license = license
+                .filter(|_| add_license)
+                .map(|license| text.replace("{{license}}", license));
+            match (place_at_marker(&readme, LICENSE_MARKER, license.as_deref()), license) {
+                (Some(placed), _) => placed,
+                (None, Some(license)) => append_license(readme, &license),
+                (None, None) => readme,
+            }
+        }
+    };
+
+    // the footer is left out when the template already has the links
+    let has_funding = nodes.is_some_and(|nodes| has_tag(nodes, "funding"));
+    if context.config.funding_footer && !has_funding {
+        if let Some(footer) = funding::footer(context) {
+            rendered = format!("{}\n\n{}", rendered.trim_end_matches('\n'), footer);
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// Process the tags of the template
+///
+/// Available tags:
+/// - `{{readme}}` documentation extracted from the rust docs
+/// - `{{crate}}` crate name defined in `Cargo.toml`
+/// - `{{lib}}` name of the library in code, with underscores, honoring `[lib] name`
+/// - `{{license}}` license defined in `Cargo.toml`
+/// - `{{version}}` version defined in `Cargo.toml`
+/// - `{{date "%Y-%m-%d"}}` and `{{year}}` the current date, in the given format, and year
+/// - `{{cli-help}}` help text of the crate binary, or of the given command
+/// - `{{cli-reference "cli.json"}}` commands and options described in a json file
+/// - `{{include-bench "target/criterion"}}` table summarizing Criterion benchmark results
+/// - `{{code "config.toml" lines="1-20"}}` file, or some of its lines, as a code block
+/// - `{{snippet "name"}}` code between `// readme:snippet-begin name` and `// readme:snippet-end`
+/// - `{{demo}}` media listed in the `demo` setting
+/// - `{{funding}}` sponsor links listed in `.github/FUNDING.yml`
+/// - `{{contributors}}` authors of the commits of the git repository, bots left out
+/// - `{{published-version}}`, `{{total-downloads}}` and `{{owners}}` the crate as published to
+///   crates.io, with `--crates-io`, see `crates_io`
+/// - `{{docsrs-ok}}` whether the docs of the latest version built on docs.rs, with `--docs-rs`
+/// - `{{ci-badge}}` and `{{source-url "path"}}` CI badge and file links of the repository, for
+///   the forge hosting it, see `forge`
+/// - `{{security}}` and `{{msrv-policy}}` the security policy and MSRV policy of the repository
+/// - `{{workspace-crates}}` list of the other crates in the workspace
+/// - `{{#each keywords}}...{{/each}}` the text in between for every item of an array, see `each`
+/// - `{{block name}}...{{/block}}` a block that templates with `{{extends "base.tpl"}}` replace,
+///   see `inherit`
+///
+/// The value of a tag can be reshaped by filters, like `{{crate | replace "-" "_"}}`, see the
+/// `filters` module. Other tags get the values given on the command line, and unknown tags are
+/// kept as they are.
+fn process_template(
+    nodes: &[Node],
+    readme: String,
+    title: Option<&str>,
+    license: Option<&str>,
+    context: &mut Context,
+) -> Result<String, String> {
+    if !has_tag(nodes, "readme") {
+        return Err("Missing `{{readme}}` in template".to_owned());
+    }
+
+    if has_tag(nodes, "license") && license.is_none() {
+        return Err(
+            "`{{license}}` was found in template but no license was provided".to_owned(),
+        );
+    }
+
+    if has_tag(nodes, "crate") && title.is_none() {
+        return Err(
+            "`{{crate}}` was found in template but no crate name was provided".to_owned(),
+        );
+    }
+
+    render_nodes(nodes, &readme, title, license, context, None)
+}
+
+/// Render the nodes of the template, or of an `{{#each}}` block for one of its items
+fn render_nodes(
+    nodes: &[Node],
+    readme: &str,
+    title: Option<&str>,
+    license: Option<&str>,
+    context: &mut Context,
+    item: Option<&toml::Value>,
+) -> Result<String, String> {
+    let mut result = String::new();
+    for node in nodes {
+        let tag = match *node {
+            Node::Text(ref text) => {
+                result.push_str(text);
+                continue;
+            }
+            Node::Each(ref tag, ref body) => {
+                for item in each::items(tag, context.cargo)? {
+                    let rendered = render_nodes(body, readme, title, license, context, Some(&item))?;
+                    result.push_str(&rendered);
+                }
+                continue;
+            }
+            Node::Block(_, ref body) => {
+                result.push_str(&render_nodes(body, readme, title, license, context, item)?);
+                continue;
+            }
+            Node::Tag(ref tag) => tag,
+        };
+
+        let value = match tag.name.as_str() {
+            "readme" => readme.to_owned(),
+            "crate" => title.unwrap().to_owned(),
+            "license" => license.unwrap().to_owned(),
+            "lib" => context.cargo.lib_name(),
+            "version" => context.cargo.version().map(|v| v.to_owned()).ok_or_else(|| {
+                "`{{version}}` was found in template but no version was found in Cargo.toml"
+                    .to_owned()
+            })?,
+            "edition" => context.cargo.edition().map(|e| e.to_owned()).ok_or_else(|| {
+                "`{{edition}}` was found in template but the edition is inherited from the \
+                 workspace"
+                    .to_owned()
+            })?,
+            "date" => date::date(tag, context)?,
+            "year" => date::year(context),
+            "toolchain" => toolchain::render(context)?,
+            "git-sha" | "git-tag" => git::render(tag, context)?,
+            "cli-help" => cli_help::render(tag, context)?,
+            "cli-reference" => cli_reference::render(tag, context)?,
+            "include-bench" => bench::render(tag, context)?,
+            "code" => code::render(tag, context)?,
+            "snippet" => snippet::render(tag, context)?,
+            "demo" => demo::render(context),
+            "funding" => funding::render(context),
+            "contributors" => contributors::render(tag, context)?,
+            "published-version" | "total-downloads" | "owners" => crates_io::render(tag, context)?,
+            "docsrs-ok" => docs_rs::render(tag, context)?,
+            "ci-badge" => forge::ci_badge(tag, context)?,
+            "source-url" => forge::source_url(tag, context)?,
+            "security" | "msrv-policy" => policy::render(tag, context)?,
+            "workspace-crates" => workspace::render(tag, context)?,
+            name if name == "this" || name.starts_with("this.") => match item {
+                Some(item) => each::render_item(tag, item)?,
+                None => {
+                    return Err(format!("`{}` is only available in `{{{{#each}}}}`", tag.source))
+                }
+            },
+            "#each" => {
+                return Err(format!("`{}` is never closed with `{{{{/each}}}}`", tag.source))
+            }
+            "/each" => return Err(format!("`{}` closes no `{{{{#each}}}}`", tag.source)),
+            "block" => {
+                return Err(format!("`{}` is never closed with `{{{{/block}}}}`", tag.source))
+            }
+            "/block" => return Err(format!("`{}` closes no `{{{{block}}}}`", tag.source)),
+            "extends" => {
+                return Err(format!("`{}` must be at the top level of the template", tag.source))
+            }
+            name => match context.vars.get(name) {
+                Some(value) => value.clone(),
+                None => {
+                    result.push_str(&tag.source);
+                    continue;
+                }
+            },
+        };
+
+        result.push_str(&filters::apply(value, tag)?);
+    }
+
+    Ok(result)
+}
+
+/// Check if the template contains the given tag
+fn has_tag(nodes: &[Node], name: &str) -> bool {
+    nodes.iter().any(|node| match *node {
+        Node::Tag(Tag { name: ref tag_name, .. }) => tag_name == name,
+        Node::Each(_, ref body) | Node::Block(_, ref body) => has_tag(body, name),
+        _ => false,
+    })
+}
+
+/// Prepend title (crate name) to output string
+fn prepend_title(readme: String, crate_name: &str) -> String {
+    let title = format!("# {}", crate_name);
+    if !readme.trim().is_empty() {
+        format!("{}\n\n{}", title, readme)
+    } else {
+        title
+    }
+}
+
+/// Append license to output string
+fn append_license(readme: String, license: &str) -> String {
+    if !readme.trim().is_empty() {
+        format!("{}\n\n{}", readme, license)
+    } else {
+        license.to_owned()
+    }
+}
+
+/// Put `text` in place of the first line of the readme that is only `marker`, outside of code
+/// blocks, or just remove the marker if there is no text
+///
+/// There is nothing to replace if the readme has no marker. Other lines with the marker are
+/// removed too.
+fn place_at_marker(readme: &str, marker: &str, text: Option<&str>) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut fence: Option<Fence> = None;
+    let mut found = false;
+    for line in readme.split('\n') {
+        match fence {
+            Some(ref open) if open.is_closed_by(line) => fence = None,
+            Some(_) => {}
+            None if line.trim() == marker => {
+                if !found {
+                    lines.extend(text);
+                }
+                found = true;
+                continue;
+            }
+            None => fence = Fence::open(line),
+        }
+        lines.push(line);
+    }
+
+    if found {
+        Some(lines.join("\n"))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use cargo_info::Cargo;
+    use config::Config;
+    use super::{Context, ContributorsCache, Delimiters, Template};
+
+    const CRATE_NAME: &str = "my_crate";
+    const LICENSE: &str = "MPL";
+
+    const TEMPLATE_NO_CRATE_NO_LICENSE: &str = "{{readme}}";
+    const TEMPLATE_CRATE_NO_LICENSE: &str = "# {{crate}}\n\n{{readme}}";
+    const TEMPLATE_NO_CRATE_LICENSE: &str = "{{readme}}\n\nLicense: {{license}}";
+    const TEMPLATE_CRATE_LICENSE: &str = "# {{crate}}\n\n{{readme}}\n\nLicense: {{license}}";
+
+    macro_rules! test_process_template {
+        ( $name:ident,
+          $template:ident,
+          input => $input:expr,
+          with_title => $with_title:expr,
+          with_license => $with_license:expr,
+          expected => $expected:expr) =>
+        {
+            #[test]
+            fn $name() {
+                let input = $input;
+                let title = if $with_title { Some(CRATE_NAME) } else { None };
+                let license = if $with_license { Some(LICENSE) } else { None };
+
+                let cargo = Cargo::default();
+                let config = Config::default();
+                let vars = BTreeMap::new();
+                let mut contributors = ContributorsCache::new();
+                let mut context = Context {
+                    project_root: Path::new("."),
+                    cargo: &cargo,
+                    config: &config,
+                    allow_exec: false,
+                    sandbox: false,
+                    warnings: Vec::new(),
+                    contributors: &mut contributors,
+                    date: 0,
+                    reproducible: false,
+                    vars: &vars,
+                    crates_io: false,
+                    docs_rs: false,
+                };
+
+                let template = Template::parse($template, &Delimiters::default());
+                let result = super::process_template(
+                    &template.nodes, input.into(), title, license, &mut context
+                ).unwrap();
+
+                assert_eq!($expected, result);
+            }
+        };
+
+        ( $name:ident,
+          $template:ident,
+          input => $input:expr,
+          with_title => $with_title:expr,
+          with_license => $with_license:expr,
+          panic => $panic:expr) =>
+        {
+            #[test]
+            #[should_panic(expected = $panic)]
+            fn $name() {
+                let input = $input;
+                let title = if $with_title { Some(CRATE_NAME) } else { None };
+                let license = if $with_license { Some(LICENSE) } else { None };
+
+                let cargo = Cargo::default();
+                let config = Config::default();
+                let vars = BTreeMap::new();
+                let mut contributors = ContributorsCache::new();
+                let mut context = Context {
+                    project_root: Path::new("."),
+                    cargo: &cargo,
+                    config: &config,
+                    allow_exec: false,
+                    sandbox: false,
+                    warnings: Vec::new(),
+                    contributors: &mut contributors,
+                    date: 0,
+                    reproducible: false,
+                    vars: &vars,
+                    crates_io: false,
+                    docs_rs: false,
+                };
+
+                let template = Template::parse($template, &Delimiters::default());
+                super::process_template(
+                    &template.nodes, input.into(), title, license, &mut context
+                ).unwrap();
+            }
+        }
+    }
+
+    // TEMPLATE_NO_CRATE_NO_LICENSE
+
+    // with title with license
+    test_process_template!(
+        process_template_no_crate_no_license_with_title_with_license,
+        TEMPLATE_NO_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => true,
+        expected => "# documentation"
+    );
+
+    // with title without license
+    test_process_template!(
+        process_template_no_crate_no_license_with_title_without_license,
+        TEMPLATE_NO_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => false,
+        expected => "# documentation"
+    );
+
+    // without title with license
+    test_process_template!(
+        process_template_no_crate_no_license_without_title_with_license,
+        TEMPLATE_NO_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => true,
+        expected => "# documentation"
+    );
+
+    // without title without license
+    test_process_template!(
+        process_template_no_crate_no_license_without_title_without_license,
+        TEMPLATE_NO_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => false,
+        expected => "# documentation"
+    );
+
+    // TEMPLATE_CRATE_NO_LICENSE
+
+    // with title with license
+    test_process_template!(
+        process_template_crate_no_license_with_title_with_license,
+        TEMPLATE_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => true,
+        expected => "# my_crate\n\n# documentation"
+    );
+
+    // with title without license
+    test_process_template!(
+        process_template_crate_no_license_with_title_without_license,
+        TEMPLATE_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => false,
+        expected => "# my_crate\n\n# documentation"
+    );
+
+    // without title with license
+    test_process_template!(
+        process_template_crate_no_license_without_title_with_license,
+        TEMPLATE_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => true,
+        panic => "`{{crate}}` was found in template but no crate name was provided"
+    );
+
+    // without title without license
+    test_process_template!(
+        process_template_crate_no_license_without_title_without_license,
+        TEMPLATE_CRATE_NO_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => false,
+        panic => "`{{crate}}` was found in template but no crate name was provided"
+    );
+
+    // TEMPLATE_NO_CRATE_LICENSE
+
+    // with title with license
+    test_process_template!(
+        process_template_no_crate_license_with_title_with_license,
+        TEMPLATE_NO_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => true,
+        expected => "# documentation\n\nLicense: MPL"
+    );
+
+    // with title without license
+    test_process_template!(
+        process_template_no_crate_license_with_title_without_license,
+        TEMPLATE_NO_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => false,
+        panic => "`{{license}}` was found in template but no license was provided"
+    );
+
+    // without title with license
+    test_process_template!(
+        process_template_no_crate_license_without_title_with_license,
+        TEMPLATE_NO_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => true,
+        expected => "# documentation\n\nLicense: MPL"
+    );
+
+    // without title without license
+    test_process_template!(
+        process_template_no_crate_license_without_title_without_license,
+        TEMPLATE_NO_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => false,
+        panic => "`{{license}}` was found in template but no license was provided"
+    );
+
+    // TEMPLATE_CRATE_LICENSE
+
+    // with title with license
+    test_process_template!(
+        process_template_crate_license_with_title_with_license,
+        TEMPLATE_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => true,
+        expected => "# my_crate\n\n# documentation\n\nLicense: MPL"
+    );
+
+    // with title without license
+    test_process_template!(
+        process_template_crate_license_with_title_without_license,
+        TEMPLATE_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => true,
+        with_license => false,
+        panic => "`{{license}}` was found in template but no license was provided"
+    );
+
+    // without title with license
+    test_process_template!(
+        process_template_crate_license_without_title_with_license,
+        TEMPLATE_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => true,
+        panic => "`{{crate}}` was found in template but no crate name was provided"
+    );
+
+    // without title with license
+    test_process_template!(
+        process_template_crate_license_without_title_witout_license,
+        TEMPLATE_CRATE_LICENSE,
+        input => "# documentation",
+        with_title => false,
+        with_license => false,
+        panic => "`{{license}}` was found in template but no license was provided"
+    );
+}
@@ -0,0 +1,686 @@
+//! Parse templates into text and tags
+//!
+//! A tag is written as `{{name}}` and may take arguments, either positional (`{{name "value"}}`)
+//! or named (`{{name key="value"}}`), followed by filters that reshape its value
+//! (`{{name | filter "arg"}}`). Anything between `{{` and `}}` that does not look like a tag is
+//! kept as text.
+//!
+//! To write `{{` without starting a tag, escape it as `\{{`. Everything between `{{{{raw}}}}` and
+//! `{{{{/raw}}}}` is kept as text too.
+//!
+//! The text between `{{#each name}}` and `{{/each}}` is a block, repeated for every item of an
+//! array. The text between `{{block name}}` and `{{/block}}` is a named block, which templates
+//! extending this one with `{{extends "base.tpl"}}` can replace. Block tags and `{{extends}}`
+//! alone on their line are removed along with the line, so they can be put on lines of their own
+//! without leaving blank lines in the readme.
+//!
+//! Comments, written `{{! note }}`, or `{{!-- note --}}` to be able to contain `}}`, are left out
+//! of the readme, along with their line when they are alone on it.
+//!
+//! A tag starting with `{{-` removes the whitespace before it, line breaks included, and a tag
+//! ending with `-}}` the whitespace after it, like `{{- crate -}}` or `{{-! note -}}`.
+//!
+//! Templates whose text has `{{` in it, like Helm or Jinja examples, can use other delimiters,
+//! like `<% crate %>`, which then replace `{{` and `}}` everywhere above.
+
+/// Name of the raw blocks, `{{{{raw}}}}` with the default delimiters
+const RAW: &str = "raw";
+const EACH_START: &str = "#each";
+const EACH_END: &str = "/each";
+const BLOCK_START: &str = "block";
+const BLOCK_END: &str = "/block";
+pub const EXTENDS: &str = "extends";
+/// Name of the tag standing for a comment while parsing, before comments are removed
+const COMMENT: &str = "!";
+/// Marker of the tags that trim the whitespace around them, like `{{- tag -}}`
+const TRIM: &str = "-";
+
+/// Strings opening and closing the tags of a template, `{{` and `}}` by default
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Delimiters {
+    pub open: String,
+    pub close: String,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Delimiters {
+            open: "{{".to_owned(),
+            close: "}}".to_owned(),
+        }
+    }
+}
+
+/// A piece of a parsed template
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Text(String),
+    Tag(Tag),
+    /// A block repeated for every item of the array named by the `{{#each}}` tag
+    Each(Tag, Vec<Node>),
+    /// A block named by the `{{block}}` tag, which templates extending this one can replace
+    Block(Tag, Vec<Node>),
+}
+
+/// A tag found in the template
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub args: Vec<String>,
+    pub named_args: Vec<(String, String)>,
+    pub filters: Vec<Filter>,
+    /// Line of the template where the tag starts, starting at 1
+    pub line: usize,
+    /// The tag as written in the template
+    pub source: String,
+}
+
+/// A filter applied to the value of a tag
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl Tag {
+    /// Get a positional argument
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(|arg| arg.as_str())
+    }
+
+    /// Get a named argument
+    pub fn named_arg(&self, name: &str) -> Option<&str> {
+        self.named_args
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Split the template into text and tags
+pub fn parse(template: &str, delimiters: &Delimiters) -> Vec<Node> {
+    let (open, close) = (delimiters.open.as_str(), delimiters.close.as_str());
+    let raw_start = format!("{0}{0}{1}{2}{2}", open, RAW, close);
+    let raw_end = format!("{0}{0}/{1}{2}{2}", open, RAW, close);
+    let trim_end = format!("{}{}", TRIM, close);
+    let comment_end = format!("--{}", close);
+
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+    let mut line = 1;
+    // whether the previous tag ended with `-}}`, trimming the whitespace after it
+    let mut trim_next = false;
+
+    while let Some(start) = rest.find(open) {
+        let (before, after) = rest.split_at(start);
+        line += before.matches('\n').count();
+        let before = if trim_next { before.trim_start() } else { before };
+        trim_next = false;
+
+        // escaped delimiter
+        if let Some(before) = before.strip_suffix('\\') {
+            text.push_str(before);
+            text.push_str(open);
+            rest = &after[open.len()..];
+            continue;
+        }
+        text.push_str(before);
+
+        // raw block, kept as is until its end or the end of the template
+        if let Some(raw) = after.strip_prefix(raw_start.as_str()) {
+            let (raw, next) = match raw.find(&raw_end) {
+                Some(end) => (&raw[..end], &raw[end + raw_end.len()..]),
+                None => (raw, ""),
+            };
+            text.push_str(raw);
+            line += raw.matches('\n').count();
+            rest = next;
+            continue;
+        }
+
+        let end = match after[open.len()..].find(close) {
+            Some(end) => open.len() + end,
+            None => {
+                rest = after;
+                break;
+            }
+        };
+
+        // `{{-` trims the whitespace before the tag and `-}}` the whitespace after it
+        let trim_before = after[open.len()..].starts_with(TRIM);
+        let content_start = open.len() + if trim_before { TRIM.len() } else { 0 };
+
+        // comments are kept as tags until the lines they are alone on are removed, the ones
+        // written `{{!-- --}}` cannot trim the whitespace after them
+        let (source, tag, trim_after) = if after[content_start..].starts_with(COMMENT) {
+            let (source, trim_after) = match after[content_start..].strip_prefix("!--") {
+                Some(comment) => match comment.find(&comment_end) {
+                    Some(end) => {
+                        (&after[..content_start + "!--".len() + end + comment_end.len()], false)
+                    }
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                },
+                None => {
+                    let source = &after[..end + close.len()];
+                    (source, source.ends_with(&trim_end))
+                }
+            };
+            let tag = Tag {
+                name: COMMENT.to_owned(),
+                args: Vec::new(),
+                named_args: Vec::new(),
+                filters: Vec::new(),
+                line,
+                source: source.to_owned(),
+            };
+            (source, Some(tag), trim_after)
+        } else {
+            let source = &after[..end + close.len()];
+            let trim_after = end > content_start && source.ends_with(&trim_end);
+            let content_end = if trim_after { end - TRIM.len() } else { end };
+            let tag = parse_tag(&source[content_start..content_end], source, line);
+            (source, tag, trim_after)
+        };
+
+        match tag {
+            Some(tag) => {
+                if trim_before {
+                    text.truncate(text.trim_end().len());
+                }
+                trim_next = trim_after;
+                if !text.is_empty() {
+                    nodes.push(Node::Text(text.split_off(0)));
+                }
+                nodes.push(Node::Tag(tag));
+            }
+            None => text.push_str(source),
+        }
+
+        line += source.matches('\n').count();
+        rest = &after[source.len()..];
+    }
+
+    let rest = if trim_next { rest.trim_start() } else { rest };
+    text.push_str(rest);
+    if !text.is_empty() {
+        nodes.push(Node::Text(text));
+    }
+
+    remove_standalone_lines(&mut nodes);
+    nest_blocks(nodes)
+}
+
+/// Name of the tag closing the block opened by a tag named `name`, if it opens one
+fn block_end(name: &str) -> Option<&'static str> {
+    match name {
+        EACH_START => Some(EACH_END),
+        BLOCK_START => Some(BLOCK_END),
+        _ => None,
+    }
+}
+
+fn is_block_tag(node: &Node) -> bool {
+    match *node {
+        Node::Tag(ref tag) => [EACH_START, EACH_END, BLOCK_START, BLOCK_END, EXTENDS, COMMENT]
+            .contains(&tag.name.as_str()),
+        _ => false,
+    }
+}
+
+/// Remove the lines that only have a block tag, `{{extends}}` or a comment, keeping the tag
+fn remove_standalone_lines(nodes: &mut [Node]) {
+    let text = |node: Option<&Node>| match node {
+        Some(Node::Text(ref text)) => Some(text.clone()),
+        _ => None,
+    };
+
+    // found before changing anything, since a line can be between two block tags
+    let standalone: Vec<usize> = (0..nodes.len())
+        .filter(|&i| is_block_tag(&nodes[i]))
+        .filter(|&i| {
+            let line_start = match text(i.checked_sub(1).and_then(|i| nodes.get(i))) {
+                Some(before) => {
+                    let line = &before[before.rfind('\n').map_or(0, |n| n + 1)..];
+                    line.trim().is_empty() && (before.contains('\n') || i == 1)
+                }
+                None => i == 0,
+            };
+            let line_end = match text(nodes.get(i + 1)) {
+                Some(after) => {
+                    after.split('\n').next().unwrap().trim().is_empty()
+                        && (after.contains('\n') || i + 2 == nodes.len())
+                }
+                None => i + 1 == nodes.len(),
+            };
+            line_start && line_end
+        })
+        .collect();
+
+    for i in standalone {
+        if let Some(&mut Node::Text(ref mut before)) = i.checked_sub(1).and_then(|i| nodes.get_mut(i))
+        {
+            let start = before.rfind('\n').map_or(0, |n| n + 1);
+            before.truncate(start);
+        }
+        if let Some(&mut Node::Text(ref mut after)) = nodes.get_mut(i + 1) {
+            let end = after.find('\n').map_or(after.len(), |n| n + 1);
+            after.replace_range(..end, "");
+        }
+    }
+}
+
+/// Put the nodes between `{{#each}}` and `{{/each}}`, or `{{block}}` and `{{/block}}`, in a block
+///
+/// Block tags that are not matched are left as tags, for rendering to report them. Comments are
+/// removed, joining the text around them.
+fn nest_blocks(nodes: Vec<Node>) -> Vec<Node> {
+    let mut stack: Vec<(Tag, Vec<Node>)> = Vec::new();
+    let mut result = Vec::new();
+    for node in nodes {
+        let closes_block = |tag: &Tag, stack: &[(Tag, Vec<Node>)]| {
+            stack.last().and_then(|(open, _)| block_end(&open.name)) == Some(tag.name.as_str())
+        };
+        match node {
+            Node::Tag(tag) if block_end(&tag.name).is_some() => stack.push((tag, Vec::new())),
+            Node::Tag(ref tag) if closes_block(tag, &stack) => {
+                let (open, body) = stack.pop().unwrap();
+                let block = if open.name == EACH_START {
+                    Node::Each(open, body)
+                } else {
+                    Node::Block(open, body)
+                };
+                match stack.last_mut() {
+                    Some(&mut (_, ref mut parent)) => parent.push(block),
+                    None => result.push(block),
+                }
+            }
+            Node::Text(ref text) if text.is_empty() => {}
+            Node::Tag(ref tag) if tag.name == COMMENT => {}
+            Node::Text(text) => {
+                let parent = match stack.last_mut() {
+                    Some(&mut (_, ref mut parent)) => parent,
+                    None => &mut result,
+                };
+                match parent.last_mut() {
+                    Some(&mut Node::Text(ref mut before)) => before.push_str(&text),
+                    _ => parent.push(Node::Text(text)),
+                }
+            }
+            node => match stack.last_mut() {
+                Some(&mut (_, ref mut parent)) => parent.push(node),
+                None => result.push(node),
+            },
+        }
+    }
+
+    // blocks that are never closed
+    while let Some((open, body)) = stack.pop() {
+        let parent = match stack.last_mut() {
+            Some(&mut (_, ref mut parent)) => parent,
+            None => &mut result,
+        };
+        parent.push(Node::Tag(open));
+        parent.extend(body);
+    }
+
+    result
+}
+
+/// Parse the content of a `{{...}}` tag, returning `None` if it is not a valid tag
+fn parse_tag(content: &str, source: &str, line: usize) -> Option<Tag> {
+    let mut tokens = tokenize(content)?.into_iter();
+
+    let name = match tokens.next() {
+        Some(Token::Bare(name)) => name,
+        _ => return None,
+    };
+
+    if !is_name(&name) && ![EACH_START, EACH_END, BLOCK_END].contains(&name.as_str()) {
+        return None;
+    }
+
+    let mut tag = Tag {
+        name,
+        args: Vec::new(),
+        named_args: Vec::new(),
+        filters: Vec::new(),
+        line,
+        source: source.to_owned(),
+    };
+
+    let mut has_filters = false;
+    for token in tokens.by_ref() {
+        match token {
+            Token::Bare(value) | Token::Quoted(value) => tag.args.push(value),
+            Token::Named(key, value) => tag.named_args.push((key, value)),
+            Token::Pipe => {
+                has_filters = true;
+                break;
+            }
+        }
+    }
+
+    if !has_filters {
+        return Some(tag);
+    }
+
+    // everything after the first `|` are filters, separated by `|`
+    let mut filter: Option<Filter> = None;
+    for token in tokens.chain(Some(Token::Pipe)) {
+        match (token, filter.as_mut()) {
+            (Token::Bare(name), None) if is_name(&name) => {
+                filter = Some(Filter { name, args: Vec::new() });
+            }
+            (Token::Bare(value), Some(filter)) | (Token::Quoted(value), Some(filter)) => {
+                filter.args.push(value);
+            }
+            (Token::Pipe, Some(_)) => tag.filters.push(filter.take().unwrap()),
+            _ => return None,
+        }
+    }
+
+    Some(tag)
+}
+
+/// Names are words, with `-` and `_`, and can have fields, like `this.name`
+fn is_name(name: &str) -> bool {
+    !name.starts_with('.')
+        && !name.ends_with('.')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+enum Token {
+    Bare(String),
+    Quoted(String),
+    Named(String, String),
+    Pipe,
+}
+
+/// Split the content of a tag into tokens, returning `None` on an unterminated string
+fn tokenize(content: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        match chars.peek() {
+            None => break,
+            Some(&'"') => {
+                chars.next();
+                tokens.push(Token::Quoted(read_quoted(&mut chars)?));
+            }
+            Some(&'|') => {
+                chars.next();
+                tokens.push(Token::Pipe);
+            }
+            Some(_) => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '|' {
+                        break;
+                    }
+                    chars.next();
+                    if c == '=' && !word.is_empty() {
+                        let value = if chars.peek() == Some(&'"') {
+                            chars.next();
+                            read_quoted(&mut chars)?
+                        } else {
+                            let mut value = String::new();
+                            while chars.peek().is_some_and(|&c| !c.is_whitespace() && c != '|') {
+                                value.push(chars.next().unwrap());
+                            }
+                            value
+                        };
+                        tokens.push(Token::Named(word.split_off(0), value));
+                        break;
+                    }
+                    word.push(c);
+                }
+                if !word.is_empty() {
+                    tokens.push(Token::Bare(word));
+                }
+            }
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Read a string until the closing quote, handling `\"` and `\\` escapes
+fn read_quoted<I: Iterator<Item = char>>(chars: &mut I) -> Option<String> {
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                c @ '"' | c @ '\\' => value.push(c),
+                c => {
+                    value.push('\\');
+                    value.push(c);
+                }
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(template: &str) -> Vec<Node> {
+        super::parse(template, &Delimiters::default())
+    }
+
+    fn tag(source: &str, name: &str, args: &[&str], named_args: &[(&str, &str)], line: usize) -> Node {
+        Node::Tag(Tag {
+            name: name.to_owned(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            named_args: named_args
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+            filters: Vec::new(),
+            line,
+            source: source.to_owned(),
+        })
+    }
+
+    #[test]
+    fn parse_text_and_tags() {
+        let result = parse("# {{crate}}\n\n{{readme}}\n");
+        let expected = vec![
+            Node::Text("# ".to_owned()),
+            tag("{{crate}}", "crate", &[], &[], 1),
+            Node::Text("\n\n".to_owned()),
+            tag("{{readme}}", "readme", &[], &[], 3),
+            Node::Text("\n".to_owned()),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_tag_arguments() {
+        let input = r#"{{ code "src/main.rs" lang="rust" lines=1-10 "with \"quotes\"" }}"#;
+        let result = parse(input);
+        let expected = vec![tag(
+            input,
+            "code",
+            &["src/main.rs", "with \"quotes\""],
+            &[("lang", "rust"), ("lines", "1-10")],
+            1,
+        )];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_tag_filters() {
+        let input = r#"{{crate | replace "-" "_" |upper}}"#;
+        let result = parse(input);
+        let expected = vec![Node::Tag(Tag {
+            name: "crate".to_owned(),
+            args: Vec::new(),
+            named_args: Vec::new(),
+            filters: vec![
+                Filter {
+                    name: "replace".to_owned(),
+                    args: vec!["-".to_owned(), "_".to_owned()],
+                },
+                Filter {
+                    name: "upper".to_owned(),
+                    args: Vec::new(),
+                },
+            ],
+            line: 1,
+            source: input.to_owned(),
+        })];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_keep_invalid_filters_as_text() {
+        let input = "{{crate | }} {{crate | \"quoted\"}} {{crate || upper}}";
+        let result = parse(input);
+        assert_eq!(result, vec![Node::Text(input.to_owned())]);
+    }
+
+    #[test]
+    fn parse_escaped_braces() {
+        let result = parse("\\{{crate}} is replaced by {{crate}}");
+        let expected = vec![
+            Node::Text("{{crate}} is replaced by ".to_owned()),
+            tag("{{crate}}", "crate", &[], &[], 1),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_raw_block() {
+        let result = parse("{{{{raw}}}}\n# {{crate}}\n{{{{/raw}}}}\n{{readme}}");
+        let expected = vec![
+            Node::Text("\n# {{crate}}\n\n".to_owned()),
+            tag("{{readme}}", "readme", &[], &[], 4),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_keep_non_tags_as_text() {
+        let input = "{{ .Values.image }} {{}} {{ \"unterminated }} {{unclosed";
+        let result = parse(input);
+        assert_eq!(result, vec![Node::Text(input.to_owned())]);
+    }
+
+    #[test]
+    fn parse_each_block() {
+        let result = parse("Authors:\n{{#each authors}}\n- {{this}}\n{{/each}}\nEnd");
+        let expected = vec![
+            Node::Text("Authors:\n".to_owned()),
+            Node::Each(
+                match tag("{{#each authors}}", "#each", &["authors"], &[], 2) {
+                    Node::Tag(open) => open,
+                    _ => unreachable!(),
+                },
+                vec![
+                    Node::Text("- ".to_owned()),
+                    tag("{{this}}", "this", &[], &[], 3),
+                    Node::Text("\n".to_owned()),
+                ],
+            ),
+            Node::Text("End".to_owned()),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_inline_and_unclosed_each() {
+        let result = parse("{{#each keywords}}`{{this}}` {{/each}}");
+        match result.as_slice() {
+            [Node::Each(_, body)] => assert_eq!(body.len(), 3),
+            _ => panic!("not a block: {:?}", result),
+        }
+
+        let result = parse("{{#each keywords}}{{this}}");
+        let expected = vec![
+            tag("{{#each keywords}}", "#each", &["keywords"], &[], 1),
+            tag("{{this}}", "this", &[], &[], 1),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_named_blocks() {
+        let result = parse("{{extends \"base.tpl\"}}\n{{block intro}}\nHello{{/each}}\n{{/block}}\n");
+        let expected = vec![
+            tag("{{extends \"base.tpl\"}}", "extends", &["base.tpl"], &[], 1),
+            Node::Block(
+                match tag("{{block intro}}", "block", &["intro"], &[], 2) {
+                    Node::Tag(open) => open,
+                    _ => unreachable!(),
+                },
+                vec![
+                    Node::Text("Hello".to_owned()),
+                    tag("{{/each}}", "/each", &[], &[], 3),
+                    Node::Text("\n".to_owned()),
+                ],
+            ),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_comments() {
+        let result = parse("{{! title }}
+# {{crate}}{{!-- not {{readme}} --}}!
+  {{! note }}  
+{{readme}}");
+        let expected = vec![
+            Node::Text("# ".to_owned()),
+            tag("{{crate}}", "crate", &[], &[], 2),
+            Node::Text("!\n".to_owned()),
+            tag("{{readme}}", "readme", &[], &[], 4),
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn parse_whitespace_control() {
+        let result = parse(concat!(
+            "a\n\n{{- crate -}}\n\n",
+            "b {{-! note -}} c {{-!-- note --}}  {{license -}}",
+        ));
+        let expected = vec![
+            Node::Text("a".to_owned()),
+            tag("{{- crate -}}", "crate", &[], &[], 3),
+            Node::Text("bc  ".to_owned()),
+            tag("{{license -}}", "license", &[], &[], 5),
+        ];
+        assert_eq!(result, expected);
+
+        let input = "{{-}} {{--}} {{- -}}";
+        assert_eq!(parse(input), vec![Node::Text(input.to_owned())]);
+    }
+
+    #[test]
+    fn parse_custom_delimiters() {
+        let delimiters = Delimiters {
+            open: "<%".to_owned(),
+            close: "%>".to_owned(),
+        };
+        let template = "image: {{ .Values.image }}\n<%! note %>\n<%- readme %> \\<%>";
+        let result = super::parse(template, &delimiters);
+        let expected = vec![
+            Node::Text("image: {{ .Values.image }}\n".to_owned()),
+            tag("<%- readme %>", "readme", &[], &[], 3),
+            Node::Text(" <%>".to_owned()),
+        ];
+        assert_eq!(result, expected);
+    }
+}
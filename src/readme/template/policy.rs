@@ -0,0 +1,80 @@
+//! Render the `{{security}}` and `{{msrv-policy}}` tags
+//!
+//! Embeds the security policy, `SECURITY.md`, or the policy on the minimum supported Rust
+//! version, `MSRV.md`, so sections that compliance requires in every readme come from the one
+//! file kept in the repository. The files are looked for at the root of the crate, in `.github/`
+//! and in `docs/`, then in the same places up to the root of the repository.
+//!
+//! Named arguments:
+//! - `mode="link"` links to the file instead of embedding it
+
+use std::fs::File;
+use std::io::Read;
+
+use workspace;
+
+use super::Context;
+use super::parser::Tag;
+
+const SECURITY_FILES: &[&str] = &["SECURITY.md", ".github/SECURITY.md", "docs/SECURITY.md"];
+const MSRV_FILES: &[&str] = &[
+    "MSRV.md",
+    "MSRV-POLICY.md",
+    ".github/MSRV.md",
+    ".github/MSRV-POLICY.md",
+    "docs/MSRV.md",
+    "docs/MSRV-POLICY.md",
+];
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    let (candidates, name) = match tag.name.as_str() {
+        "security" => (SECURITY_FILES, "SECURITY.md"),
+        _ => (MSRV_FILES, "MSRV.md"),
+    };
+    let path = context.find_repo_file(candidates).ok_or_else(|| {
+        format!("`{}` was found in template but no {} was found", tag.source, name)
+    })?;
+
+    match tag.named_arg("mode") {
+        None | Some("embed") => {}
+        Some("link") => {
+            let root = context.project_root.canonicalize().map_err(|e| format!("{}", e))?;
+            let file = path.file_name().unwrap_or_default().to_string_lossy();
+            return Ok(format!("[{}]({})", file, workspace::relative_path(&root, &path)));
+        }
+        Some(other) => {
+            return Err(format!(
+                "Invalid mode '{}' in `{}`, expected \"embed\" or \"link\"",
+                other, tag.source
+            ))
+        }
+    }
+
+    let mut content = String::new();
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut content))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+    Ok(without_title(&content))
+}
+
+/// The policy without its `#` title, since the template gives it a heading of its own
+fn without_title(content: &str) -> String {
+    let content = content.trim();
+    let body = match content.lines().next() {
+        Some(first) if first.starts_with("# ") => &content[first.len()..],
+        _ => content,
+    };
+    body.trim().replace("\r\n", "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::without_title;
+
+    #[test]
+    fn title_is_left_out() {
+        let policy = "# Security Policy\n\nReport vulnerabilities to security@example.com.\n";
+        assert_eq!("Report vulnerabilities to security@example.com.", without_title(policy));
+        assert_eq!("## Reporting\n\nBy email", without_title("## Reporting\n\nBy email\n"));
+    }
+}
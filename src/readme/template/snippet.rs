@@ -0,0 +1,176 @@
+//! Render the `{{snippet "name"}}` tag
+//!
+//! Embeds code marked in the crate sources, so the readme can show real code that is compiled
+//! and tested along with the crate:
+//!
+//! ```rust,ignore
+//! // readme:snippet-begin connect
+//! let client = Client::connect("localhost:8080")?;
+//! // readme:snippet-end
+//! ```
+//!
+//! The `.rs` files under the project root are searched, except in hidden directories and
+//! `target`. The common indentation of the snippet is removed.
+
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::Context;
+use super::code;
+use super::parser::Tag;
+
+const SNIPPET_BEGIN: &str = "// readme:snippet-begin";
+const SNIPPET_END: &str = "// readme:snippet-end";
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    let name = tag
+        .arg(0)
+        .ok_or_else(|| format!("Missing snippet name in `{}`", tag.source))?;
+
+    let mut files = Vec::new();
+    find_sources(context.project_root, &mut files)?;
+
+    let mut found: Option<(PathBuf, String)> = None;
+    for file in files {
+        let mut content = String::new();
+        File::open(&file)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .map_err(|e| format!("Could not read file '{}': {}", file.to_string_lossy(), e))?;
+
+        let display = file.strip_prefix(context.project_root).unwrap_or(&file).to_path_buf();
+        if let Some(snippet) = find_snippet(&content, name)
+            .map_err(|e| format!("{}: {}", display.to_string_lossy(), e))?
+        {
+            if let Some((ref previous, _)) = found {
+                return Err(format!(
+                    "Snippet '{}' is defined in both '{}' and '{}'",
+                    name,
+                    previous.to_string_lossy(),
+                    display.to_string_lossy()
+                ));
+            }
+            found = Some((display, snippet));
+        }
+    }
+
+    match found {
+        Some((_, snippet)) => {
+            Ok(code::fenced(tag.named_arg("lang").unwrap_or("rust"), &snippet))
+        }
+        None => Err(format!("Snippet '{}' not found", name)),
+    }
+}
+
+/// Collect the `.rs` files under `dir`, sorted by path
+fn find_sources(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Could not read directory '{}': {}", dir.to_string_lossy(), e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" {
+                find_sources(&path, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the snippet with the given name in the content of a file
+fn find_snippet(content: &str, name: &str) -> Result<Option<String>, String> {
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let begin = match line.trim().strip_prefix(SNIPPET_BEGIN) {
+            Some(begin) => begin.trim(),
+            None => continue,
+        };
+        if begin != name {
+            continue;
+        }
+
+        let mut snippet = Vec::new();
+        loop {
+            match lines.next() {
+                Some(line) if line.trim() == SNIPPET_END => break,
+                Some(line) => snippet.push(line),
+                None => return Err(format!("snippet '{}' is never closed", name)),
+            }
+        }
+
+        return Ok(Some(dedent(&snippet)));
+    }
+
+    Ok(None)
+}
+
+/// Remove the indentation common to all non blank lines
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| if line.len() >= indent { &line[indent..] } else { line.trim_start() })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = concat_lines!(
+        "fn main() {",
+        "    // readme:snippet-begin connect",
+        "    let client = Client::connect(\"localhost\");",
+        "    if client.is_ok() {",
+        "        println!(\"connected\");",
+        "    }",
+        "    // readme:snippet-end",
+        "}",
+    );
+
+    #[test]
+    fn find_and_dedent_snippet() {
+        let expected = concat!(
+            "let client = Client::connect(\"localhost\");\n",
+            "if client.is_ok() {\n",
+            "    println!(\"connected\");\n",
+            "}",
+        );
+
+        assert_eq!(Ok(Some(expected.to_owned())), find_snippet(SOURCE, "connect"));
+    }
+
+    #[test]
+    fn snippet_not_in_file() {
+        assert_eq!(Ok(None), find_snippet(SOURCE, "other"));
+    }
+
+    #[test]
+    fn snippet_never_closed() {
+        let source = "// readme:snippet-begin open\nlet x = 1;\n";
+        assert_eq!(
+            Err("snippet 'open' is never closed".to_owned()),
+            find_snippet(source, "open")
+        );
+    }
+}
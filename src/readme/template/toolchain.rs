@@ -0,0 +1,85 @@
+//! Render the `{{toolchain}}` tag
+//!
+//! The toolchain is the `channel` of the `rust-toolchain.toml` file of the project, or of its
+//! older form `rust-toolchain`, which can also contain just the channel. Like rustup does, the file
+//! is searched in the project root and then in its parents.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use toml;
+
+use super::Context;
+
+const TOOLCHAIN_FILES: &[&str] = &["rust-toolchain", "rust-toolchain.toml"];
+
+pub fn render(context: &Context) -> Result<String, String> {
+    // the parents of the project root are out of reach in a sandbox
+    let depth = if context.sandbox { 1 } else { usize::MAX };
+    for dir in context.project_root.ancestors().take(depth) {
+        for name in TOOLCHAIN_FILES {
+            let path = dir.join(name);
+            if !path.is_file() {
+                continue;
+            }
+
+            let mut content = String::new();
+            File::open(&path)
+                .and_then(|mut f| f.read_to_string(&mut content))
+                .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+            return channel(&content).ok_or_else(|| {
+                format!("No toolchain channel found in '{}'", display(&path, context))
+            });
+        }
+    }
+
+    Err("`{{toolchain}}` was found in template but no rust-toolchain file was found".to_owned())
+}
+
+/// Channel from the content of a toolchain file
+fn channel(content: &str) -> Option<String> {
+    if let Ok(toml::Value::Table(table)) = content.parse::<toml::Value>() {
+        return table
+            .get("toolchain")
+            .and_then(|toolchain| toolchain.get("channel"))
+            .and_then(|channel| channel.as_str())
+            .map(|channel| channel.to_owned());
+    }
+
+    // the legacy format is just the channel on a line
+    let mut lines = content.lines().map(|line| line.trim()).filter(|line| !line.is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(channel), None) if !channel.contains(['=', '[']) => Some(channel.to_owned()),
+        _ => None,
+    }
+}
+
+fn display(path: &Path, context: &Context) -> String {
+    path.strip_prefix(context.project_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::channel;
+
+    #[test]
+    fn channel_from_toml() {
+        let content = concat_lines!(
+            "[toolchain]",
+            "channel = \"1.70.0\"",
+            "components = [\"clippy\"]",
+        );
+        assert_eq!(Some("1.70.0".to_owned()), channel(content));
+        assert_eq!(None, channel("[toolchain]\ncomponents = [\"clippy\"]\n"));
+    }
+
+    #[test]
+    fn channel_from_legacy_file() {
+        assert_eq!(Some("nightly-2024-01-01".to_owned()), channel("nightly-2024-01-01\n"));
+        assert_eq!(None, channel("nightly\nstable\n"));
+    }
+}
@@ -0,0 +1,66 @@
+//! Render the `{{workspace-crates}}` tag
+//!
+//! Lists the other crates of the workspace with their descriptions, linking to their
+//! directories, so each crate readme can advertise the rest of the family. With
+//! `links="crates.io"`, crates that can be published link to their crates.io page instead.
+
+use workspace;
+
+use super::Context;
+use super::parser::Tag;
+
+pub fn render(tag: &Tag, context: &Context) -> Result<String, String> {
+    let link_crates_io = match tag.named_arg("links") {
+        None | Some("path") => false,
+        Some("crates.io") => true,
+        Some(other) => {
+            return Err(format!(
+                "Invalid links '{}' in `{}`, expected \"path\" or \"crates.io\"",
+                other, tag.source
+            ))
+        }
+    };
+
+    if context.sandbox {
+        return Err(format!(
+            "`{}` reads the other crates of the workspace, which is not allowed with `--sandbox`",
+            tag.source
+        ));
+    }
+
+    let project_root = context
+        .project_root
+        .canonicalize()
+        .map_err(|e| format!("{}", e))?;
+    let workspace_root = workspace::find_workspace_root(&project_root).ok_or_else(|| {
+        format!("`{}` was found in template but the crate is not in a workspace", tag.source)
+    })?;
+
+    let mut members = workspace::members(&workspace_root)?;
+    members.retain(|member| member.path != project_root);
+    members.sort_by(|a, b| a.cargo.package.name.cmp(&b.cargo.package.name));
+
+    let lines: Vec<String> = members
+        .iter()
+        .map(|member| {
+            let name = &member.cargo.package.name;
+            let link = if link_crates_io && member.cargo.is_publishable() {
+                format!("https://crates.io/crates/{}", name)
+            } else {
+                workspace::relative_path(&project_root, &member.path)
+            };
+
+            match member.cargo.package.description {
+                Some(ref description) => format!(
+                    "- [{}]({}): {}",
+                    name,
+                    link,
+                    description.split_whitespace().collect::<Vec<_>>().join(" ")
+                ),
+                None => format!("- [{}]({})", name, link),
+            }
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
@@ -1,27 +1,51 @@
 //! Transform code blocks from rustdoc into markdown
 //!
 //! Rewrite code block start tags, changing rustdoc into equivalent in markdown:
-//! - "```", "```no_run", "```ignore" and "```should_panic" are converted to "```rust"
-//! - markdown heading are indentend to be one level lower, so the crate name is at the top level
+//! - "```", "```no_run", "```ignore" and "```should_panic" are converted to "```rust", the
+//!   label can be changed, like "```rs", and the attributes kept, like "```rust,no_run"
+//! - markdown heading are indentend to be one level lower, so the crate name is at the top level,
+//!   only lines starting with one to six `#` followed by a space are headings, like in CommonMark
+//! - headings can be renamed, so the readme can use different terms than docs.rs, and prefixed,
+//!   like "🚀 Examples", for the style of the readme without changing the docs
+//!
+//! Code blocks are found as described in `blocks`, their indentation is kept in the readme.
+//! Hidden lines of rust code blocks can be indented by any whitespace, including tabs, like
+//! rustdoc allows. Headings in block quotes and list items are indented too.
+//!
+//! Problems found along the way, like code blocks that are never closed, are collected as
+//! warnings pointing to the line of the source file where they were found.
 
+use std::collections::BTreeMap;
 use std::iter::{Iterator, IntoIterator};
 
-use regex::Regex;
+use super::Warning;
+use super::blocks::{Block, Blocks};
+use super::extract::Line;
+use super::matchers::Matchers;
 
-const REGEX_CODE_RUST: &'static str = r"^```(rust|((rust,)?(no_run|ignore|should_panic)))?$";
-const REGEX_CODE_TEXT: &'static str = r"^```text$";
-const REGEX_CODE_OTHER: &'static str = r"^```\w[\w,\+]*$";
+/// Info strings of the code blocks that rustdoc considers rust
+pub const REGEX_CODE_RUST: &str = r"^(rust|((rust,)?(no_run|ignore|should_panic)))?$";
+pub const REGEX_CODE_TEXT: &str = r"^text$";
+pub const REGEX_HEADING: &str = r"^( {0,3}#{1,6}(?:[ \t]+|$))(.*?)(\s*)$";
+pub const REGEX_HEADING_MAX_LEVEL: &str = r"^ {0,3}######(\s|$)";
+pub const REGEX_INTRA_DOC_LINK: &str = r"\]\(((crate|self|super)::[\w:]*|[A-Za-z_]\w*::[\w:]+)\)";
 
 pub trait DocTransform {
-    fn transform_doc(self, indent_headings: bool) -> DocTransformer<Self>
+    fn transform_doc(self, indent_headings: bool, matchers: &Matchers) -> DocTransformer<Self>
     where
-        Self: Sized + Iterator<Item = String>,
+        Self: Sized + Iterator<Item = Line>,
     {
-        DocTransformer::new(self, indent_headings)
+        DocTransformer::new(self, indent_headings, matchers)
     }
 }
 
-impl<I: Iterator<Item = String>> DocTransform for I {}
+impl<I: Iterator<Item = Line>> DocTransform for I {}
+
+/// Whether a line of a rust code block is hidden in the docs, which rustdoc allows to indent
+pub fn is_hidden_line(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with("# ") || text == "#"
+}
 
 #[derive(PartialEq)]
 enum Code {
@@ -33,78 +57,197 @@ enum Code {
 pub struct DocTransformer<I: Iterator> {
     iter: I,
     indent_headings: bool,
+    heading_names: BTreeMap<String, String>,
+    heading_prefixes: BTreeMap<String, String>,
+    fence_label: String,
+    keep_fence_attributes: bool,
+    blocks: Blocks,
     section: Code,
-    re_code_rust: Regex,
-    re_code_text: Regex,
-    re_code_other: Regex,
+    section_start: usize,
+    warnings: Vec<Warning>,
+    matchers: Matchers,
 }
 
-impl<I: Iterator<Item = String>> DocTransformer<I> {
-    pub fn new<J: IntoIterator<IntoIter = I, Item = String>>(
+impl<I: Iterator<Item = Line>> DocTransformer<I> {
+    pub fn new<J: IntoIterator<IntoIter = I, Item = Line>>(
         iter: J,
         indent_headings: bool,
+        matchers: &Matchers,
     ) -> Self {
-        // Is this code block rust?
-        let re_code_rust = Regex::new(REGEX_CODE_RUST).unwrap();
-        // Is this code block just text?
-        let re_code_text = Regex::new(REGEX_CODE_TEXT).unwrap();
-        // Is this code block a language other than rust?
-        let re_code_other = Regex::new(REGEX_CODE_OTHER).unwrap();
-
         DocTransformer {
             iter: iter.into_iter(),
-            indent_headings: indent_headings,
+            indent_headings,
+            heading_names: BTreeMap::new(),
+            heading_prefixes: BTreeMap::new(),
+            fence_label: "rust".to_owned(),
+            keep_fence_attributes: false,
+            blocks: Blocks::new(matchers),
             section: Code::None,
-            re_code_rust: re_code_rust,
-            re_code_text: re_code_text,
-            re_code_other: re_code_other,
+            section_start: 0,
+            warnings: Vec::new(),
+            matchers: matchers.clone(),
+        }
+    }
+
+    /// Rename the headings whose text is a key of `names` to the corresponding value
+    pub fn rename_headings(mut self, names: BTreeMap<String, String>) -> Self {
+        self.heading_names = names;
+        self
+    }
+
+    /// Add the value of `prefixes` before the headings whose text, once renamed, is its key
+    pub fn prefix_headings(mut self, prefixes: BTreeMap<String, String>) -> Self {
+        self.heading_prefixes = prefixes;
+        self
+    }
+
+    /// Label the rust code blocks with `label` instead of `rust`, keeping their attributes, like
+    /// `no_run`, after it if `keep_attributes` is set
+    pub fn fence_label(mut self, label: &str, keep_attributes: bool) -> Self {
+        self.fence_label = label.to_owned();
+        self.keep_fence_attributes = keep_attributes;
+        self
+    }
+
+    /// Info string of a rust code block in the readme
+    fn rust_info(&self, info: &str) -> String {
+        let attributes = info.trim_start_matches("rust").trim_start_matches(',');
+        if self.keep_fence_attributes && !attributes.is_empty() {
+            format!("{},{}", self.fence_label, attributes)
+        } else {
+            self.fence_label.clone()
+        }
+    }
+
+    /// Warnings collected so far
+    ///
+    /// Warnings about the end of the docs, like a code block that is never closed, are only
+    /// available after the iterator is exhausted.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, src_line: usize, message: &str) {
+        self.warnings.push(Warning::new(src_line, message));
+    }
+
+    fn rename_heading(&self, heading: &mut String) {
+        let renamed = self.matchers.heading.captures(heading).and_then(|caps| {
+            let name = self.heading_names.get(&caps[2]).map_or(&caps[2], |name| name.as_str());
+            let text = match self.heading_prefixes.get(name) {
+                Some(prefix) => format!("{} {}", prefix.trim_end(), name),
+                None if name != &caps[2] => name.to_owned(),
+                None => return None,
+            };
+            Some(format!("{}{}{}", &caps[1], text, &caps[3]))
+        });
+
+        if let Some(renamed) = renamed {
+            *heading = renamed;
+        }
+    }
+
+    /// Transform a line, or return `None` if it is hidden in the docs
+    fn transform_line(&mut self, mut line: Line) -> Option<Line> {
+        let (prefix, mut text, block) = self.blocks.parse(&line.text);
+
+        match block {
+            Block::Code => {
+                if self.section == Code::Rust && is_hidden_line(&text) {
+                    return None;
+                }
+            }
+            Block::Close => self.section = Code::None,
+            Block::Open(fence) => {
+                self.section_start = line.src_line;
+                if self.matchers.code_rust.is_match(&fence.info) {
+                    self.section = Code::Rust;
+                    text = fence.with_info(&self.rust_info(&fence.info));
+                } else if self.matchers.code_text.is_match(&fence.info) {
+                    self.section = Code::Other;
+                    text = fence.with_info("");
+                } else {
+                    self.section = Code::Other;
+                }
+            }
+            Block::Text => {
+                // the code block may have ended with the block quote or list item it was in
+                self.section = Code::None;
+
+                let is_heading = self.matchers.heading.is_match(&text);
+                if is_heading {
+                    self.rename_heading(&mut text);
+                }
+
+                // indent heading when outside code
+                if self.indent_headings && is_heading {
+                    if self.matchers.heading_max_level.is_match(&text) {
+                        self.warn(line.src_line, "heading is too deep to be indented, it is left at the deepest level");
+                    } else {
+                        let level_start = text.find('#').unwrap_or(0);
+                        text.insert(level_start, '#');
+                    }
+                } else if self.matchers.intra_doc_link.is_match(&text) {
+                    self.warn(line.src_line, "intra-doc links cannot be resolved outside of rustdoc");
+                }
+            }
+        }
+
+        line.text = format!("{}{}", prefix, text);
+        Some(line)
+    }
+
+    /// Called when the input is exhausted
+    fn finish(&mut self) {
+        if self.section != Code::None {
+            let src_line = self.section_start;
+            self.warn(src_line, "code block is never closed");
+            self.section = Code::None;
         }
     }
 }
 
 impl<I> Iterator for DocTransformer<I>
 where
-    I: Iterator<Item = String>,
+    I: Iterator<Item = Line>,
 {
-    type Item = String;
+    type Item = Line;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut line = match self.iter.next() {
-            Some(line) => line,
-            None => return None,
-        };
-
-        // Skip lines that should be hidden in docs
-        while self.section == Code::Rust && line.starts_with("# ") {
-            line = match self.iter.next() {
+        loop {
+            let line = match self.iter.next() {
                 Some(line) => line,
-                None => return None,
+                None => {
+                    self.finish();
+                    return None;
+                }
             };
-        }
 
-        // indent heading when outside code
-        if self.indent_headings && self.section == Code::None && line.starts_with("#") {
-            line.insert(0, '#');
-        } else if self.section == Code::None && self.re_code_rust.is_match(&line) {
-            self.section = Code::Rust;
-            line = "```rust".to_owned();
-        } else if self.section == Code::None && self.re_code_text.is_match(&line) {
-            self.section = Code::Other;
-            line = "```".to_owned();
-        } else if self.section == Code::None && self.re_code_other.is_match(&line) {
-            self.section = Code::Other;
-        } else if self.section != Code::None && line == "```" {
-            self.section = Code::None;
+            // hidden lines are skipped
+            if let Some(line) = self.transform_line(line) {
+                return Some(line);
+            }
         }
-
-        Some(line)
     }
 }
 
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::DocTransformer;
+    use super::super::extract::Line;
+    use super::super::matchers::Matchers;
+    use super::super::Warning;
+
+    fn lines(input: &str) -> Vec<Line> {
+        input.lines().enumerate().map(|(i, x)| Line::new(x, i + 1)).collect()
+    }
+
+    fn texts<I: Iterator<Item = Line>>(iter: I) -> Vec<String> {
+        iter.map(|line| line.text).collect()
+    }
 
     const INPUT_HIDDEN_LINE: &str = concat_lines!(
         "```",
@@ -123,10 +266,10 @@ mod tests {
 
     #[test]
     fn hide_line_in_rust_code_block() {
-        let input: Vec<_> = INPUT_HIDDEN_LINE.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_HIDDEN_LINE);
         let expected: Vec<_> = EXPECTED_HIDDEN_LINE.lines().map(|x| x.to_owned()).collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
 
         assert_eq!(result, expected);
     }
@@ -156,15 +299,88 @@ mod tests {
 
     #[test]
     fn do_not_hide_line_in_code_block() {
-        let input: Vec<_> = INPUT_NOT_HIDDEN_LINE.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_NOT_HIDDEN_LINE);
         let expected: Vec<_> = EXPECTED_NOT_HIDDEN_LINE.lines().map(|x| x.to_owned()).collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
+
+        assert_eq!(result, expected);
+    }
+
+    const INPUT_INDENTED_CODE_BLOCK: &str = concat_lines!(
+        "  ```",
+        "  let visible = true;",
+        "\t# let hidden = true;",
+        "    #",
+        "   ```",
+        "",
+        "    ```",
+        "    # indented code, not a fence",
+    );
+
+    const EXPECTED_INDENTED_CODE_BLOCK: &str = concat_lines!(
+        "  ```rust",
+        "  let visible = true;",
+        "   ```",
+        "",
+        "    ```",
+        "    # indented code, not a fence",
+    );
+
+    #[test]
+    fn indented_code_block() {
+        let input = lines(INPUT_INDENTED_CODE_BLOCK);
+        let expected: Vec<_> = EXPECTED_INDENTED_CODE_BLOCK.lines().map(|x| x.to_owned()).collect();
+
+        let result = texts(DocTransformer::new(input, false, &Matchers::new()));
+
+        assert_eq!(result, expected);
+    }
+
+    const INPUT_LONGER_FENCES: &str = concat_lines!(
+        "````",
+        "```",
+        "# let hidden = true;",
+        "```",
+        "````",
+        "",
+        "~~~text",
+        "```",
+        "~~~~",
+        "",
+        "```toml title",
+        "[package]",
+        "```",
+    );
+
+    const EXPECTED_LONGER_FENCES: &str = concat_lines!(
+        "````rust",
+        "```",
+        "```",
+        "````",
+        "",
+        "~~~",
+        "```",
+        "~~~~",
+        "",
+        "```toml title",
+        "[package]",
+        "```",
+    );
+
+    #[test]
+    fn longer_fences() {
+        let input = lines(INPUT_LONGER_FENCES);
+        let expected: Vec<_> = EXPECTED_LONGER_FENCES.lines().map(|x| x.to_owned()).collect();
+
+        let mut transformer = DocTransformer::new(input, true, &Matchers::new());
+        let result = texts(transformer.by_ref());
 
         assert_eq!(result, expected);
+        assert_eq!(transformer.warnings(), &[]);
     }
 
-    const INPUT_RUST_CODE_BLOCK: &'static str = concat_lines!(
+    const INPUT_RUST_CODE_BLOCK: &str = concat_lines!(
         "```",
         "let block = \"simple code block\";",
         "```",
@@ -210,15 +426,15 @@ mod tests {
 
     #[test]
     fn transform_rust_code_block() {
-        let input: Vec<_> = INPUT_RUST_CODE_BLOCK.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_RUST_CODE_BLOCK);
         let expected: Vec<_> = EXPECTED_RUST_CODE_BLOCK.lines().map(|x| x.to_owned()).collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
 
         assert_eq!(result, expected);
     }
 
-    const INPUT_RUST_CODE_BLOCK_RUST_PREFIX: &'static str = concat_lines!(
+    const INPUT_RUST_CODE_BLOCK_RUST_PREFIX: &str = concat_lines!(
         "```rust",
         "let block = \"simple code block\";",
         "```",
@@ -242,15 +458,28 @@ mod tests {
 
     #[test]
     fn transform_rust_code_block_with_prefix() {
-        let input: Vec<_> = INPUT_RUST_CODE_BLOCK_RUST_PREFIX.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_RUST_CODE_BLOCK_RUST_PREFIX);
         let expected: Vec<_> = EXPECTED_RUST_CODE_BLOCK.lines().map(|x| x.to_owned()).collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
 
         assert_eq!(result, expected);
     }
 
-    const INPUT_TEXT_BLOCK: &'static str = concat_lines!(
+    #[test]
+    fn custom_fence_label() {
+        let input = concat_lines!("```", "```", "```no_run", "```", "```rust,ignore", "```");
+
+        let result = texts(DocTransformer::new(lines(input), true, &Matchers::new())
+            .fence_label("rs", false));
+        assert_eq!(result, vec!["```rs", "```", "```rs", "```", "```rs", "```"]);
+
+        let result = texts(DocTransformer::new(lines(input), true, &Matchers::new())
+            .fence_label("rust", true));
+        assert_eq!(result, vec!["```rust", "```", "```rust,no_run", "```", "```rust,ignore", "```"]);
+    }
+
+    const INPUT_TEXT_BLOCK: &str = concat_lines!(
         "```text",
         "this is text",
         "```",
@@ -264,15 +493,15 @@ mod tests {
 
     #[test]
     fn transform_text_block() {
-        let input: Vec<_> = INPUT_TEXT_BLOCK.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_TEXT_BLOCK);
         let expected: Vec<_> = EXPECTED_TEXT_BLOCK.lines().map(|x| x.to_owned()).collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
 
         assert_eq!(result, expected);
     }
 
-    const INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS: &'static str = concat_lines!(
+    const INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS: &str = concat_lines!(
         "```html,django",
         "{% if True %}True{% endif %}",
         "```",
@@ -284,15 +513,15 @@ mod tests {
 
     #[test]
     fn transform_other_code_block_with_symbols() {
-        let input: Vec<_> = INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS);
         let expected: Vec<_> = INPUT_OTHER_CODE_BLOCK_WITH_SYMBOLS.lines().map(|x| x.to_owned()).collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
 
         assert_eq!(result, expected);
     }
 
-    const INPUT_INDENT_HEADINGS: &'static str = concat_lines!(
+    const INPUT_INDENT_HEADINGS: &str = concat_lines!(
         "# heading 1",
         "some text",
         "## heading 2",
@@ -308,21 +537,124 @@ mod tests {
 
     #[test]
     fn indent_markdown_headings() {
-        let input: Vec<_> = INPUT_INDENT_HEADINGS.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_INDENT_HEADINGS);
         let expected: Vec<_> = EXPECTED_INDENT_HEADINGS.lines().collect();
 
-        let result: Vec<_> = DocTransformer::new(input, true).collect();
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
+
+        assert_eq!(result, expected);
+    }
+
+    const INPUT_NOT_HEADINGS: &str = concat_lines!(
+        "#include <stdio.h>",
+        "#hashtag",
+        "####### seven is too many",
+        "#",
+        "   # indented heading",
+        "#\theading after a tab",
+    );
+
+    const EXPECTED_NOT_HEADINGS: &str = concat_lines!(
+        "#include <stdio.h>",
+        "#hashtag",
+        "####### seven is too many",
+        "##",
+        "   ## indented heading",
+        "##\theading after a tab",
+    );
+
+    #[test]
+    fn indent_only_markdown_headings() {
+        let input = lines(INPUT_NOT_HEADINGS);
+        let expected: Vec<_> = EXPECTED_NOT_HEADINGS.lines().collect();
+
+        let result = texts(DocTransformer::new(input, true, &Matchers::new()));
 
         assert_eq!(result, expected);
     }
 
     #[test]
     fn do_not_indent_markdown_headings() {
-        let input: Vec<_> = INPUT_INDENT_HEADINGS.lines().map(|x| x.to_owned()).collect();
+        let input = lines(INPUT_INDENT_HEADINGS);
         let expected: Vec<_> = INPUT_INDENT_HEADINGS.lines().collect();
 
-        let result: Vec<_> = DocTransformer::new(input, false).collect();
+        let result = texts(DocTransformer::new(input, false, &Matchers::new()));
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn rename_markdown_headings() {
+        let input = lines(concat_lines!(
+            "# Examples",
+            "```",
+            "# Examples",
+            "```",
+            "## Examples of errors",
+        ));
+        let expected = vec!["## Quick start", "```rust", "```", "### Examples of errors"];
+
+        let mut names = BTreeMap::new();
+        names.insert("Examples".to_owned(), "Quick start".to_owned());
+        let transformer = DocTransformer::new(input, true, &Matchers::new()).rename_headings(names);
+        let result = texts(transformer);
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn prefix_markdown_headings() {
+        let input = lines(concat_lines!("# Examples", "## Usage", "# Errors"));
+        let expected = vec!["## 🚀 Quick start", "### 📖 Usage", "## Errors"];
+
+        let mut names = BTreeMap::new();
+        names.insert("Examples".to_owned(), "Quick start".to_owned());
+        let mut prefixes = BTreeMap::new();
+        prefixes.insert("Quick start".to_owned(), "🚀".to_owned());
+        prefixes.insert("Usage".to_owned(), "📖 ".to_owned());
+        let transformer = DocTransformer::new(input, true, &Matchers::new())
+            .rename_headings(names)
+            .prefix_headings(prefixes);
+        let result = texts(transformer);
+
+        assert_eq!(result, expected);
+    }
+
+    const INPUT_UNCLOSED_CODE_BLOCK: &str = concat_lines!(
+        "some text",
+        "",
+        "```",
+        "let unclosed = true;",
+    );
+
+    #[test]
+    fn warn_unclosed_code_block() {
+        let input = lines(INPUT_UNCLOSED_CODE_BLOCK);
+        let mut transformer = DocTransformer::new(input, true, &Matchers::new());
+        transformer.by_ref().count();
+
+        assert_eq!(transformer.warnings(), &[Warning::new(3, "code block is never closed")]);
+    }
+
+    #[test]
+    fn warn_heading_too_deep() {
+        let input = lines("text\n###### deepest heading\n");
+        let mut transformer = DocTransformer::new(input, true, &Matchers::new());
+        transformer.by_ref().count();
+
+        assert_eq!(transformer.warnings().len(), 1);
+        assert_eq!(transformer.warnings()[0].src_line, Some(2));
+    }
+
+    #[test]
+    fn warn_intra_doc_link() {
+        let input = lines("See [`Config`](crate::config::Config)\nand [docs](https://docs.rs)\n");
+        let mut transformer = DocTransformer::new(input, true, &Matchers::new());
+        transformer.by_ref().count();
+
+        assert_eq!(
+            transformer.warnings(),
+            &[Warning::new(1, "intra-doc links cannot be resolved outside of rustdoc")]
+        );
+    }
 }
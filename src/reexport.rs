@@ -0,0 +1,102 @@
+//! Follow the docs of a facade crate to the crate it re-exports
+//!
+//! Facade crates are often little more than `pub use inner::*;`, with the docs written in the
+//! inner crate. The crates re-exported with a glob are looked up in the dependencies of the
+//! facade, and the entrypoint of the first one that is a path dependency, given in the
+//! `[dependencies]` of the facade or in the `[workspace.dependencies]` of its workspace, is read
+//! instead of the one of the facade. The title, license and other details of `Cargo.toml` are
+//! still those of the facade, as it is the crate the readme is for.
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use toml;
+
+use cargo_info::{self, Cargo};
+use workspace;
+
+const REGEX_GLOB_REEXPORT: &str = r"^\s*pub\s+use\s+(?:::)?([A-Za-z_]\w*)\s*::\s*\*\s*;";
+
+/// Entrypoint of the crate re-exported by `source`, the root of the crate at `project_root`
+pub fn reexported_source(project_root: &Path, source: &str) -> Result<PathBuf, String> {
+    let names = glob_reexports(source);
+    if names.is_empty() {
+        return Err("No glob re-export, like `pub use inner::*;`, found in the source".to_owned());
+    }
+
+    let cargo = cargo_info::get_cargo_info(project_root)?;
+    for name in &names {
+        if let Some(root) = dependency_root(project_root, &cargo, name) {
+            return entrypoint(&root);
+        }
+    }
+
+    Err(format!(
+        "None of the re-exported crates ({}) is a path dependency of the crate",
+        names.join(", ")
+    ))
+}
+
+/// Names of the crates re-exported with a glob, in the order they are found
+fn glob_reexports(source: &str) -> Vec<&str> {
+    let re = Regex::new(REGEX_GLOB_REEXPORT).unwrap();
+    source
+        .lines()
+        .filter_map(|line| re.captures(line))
+        .map(|caps| caps.get(1).unwrap().as_str())
+        .filter(|&name| !["crate", "self", "super"].contains(&name))
+        .collect()
+}
+
+/// Directory of the path dependency used as `name` in the code, if there is one
+fn dependency_root(project_root: &Path, cargo: &Cargo, name: &str) -> Option<PathBuf> {
+    let (key, dependency) = cargo
+        .dependencies
+        .iter()
+        .find(|&(key, _)| key.replace('-', "_") == name)?;
+    if let Some(path) = dependency_path(dependency) {
+        return Some(project_root.join(path));
+    }
+
+    // `name = { workspace = true }` takes the path from the workspace, relative to its root
+    if dependency.get("workspace").and_then(|value| value.as_bool()) != Some(true) {
+        return None;
+    }
+    let workspace_root = workspace::find_workspace_root(project_root)?;
+    let workspace = workspace::read_workspace(&workspace_root)?;
+    let path = workspace.dependencies.get(key).and_then(dependency_path)?;
+    Some(workspace_root.join(path))
+}
+
+fn dependency_path(dependency: &toml::Value) -> Option<&str> {
+    dependency.get("path").and_then(|path| path.as_str())
+}
+
+/// Entrypoint of the library of the crate at `root`
+fn entrypoint(root: &Path) -> Result<PathBuf, String> {
+    let cargo = cargo_info::get_cargo_info(root)
+        .map_err(|e| format!("Could not read the re-exported crate: {}", e))?;
+    let path = cargo.lib.as_ref().and_then(|lib| lib.path.as_deref()).unwrap_or("src/lib.rs");
+    let source = root.join(path);
+    source.canonicalize().map_err(|e| {
+        format!("Could not find the source of the re-exported crate '{}': {}", cargo.package.name, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_glob_reexports() {
+        let source = concat_lines!(
+            "//! Facade",
+            "pub use facade_core::*;",
+            "pub use ::facade_macros :: *;",
+            "pub use self::prelude::*;",
+            "pub use facade_util::Helper;",
+            "// pub use commented::*;",
+        );
+        assert_eq!(vec!["facade_core", "facade_macros"], glob_reexports(source));
+    }
+}
@@ -0,0 +1,154 @@
+//! Read single-file cargo scripts, whose manifest is embedded in their source
+//!
+//! A cargo script, run with `cargo +nightly -Zscript tool.rs`, has its manifest in a frontmatter
+//! between `---` lines at the top of the file, after the shebang:
+//!
+//! ```text
+//! #!/usr/bin/env -S cargo +nightly -Zscript
+//! ---
+//! [package]
+//! license = "MIT"
+//!
+//! [dependencies]
+//! clap = "4"
+//! ---
+//! ```
+//!
+//! Scripts written for the older cargo-script and rust-script tools have it in a `cargo` code
+//! block of their doc comments instead, which is left out of the readme. Like cargo, the package
+//! is named after the file when the manifest does not name it.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use toml;
+
+use cargo_info::Cargo;
+
+/// Crate information of the cargo script at `path`, from the manifest embedded in it
+pub fn read_manifest(path: &Path) -> Result<Cargo, String> {
+    let mut source = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut source))
+        .map_err(|e| format!("Could not read file '{}': {}", path.to_string_lossy(), e))?;
+
+    let manifest = frontmatter(&source).or_else(|| manifest_block(&source)).ok_or_else(|| {
+        format!(
+            "Could not read Cargo.toml, and '{}' is not a cargo script with its manifest \
+             embedded",
+            path.to_string_lossy()
+        )
+    })?;
+    let mut manifest: toml::Value = toml::from_str(&manifest)
+        .map_err(|e| format!("Invalid manifest in '{}': {}", path.to_string_lossy(), e))?;
+
+    let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+    let name = name.unwrap_or_default();
+    if let toml::Value::Table(ref mut manifest) = manifest {
+        let package = manifest
+            .entry("package".to_owned())
+            .or_insert_with(|| toml::Value::Table(Default::default()));
+        if let toml::Value::Table(ref mut package) = *package {
+            package.entry("name".to_owned()).or_insert(toml::Value::String(name));
+        }
+    }
+
+    manifest
+        .try_into()
+        .map_err(|e| format!("Invalid manifest in '{}': {}", path.to_string_lossy(), e))
+}
+
+/// The source without the `cargo` code block of its doc comments, which is its manifest, nor the
+/// empty line after it
+pub fn without_manifest_block(source: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_block = false;
+    let mut after_block = false;
+    for line in source.lines() {
+        let doc = doc_text(line);
+        if in_block {
+            in_block = doc != Some("```");
+            after_block = !in_block;
+        } else if doc == Some("```cargo") {
+            in_block = true;
+        } else {
+            if !after_block || doc != Some("") {
+                lines.push(line);
+            }
+            after_block = false;
+        }
+    }
+    lines.join("\n")
+}
+
+/// The text between the `---` lines at the start of the file, after a shebang
+fn frontmatter(source: &str) -> Option<String> {
+    let mut lines = source.lines().peekable();
+    if lines.peek().is_some_and(|line| line.starts_with("#!") && !line.starts_with("#![")) {
+        lines.next();
+    }
+
+    let open = lines.find(|line| !line.trim().is_empty())?;
+    if !open.starts_with("---") {
+        return None;
+    }
+    let dashes: String = open.chars().take_while(|&c| c == '-').collect();
+    let manifest: Vec<&str> = lines.take_while(|line| line.trim_end() != dashes).collect();
+    Some(manifest.join("\n"))
+}
+
+/// The content of the `cargo` code block of the doc comments
+fn manifest_block(source: &str) -> Option<String> {
+    let mut docs = source.lines().filter_map(doc_text);
+    docs.find(|&doc| doc == "```cargo")?;
+    let manifest: Vec<&str> = docs.take_while(|&doc| doc != "```").collect();
+    Some(manifest.join("\n"))
+}
+
+/// Text of a `//!` doc comment line, without the comment mark and the space after it
+fn doc_text(line: &str) -> Option<&str> {
+    let text = line.strip_prefix("//!")?;
+    Some(text.strip_prefix(' ').unwrap_or(text).trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_of_frontmatter() {
+        let source = concat_lines!(
+            "#!/usr/bin/env -S cargo +nightly -Zscript",
+            "---cargo",
+            "[dependencies]",
+            "clap = \"4\"",
+            "---",
+            "",
+            "//! Docs",
+            "fn main() {}",
+        );
+        assert_eq!(Some("[dependencies]\nclap = \"4\"".to_owned()), frontmatter(source));
+        assert_eq!(None, frontmatter("//! Docs\n---\n"));
+    }
+
+    #[test]
+    fn manifest_of_doc_comments() {
+        let source = concat_lines!(
+            "//! Docs",
+            "//!",
+            "//! ```cargo",
+            "//! [dependencies]",
+            "//! time = \"0.1\"",
+            "//! ```",
+            "//!",
+            "//! More docs",
+            "fn main() {}",
+        );
+        assert_eq!(Some("[dependencies]\ntime = \"0.1\"".to_owned()), manifest_block(source));
+        assert_eq!(
+            concat_lines!("//! Docs", "//!", "//! More docs", "fn main() {}").trim_end(),
+            without_manifest_block(source)
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Serve a live preview of the readme, rendered to HTML like with `--format html`
+//!
+//! The page asks the server for the version of the readme every second, and reloads when it
+//! changes. The readme is generated again when a file of the crate changed since the last time,
+//! found from the modification times of its files, leaving out `target/` and hidden directories.
+//! Other files, like the images the readme shows, are served from the project root.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use html::{self, CSS};
+
+/// Serve the preview on localhost until the process is stopped
+///
+/// `generate` generates the readme, as markdown.
+pub fn serve<F>(port: u16, project_root: &Path, mut generate: F) -> Result<(), String>
+where
+    F: FnMut() -> Result<String, String>,
+{
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Could not listen on port {}: {}", port, e))?;
+    io::stderr()
+        .write_fmt(format_args!("Serving the readme on http://127.0.0.1:{}/\n", port))
+        .expect("An error occurred while trying to show a message");
+
+    let mut preview = Preview {
+        changed: None,
+        version: 0,
+        body: String::new(),
+    };
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        preview.update(project_root, &mut generate);
+        // the browser may have closed the connection, which is no reason to stop
+        let _ = respond(stream, &preview, project_root);
+    }
+
+    Ok(())
+}
+
+/// The readme as last generated
+struct Preview {
+    /// When a file of the crate last changed, when the readme was generated
+    changed: Option<SystemTime>,
+    /// Changes every time the readme is generated again
+    version: u64,
+    /// HTML of the readme, or of the error that prevented generating it
+    body: String,
+}
+
+impl Preview {
+    fn update<F>(&mut self, project_root: &Path, mut generate: F)
+    where
+        F: FnMut() -> Result<String, String>,
+    {
+        let changed = last_change(project_root);
+        if self.version > 0 && changed == self.changed {
+            return;
+        }
+
+        self.changed = changed;
+        self.version += 1;
+        self.body = match generate() {
+            Ok(readme) => html::to_html(&readme),
+            Err(e) => {
+                io::stderr()
+                    .write_fmt(format_args!("Error: {}\n", e))
+                    .expect("An error occurred while trying to show a message");
+                format!("<pre class=\"error\">{}</pre>", html::escape(&e))
+            }
+        };
+    }
+
+    fn page(&self) -> String {
+        format!(
+            concat!(
+                "<!DOCTYPE html>\n",
+                "<html>\n",
+                "<head>\n",
+                "<meta charset=\"utf-8\">\n",
+                "<title>Readme preview</title>\n",
+                "<style>{}</style>\n",
+                "</head>\n",
+                "<body>\n",
+                "<article class=\"markdown-body\">\n{}</article>\n",
+                "<script>\n",
+                "setInterval(function () {{\n",
+                "    fetch('/version').then(function (response) {{ return response.text(); }})\n",
+                "        .then(function (version) {{\n",
+                "            if (version !== '{}') {{ location.reload(); }}\n",
+                "        }}, function () {{}});\n",
+                "}}, 1000);\n",
+                "</script>\n",
+                "</body>\n",
+                "</html>\n",
+            ),
+            CSS, self.body, self.version
+        )
+    }
+}
+
+/// When a file of the crate last changed, leaving out `target/` and hidden directories
+fn last_change(dir: &Path) -> Option<SystemTime> {
+    let mut latest = None;
+    for entry in fs::read_dir(dir).ok()?.filter_map(Result::ok) {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with('.') || name == "target" {
+            continue;
+        }
+        let path = entry.path();
+        let changed = if path.is_dir() {
+            last_change(&path)
+        } else {
+            entry.metadata().and_then(|metadata| metadata.modified()).ok()
+        };
+        latest = latest.max(changed);
+    }
+    latest
+}
+
+fn respond(stream: TcpStream, preview: &Preview, project_root: &Path) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // the headers are not needed, but closing the connection before reading them resets it
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let (status, content_type, body) = match path {
+        "/" | "/index.html" => ("200 OK", "text/html; charset=utf-8", preview.page().into_bytes()),
+        "/version" => ("200 OK", "text/plain", preview.version.to_string().into_bytes()),
+        _ => match read_file(project_root, path) {
+            Some((content_type, body)) => ("200 OK", content_type, body),
+            None => ("404 Not Found", "text/plain", b"Not found".to_vec()),
+        },
+    };
+
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+         Connection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(&body)
+}
+
+/// Content type and content of a file of the project, if `path` names one
+fn read_file(project_root: &Path, path: &str) -> Option<(&'static str, Vec<u8>)> {
+    let path = path.split(['?', '#']).next().unwrap_or("");
+    let root = project_root.canonicalize().ok()?;
+    let file: PathBuf = root.join(path.trim_start_matches('/')).canonicalize().ok()?;
+    if !file.starts_with(&root) || !file.is_file() {
+        return None;
+    }
+
+    let content_type = match file.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("md") | Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    };
+    let mut body = Vec::new();
+    File::open(&file).and_then(|mut f| f.read_to_end(&mut body)).ok()?;
+    Some((content_type, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_reloads_on_new_version() {
+        let preview = Preview {
+            changed: None,
+            version: 3,
+            body: html::to_html("# my-crate\n\n| a | b |\n|---|---|\n| 1 | 2 |\n"),
+        };
+        let page = preview.page();
+
+        assert!(page.contains("<h1>my-crate</h1>"));
+        assert!(page.contains("<table>"));
+        assert!(page.contains("if (version !== '3')"));
+    }
+}
@@ -0,0 +1,40 @@
+//! Temporary directories that are removed when dropped
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory inside the system temporary directory, removed with all its content when dropped
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create a new, empty, temporary directory whose name starts with `prefix`
+    pub fn new(prefix: &str) -> io::Result<TempDir> {
+        let path = env::temp_dir().join(format!(
+            "{}-{}-{}",
+            prefix,
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        fs::create_dir_all(&path)?;
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -4,10 +4,10 @@
 macro_rules! concat_lines {
     // no trailing comma
     ( $( $line:expr ),+ ) => {
-        concat!( $( $line, "\n", )* );
+        concat!( $( $line, "\n", )* )
     };
     // trailing comma
     ( $( $line:expr ),+, ) => {
-        concat!( $( $line, "\n", )* );
+        concat!( $( $line, "\n", )* )
     };
 }
@@ -0,0 +1,97 @@
+//! Check from the tests of a crate that its readme is up to date
+//!
+//! Enable the `test-support` feature in `[dev-dependencies]` and add a test like this one:
+//!
+//! ```ignore
+//! #[macro_use]
+//! extern crate cargo_readme;
+//!
+//! #[test]
+//! fn readme_is_up_to_date() {
+//!     assert_readme_up_to_date!("README.md");
+//! }
+//! ```
+//!
+//! The readme is generated the same way as `cargo readme` does with no arguments: from the
+//! default entrypoint, with `README.tpl` if it exists and the settings in `Cargo.toml`.
+
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use cargo_info;
+use helper;
+use readme::{generate_readme_with_options, is_up_to_date, Options};
+
+/// Panic if the readme of the crate in the current directory is out of date
+#[macro_export]
+macro_rules! assert_readme_up_to_date {
+    ($readme:expr) => {
+        $crate::test_support::assert_readme_up_to_date(env!("CARGO_MANIFEST_DIR"), $readme)
+    };
+}
+
+/// Panic if `readme`, relative to `project_root`, is not what `cargo readme` would generate
+pub fn assert_readme_up_to_date<P: AsRef<Path>>(project_root: P, readme: &str) {
+    if let Err(e) = check_readme(project_root.as_ref(), readme) {
+        panic!("{}", e);
+    }
+}
+
+/// Check that `readme`, relative to `project_root`, is what `cargo readme` would generate
+///
+/// Line endings and trailing whitespace at the end of the file are ignored.
+pub fn check_readme(project_root: &Path, readme: &str) -> Result<(), String> {
+    let config = cargo_info::get_cargo_info(project_root)?.config(None)?;
+    let options = Options {
+        config,
+        ..Options::default()
+    };
+
+    let (_, mut source) = helper::find_entrypoint(project_root, options.config.prefer)?;
+    let template = helper::get_template(
+        project_root,
+        options.config.template.as_deref(),
+        options.add_title,
+        options.add_license,
+    )?;
+    let mut template = template.map(|template| Cursor::new(template.into_bytes()));
+    let mut source_text = Vec::new();
+    source.read_to_end(&mut source_text).map_err(|e| format!("{}", e))?;
+    let expected = generate_readme_with_options(
+        project_root,
+        &mut Cursor::new(source_text),
+        template.as_mut(),
+        &options,
+    )?;
+
+    let path = project_root.join(readme);
+    let mut actual = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut actual))
+        .map_err(|e| format!("Could not read '{}': {}", path.to_string_lossy(), e))?;
+
+    if is_up_to_date(&expected.content, &actual) {
+        return Ok(());
+    }
+
+    let expected = expected.content.replace("\r\n", "\n");
+    let actual = actual.replace("\r\n", "\n");
+
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+
+    Err(format!(
+        "{} is out of date, starting at line {}; regenerate it with `cargo readme > {}`\n\
+         expected: {:?}\n\
+         found:    {:?}",
+        readme,
+        line + 1,
+        readme,
+        expected.lines().nth(line).unwrap_or(""),
+        actual.lines().nth(line).unwrap_or(""),
+    ))
+}
@@ -0,0 +1,211 @@
+//! Check that the install snippets of a readme give the current versions of the crates
+//!
+//! Install snippets are the lines of code blocks adding a dependency, like `my-crate = "1.2"` or
+//! `my-crate = { version = "1.2", features = ["derive"] }`, and the `cargo add my-crate@1.2` and
+//! `cargo install my-crate --version 1.2` commands. The versions they give of the crate and of
+//! its path dependencies, usually the other crates of its workspace, must match the ones of
+//! their `Cargo.toml`: `1`, `1.2` and `1.2.3` all match `1.2.3`, `1.1` does not. Versions
+//! inherited from `[workspace.package]` are read from the workspace.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use regex::Regex;
+
+use cargo_info::{self, Cargo};
+use workspace;
+
+const REGEX_DEPENDENCY: &str = r#"^\s*([A-Za-z0-9_-]+)\s*=\s*"([^"]*)""#;
+const REGEX_DEPENDENCY_TABLE: &str =
+    r#"^\s*([A-Za-z0-9_-]+)\s*=\s*\{.*\bversion\s*=\s*"([^"]*)""#;
+const REGEX_CARGO_ADD: &str = r"([A-Za-z0-9_-]+)@([^\s`'\x22]+)";
+const REGEX_CARGO_INSTALL: &str = r"--vers(?:ion)?[= ]+([^\s`'\x22]+)";
+
+/// A version of a crate in the readme that is not its current one
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+    /// Line of the readme, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+/// Versions in the install snippets of `readme` that differ from the current ones of the crate
+/// in `project_root` and of its path dependencies
+pub fn version_mismatches(project_root: &Path, readme: &str) -> Result<Vec<Mismatch>, String> {
+    let versions = crate_versions(project_root)?;
+    Ok(mismatches(readme, &versions))
+}
+
+/// Current versions of the crate and of its path dependencies, by package name
+fn crate_versions(project_root: &Path) -> Result<BTreeMap<String, String>, String> {
+    let workspace_root = workspace::find_workspace_root(project_root);
+    let workspace = workspace_root.as_ref().and_then(|root| workspace::read_workspace(root));
+    let inherited = workspace
+        .as_ref()
+        .and_then(|workspace| workspace.package.get("version"))
+        .and_then(|version| version.as_str());
+    let version = |cargo: &Cargo| cargo.version().or(inherited).map(|v| v.to_owned());
+
+    let cargo = cargo_info::get_cargo_info(project_root)?;
+    let mut versions = BTreeMap::new();
+    if let Some(version) = version(&cargo) {
+        versions.insert(cargo.package.name.clone(), version);
+    }
+
+    for (name, dependency) in &cargo.dependencies {
+        // dependencies inherited from the workspace have their path relative to its root
+        let inherits = dependency.get("workspace").and_then(|w| w.as_bool()) == Some(true);
+        let (root, dependency) = match (inherits, &workspace_root, &workspace) {
+            (true, Some(root), Some(workspace)) => match workspace.dependencies.get(name) {
+                Some(dependency) => (root.as_path(), dependency),
+                None => continue,
+            },
+            _ => (project_root, dependency),
+        };
+        let path = match dependency.get("path").and_then(|path| path.as_str()) {
+            Some(path) => root.join(path),
+            None => continue,
+        };
+        let cargo = cargo_info::get_cargo_info(&path)?;
+        if let Some(version) = version(&cargo) {
+            versions.insert(cargo.package.name.clone(), version);
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Versions given in the code blocks of `readme` that do not match `versions`
+fn mismatches(readme: &str, versions: &BTreeMap<String, String>) -> Vec<Mismatch> {
+    let dependency = Regex::new(REGEX_DEPENDENCY).unwrap();
+    let dependency_table = Regex::new(REGEX_DEPENDENCY_TABLE).unwrap();
+    let cargo_add = Regex::new(REGEX_CARGO_ADD).unwrap();
+    let cargo_install = Regex::new(REGEX_CARGO_INSTALL).unwrap();
+
+    let mut found = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (number, line) in readme.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match fence {
+            None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                fence = Some(&trimmed[..3]);
+                continue;
+            }
+            None => continue,
+            Some(mark) if trimmed.starts_with(mark) => {
+                fence = None;
+                continue;
+            }
+            Some(_) => {}
+        }
+
+        let mut mentions = Vec::new();
+        if let Some(caps) = dependency.captures(line).or_else(|| dependency_table.captures(line)) {
+            mentions.push((caps[1].to_owned(), caps[2].to_owned()));
+        } else if line.contains("cargo add") {
+            for caps in cargo_add.captures_iter(line) {
+                mentions.push((caps[1].to_owned(), caps[2].to_owned()));
+            }
+        } else if let (true, Some(caps)) =
+            (line.contains("cargo install"), cargo_install.captures(line))
+        {
+            let name = line
+                .split_whitespace()
+                .skip_while(|&word| word != "install")
+                .find(|word| versions.contains_key(*word));
+            if let Some(name) = name {
+                mentions.push((name.to_owned(), caps[1].to_owned()));
+            }
+        }
+
+        for (name, requirement) in mentions {
+            let current = match versions.get(&name) {
+                Some(current) => current,
+                None => continue,
+            };
+            if !is_match(&requirement, current) {
+                found.push(Mismatch {
+                    line: number + 1,
+                    message: format!(
+                        "The readme gives version {} of {}, which is at {} in its Cargo.toml",
+                        requirement, name, current
+                    ),
+                });
+            }
+        }
+    }
+
+    found
+}
+
+/// Whether the version `requirement` written in the readme selects `version`, ranges and
+/// wildcards matching any version
+fn is_match(requirement: &str, version: &str) -> bool {
+    let requirement = requirement.trim().trim_start_matches(['^', '=', '~', 'v']).trim();
+    let wildcard = requirement
+        .split('.')
+        .any(|part| part == "*" || part.eq_ignore_ascii_case("x"));
+    if requirement.is_empty() || wildcard || requirement.contains(['<', '>', ',']) {
+        return true;
+    }
+    version == requirement || version.starts_with(&format!("{}.", requirement))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_of_install_snippets() {
+        let versions: BTreeMap<String, String> = vec![
+            ("my-crate".to_owned(), "1.2.3".to_owned()),
+            ("my-crate-derive".to_owned(), "0.4.0".to_owned()),
+        ]
+        .into_iter()
+        .collect();
+        let readme = concat_lines!(
+            "my-crate = \"1.0\" outside of code blocks",
+            "",
+            "```toml",
+            "[dependencies]",
+            "my-crate = \"1.2\"",
+            "my-crate-derive = { version = \"0.3\", optional = true }",
+            "serde = \"1\"",
+            "```",
+            "",
+            "```sh",
+            "cargo add my-crate@1 my-crate-derive@0.4.0",
+            "cargo install my-crate --version 1.1.0",
+            "```",
+        );
+
+        assert_eq!(
+            vec![
+                Mismatch {
+                    line: 6,
+                    message: "The readme gives version 0.3 of my-crate-derive, which is at 0.4.0 \
+                              in its Cargo.toml"
+                        .to_owned(),
+                },
+                Mismatch {
+                    line: 12,
+                    message: "The readme gives version 1.1.0 of my-crate, which is at 1.2.3 in \
+                              its Cargo.toml"
+                        .to_owned(),
+                },
+            ],
+            mismatches(readme, &versions)
+        );
+    }
+
+    #[test]
+    fn version_requirements() {
+        assert!(is_match("1", "1.2.3"));
+        assert!(is_match("^1.2", "1.2.3"));
+        assert!(is_match("=1.2.3", "1.2.3"));
+        assert!(is_match(">=1.0, <2", "1.2.3"));
+        assert!(!is_match("1.1", "1.2.3"));
+        assert!(!is_match("1.2.30", "1.2.3"));
+        assert!(is_match("1.*", "1.2.3"));
+    }
+}
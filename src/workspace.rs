@@ -0,0 +1,201 @@
+//! Find the workspace a crate belongs to and the other crates in it
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use regex::{self, Regex};
+use toml;
+
+use cargo_info::{self, Cargo};
+
+/// Cargo.toml workspace information
+#[derive(Clone, Default, Deserialize)]
+pub struct CargoWorkspace {
+    #[serde(default)]
+    pub members: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Fields of `[workspace.package]`, which the members can inherit
+    #[serde(default)]
+    pub package: BTreeMap<String, toml::Value>,
+    /// Dependencies the members can inherit with `{ workspace = true }`
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, toml::Value>,
+}
+
+/// A manifest that may not have a `[package]`, like the root of a virtual workspace
+#[derive(Deserialize)]
+struct Manifest {
+    workspace: Option<CargoWorkspace>,
+}
+
+/// A crate of the workspace
+pub struct Member {
+    /// Directory of the crate
+    pub path: PathBuf,
+    pub cargo: Cargo,
+}
+
+/// Find the root of the workspace `project_root` belongs to, which may be `project_root` itself
+pub fn find_workspace_root(project_root: &Path) -> Option<PathBuf> {
+    let project_root = project_root.canonicalize().ok()?;
+    project_root
+        .ancestors()
+        .find(|dir| read_workspace(dir).is_some())
+        .map(|dir| dir.to_path_buf())
+}
+
+/// List the members of the workspace at `workspace_root`, sorted by path
+pub fn members(workspace_root: &Path) -> Result<Vec<Member>, String> {
+    let workspace = read_workspace(workspace_root).ok_or_else(|| {
+        format!(
+            "No `[workspace]` found in '{}'",
+            workspace_root.join("Cargo.toml").to_string_lossy()
+        )
+    })?;
+
+    let excluded: Vec<PathBuf> = workspace
+        .exclude
+        .iter()
+        .map(|path| normalize(&workspace_root.join(path)))
+        .collect();
+
+    let mut paths = Vec::new();
+    for pattern in &workspace.members {
+        paths.extend(expand_pattern(workspace_root, pattern));
+    }
+    paths.sort();
+    paths.dedup();
+
+    let mut members = Vec::new();
+    for path in paths {
+        if excluded.iter().any(|excluded| path.starts_with(excluded)) {
+            continue;
+        }
+        if !path.join("Cargo.toml").is_file() {
+            continue;
+        }
+        let cargo = cargo_info::get_cargo_info(&path)?;
+        members.push(Member { path, cargo });
+    }
+
+    Ok(members)
+}
+
+/// Path from directory `from` to `to`, using `/` as separator
+pub fn relative_path(from: &Path, to: &Path) -> String {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let parts: Vec<String> = from[common..]
+        .iter()
+        .map(|_| "..".to_owned())
+        .chain(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()))
+        .collect();
+
+    if parts.is_empty() {
+        ".".to_owned()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// The `[workspace]` of the manifest in `dir`, if it has one
+pub fn read_workspace(dir: &Path) -> Option<CargoWorkspace> {
+    let mut buf = String::new();
+    File::open(dir.join("Cargo.toml"))
+        .and_then(|mut file| file.read_to_string(&mut buf))
+        .ok()?;
+
+    toml::from_str::<Manifest>(&buf).ok()?.workspace
+}
+
+/// Expand a member pattern like `crates/*` into the directories it matches
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![root.to_path_buf()];
+
+    for component in Path::new(pattern).components() {
+        let component = component.as_os_str().to_string_lossy();
+        if !component.contains(['*', '?', '[']) {
+            paths = paths.into_iter().map(|path| path.join(&*component)).collect();
+            continue;
+        }
+
+        let re = glob_to_regex(&component);
+        let mut matches = Vec::new();
+        for path in paths {
+            let entries = match fs::read_dir(&path) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if re.is_match(&name) && entry.path().is_dir() {
+                    matches.push(entry.path());
+                }
+            }
+        }
+        paths = matches;
+    }
+
+    paths.iter().map(|path| normalize(path)).collect()
+}
+
+/// Turn a glob matching a single path component into a regex
+fn glob_to_regex(glob: &str) -> Regex {
+    let mut re = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' | ']' => re.push(c),
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re).unwrap_or_else(|_| Regex::new("^$").unwrap())
+}
+
+/// Remove `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            c => result.push(c.as_os_str()),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{glob_to_regex, relative_path};
+
+    #[test]
+    fn relative_path_to_sibling() {
+        let result = relative_path(Path::new("/ws/crates/a"), Path::new("/ws/crates/b"));
+        assert_eq!(result, "../b");
+    }
+
+    #[test]
+    fn relative_path_to_child() {
+        let result = relative_path(Path::new("/ws"), Path::new("/ws/crates/b"));
+        assert_eq!(result, "crates/b");
+    }
+
+    #[test]
+    fn glob_matches_component() {
+        let re = glob_to_regex("cargo-*");
+        assert!(re.is_match("cargo-readme"));
+        assert!(!re.is_match("readme"));
+    }
+}
@@ -0,0 +1,37 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# after-build
+
+Docs written by the build script
+
+## Usage
+
+Call `generated()`
+
+License: MIT
+"#;
+
+#[test]
+fn docs_from_build_script() {
+    let args = ["readme", "--project-root", "tests/after-build", "--after-build"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn without_after_build() {
+    let args = ["readme", "--project-root", "tests/after-build", "--no-license"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Error: No docs found in 'src/lib.rs': 0 doc comment lines")
+        .unwrap();
+}
@@ -0,0 +1,3 @@
+#![doc = include_str!(concat!(env!("OUT_DIR"), "/lib_docs.md"))]
+
+pub fn generated() {}
@@ -13,10 +13,33 @@ fn empty_docs() {
         "src/no_docs.rs",
     ];
 
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error(
+            "Error: No docs found in 'src/no_docs.rs': 0 doc comment lines, none of them with \
+             text, use `--allow-empty` to generate the readme anyway",
+        )
+        .unwrap();
+}
+
+#[test]
+fn allow_empty_docs() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--input",
+        "src/no_docs.rs",
+        "--allow-empty",
+    ];
+
     Assert::main_binary()
         .with_args(&args)
         .succeeds()
         .prints_exactly("# readme-test\n\nLicense: MIT")
+        .prints_error("Warning: No docs found in 'src/no_docs.rs': 0 doc comment lines")
         .unwrap();
 }
 
@@ -73,3 +96,42 @@ License: MIT
         .prints_exactly(expected)
         .unwrap();
 }
+
+#[test]
+fn input_outside_project_root() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/lib-name",
+        "--input",
+        "../test-project/src/warnings.rs",
+        "--no-template",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("Warning: ../test-project/src/warnings.rs:5: code block is never closed")
+        .unwrap();
+}
+
+#[test]
+fn absolute_input() {
+    let input = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test-project/src/single_line.rs");
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/lib-name",
+        "--input",
+        input,
+        "--no-template",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("# lib-name\n\nTest crate for cargo-readme")
+        .unwrap();
+}
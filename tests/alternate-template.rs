@@ -62,3 +62,30 @@ fn test() {
         .prints_exactly(EXPECTED)
         .unwrap();
 }
+
+#[test]
+fn template_with_comments_and_whitespace_control() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "TRIM.tpl",
+        "--input",
+        "src/single_line.rs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# readme-test
+
+Test crate for cargo-readme
+
+License: MIT
+"#,
+        )
+        .unwrap();
+}
@@ -0,0 +1,35 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+const EXPECTED: &str = r#"{
+  "default": {
+    "Usage": "usage",
+    "anchors-crate": "anchors-crate"
+  },
+  "internal": {
+    "Usage": "usage"
+  }
+}
+"#;
+
+#[test]
+fn anchors_of_every_profile() {
+    let root = TempDir::copy_of("anchors");
+    let root_arg = root.arg();
+    for profile in &[None, Some("internal")] {
+        let mut args = vec!["readme", "--project-root", root_arg, "--emit-anchors", "anchors.json"];
+        if let Some(profile) = *profile {
+            args.extend(&["--config-profile", profile]);
+        }
+        Assert::main_binary().with_args(&args).succeeds().unwrap();
+    }
+
+    assert_eq!(EXPECTED, fs::read_to_string(root.path().join("anchors.json")).unwrap());
+}
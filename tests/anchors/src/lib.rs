@@ -0,0 +1,3 @@
+//! Intro
+//!
+//! # Usage
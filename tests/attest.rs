@@ -0,0 +1,60 @@
+extern crate assert_cli;
+extern crate serde_json;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+use serde_json::Value;
+
+use support::TempDir;
+
+#[test]
+fn attest_and_verify() {
+    let root = TempDir::copy_of("attest");
+    let root_arg = root.arg();
+    let args = [
+        "readme", "--project-root", root_arg, "--no-license", "--output", "README.md",
+        "--attest", "attestation.json",
+    ];
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+
+    let attestation: Value =
+        serde_json::from_str(&fs::read_to_string(root.path().join("attestation.json")).unwrap()).unwrap();
+    assert_eq!("cargo-readme", attestation["tool"]);
+    assert_eq!(env!("CARGO_PKG_VERSION"), attestation["version"]);
+    assert_eq!(
+        serde_json::json!(["--project-root", root_arg, "--no-license", "--output", "README.md"]),
+        attestation["arguments"]
+    );
+    let inputs: Vec<&str> = attestation["inputs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|input| input["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(vec!["Cargo.toml", "src/lib.rs", "README.tpl"], inputs);
+    assert_eq!("README.md", attestation["output"]["path"]);
+
+    let verify = ["readme", "verify-attestation", "--project-root", root_arg, "attestation.json"];
+    Assert::main_binary()
+        .with_args(&verify)
+        .succeeds()
+        .prints_error("'README.md' matches the attestation 'attestation.json'")
+        .unwrap();
+
+    fs::write(root.path().join("src/lib.rs"), "//! Other docs\n").unwrap();
+    Assert::main_binary()
+        .with_args(&verify)
+        .fails()
+        .prints_error("'src/lib.rs' changed since the readme was generated")
+        .unwrap();
+
+    // the readme of stdout cannot be hashed afterwards
+    Assert::main_binary()
+        .with_args(&["readme", "--project-root", root_arg, "--output", "-", "--attest", "a.json"])
+        .fails()
+        .prints_error("`--attest` needs the readme to be written to a file")
+        .unwrap();
+}
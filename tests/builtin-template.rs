@@ -0,0 +1,65 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED_BADGES: &str = r#"
+# readme-test
+
+[![crates.io](https://img.shields.io/crates/v/readme-test.svg)](https://crates.io/crates/readme-test)
+[![docs.rs](https://docs.rs/readme-test/badge.svg)](https://docs.rs/readme-test)
+
+Test crate for cargo-readme
+
+License: MIT
+"#;
+
+#[test]
+fn badges_template() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/single_line.rs",
+        "--template",
+        "builtin:badges",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED_BADGES)
+        .unwrap();
+}
+
+#[test]
+fn minimal_template_without_title_and_license() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/single_line.rs",
+        "--template",
+        "builtin:minimal",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("Test crate for cargo-readme")
+        .unwrap();
+}
+
+#[test]
+fn unknown_template() {
+    let args = ["readme", "--project-root", "tests/test-project", "--template", "builtin:fancy"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Unknown built-in template 'fancy', expected one of: minimal, badges")
+        .unwrap();
+}
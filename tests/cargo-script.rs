@@ -0,0 +1,40 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn script_with_frontmatter() {
+    let args = [
+        "readme", "--project-root", "tests/cargo-script", "--input", "tool.rs", "--stdout-only",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "# tool\n\n",
+            "Print the arguments it is given\n\n",
+            "A single-file tool, without a `Cargo.toml`.\n\n",
+            "License: MIT",
+        ))
+        .unwrap();
+}
+
+#[test]
+fn script_with_cargo_block() {
+    let args = [
+        "readme", "--project-root", "tests/cargo-script", "--input", "legacy.rs", "--stdout-only",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "# clock\n\n",
+            "Print the time\n\n",
+            "Written for cargo-script.\n\n",
+            "License: Apache-2.0",
+        ))
+        .unwrap();
+}
+
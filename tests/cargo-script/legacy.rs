@@ -0,0 +1,17 @@
+#!/usr/bin/env run-cargo-script
+//! Print the time
+//!
+//! ```cargo
+//! [package]
+//! name = "clock"
+//! license = "Apache-2.0"
+//!
+//! [dependencies]
+//! time = "0.1"
+//! ```
+//!
+//! Written for cargo-script.
+
+fn main() {
+    println!("{}", time::now().rfc822());
+}
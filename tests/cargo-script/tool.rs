@@ -0,0 +1,16 @@
+#!/usr/bin/env -S cargo +nightly -Zscript
+---
+[package]
+version = "0.1.0"
+license = "MIT"
+---
+
+//! Print the arguments it is given
+//!
+//! A single-file tool, without a `Cargo.toml`.
+
+fn main() {
+    for arg in std::env::args().skip(1) {
+        println!("{}", arg);
+    }
+}
@@ -0,0 +1,23 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn versions_of_workspace_crates() {
+    let app = "tests/check-versions/crates/app";
+
+    // the readme is up to date with its template, which has an old version of app-core
+    Assert::main_binary()
+        .with_args(&["readme", "check", "--project-root", app])
+        .succeeds()
+        .unwrap();
+    Assert::main_binary()
+        .with_args(&["readme", "check", "--check-versions", "--project-root", app])
+        .fails()
+        .prints_error(
+            "README.md:5: error: The readme gives version 0.1 of app-core, which is at 0.2.0 in \
+             its Cargo.toml",
+        )
+        .prints_error("README.md has 1 outdated versions")
+        .unwrap();
+}
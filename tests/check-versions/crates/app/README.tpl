@@ -0,0 +1,8 @@
+{{readme}}
+
+```toml
+app = "0.5"
+app-core = "0.1"
+```
+
+License: {{license}}
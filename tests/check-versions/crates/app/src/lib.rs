@@ -0,0 +1 @@
+//! The app
@@ -0,0 +1,85 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn readme_up_to_date() {
+    let args = ["readme", "check", "--project-root", "tests/section-order"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .unwrap();
+}
+
+#[test]
+fn readme_out_of_date() {
+    let args = ["readme", "check", "STALE.md", "--project-root", "tests/section-order"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("STALE.md is out of date")
+        .unwrap();
+}
+
+#[test]
+fn granular_report() {
+    let args = [
+        "readme",
+        "check",
+        "STALE.md",
+        "--project-root",
+        "tests/section-order",
+        "--granular",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error(
+            "Error: STALE.md:11: code block 1 of section 'Quick start' is out of date\n\
+             Error: STALE.md:15: section 'Features' is out of date\n\
+             Error: STALE.md has 2 outdated parts",
+        )
+        .unwrap();
+}
+
+#[test]
+fn docs_reach_thresholds() {
+    let args = [
+        "readme",
+        "check",
+        "--project-root",
+        "tests/section-order",
+        "--min-examples",
+        "1",
+        "--require-intro-words",
+        "6",
+    ];
+
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+}
+
+#[test]
+fn docs_too_thin() {
+    let args = [
+        "readme",
+        "check",
+        "--project-root",
+        "tests/section-order",
+        "--min-examples",
+        "2",
+        "--require-intro-words",
+        "20",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error(
+            "The crate docs are too thin: 1 examples where `--min-examples` requires 2, and 6 \
+             words before the first heading where `--require-intro-words` requires 20",
+        )
+        .unwrap();
+}
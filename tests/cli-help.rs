@@ -0,0 +1,42 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn given_command() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CLI.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints("Test crate for cargo-readme\n\n## Usage\n\n```text\ncargo ")
+        .unwrap();
+}
+
+#[test]
+fn no_exec() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CLI.tpl",
+        "--no-exec",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("running commands was disabled with `--no-exec`")
+        .unwrap();
+}
@@ -0,0 +1,36 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+Test crate for cargo-readme
+
+## Manifest
+
+```toml
+[package]
+name = "readme-test"
+version = "0.1.0"
+```
+"#;
+
+#[test]
+fn include_file_lines() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CODE.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
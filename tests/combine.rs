@@ -0,0 +1,93 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn combine_lib_and_bin() {
+    let args = ["readme", "--project-root", "tests/combine", "--combine", "lib,bin"];
+
+    let expected = r#"
+# combine
+
+## As a library
+
+Parse things
+
+### Examples
+
+```rust
+combine::parse("thing");
+```
+
+## As a CLI
+
+Parse things from the command line
+
+### Usage
+
+```sh
+# parse a thing
+combine thing
+```
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(expected)
+        .unwrap();
+}
+
+#[test]
+fn combine_with_headings_from_settings() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/combine",
+        "--combine",
+        "bin",
+        "--config-profile",
+        "renamed",
+        "--no-license",
+    ];
+
+    let expected = r#"
+# combine
+
+## Command line tool
+
+Parse things from the command line
+
+### Usage
+
+```sh
+# parse a thing
+combine thing
+```
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(expected)
+        .unwrap();
+}
+
+#[test]
+fn combine_missing_target() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--combine",
+        "lib,bin",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("No bin target found in the crate to combine")
+        .unwrap();
+}
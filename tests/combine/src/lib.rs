@@ -0,0 +1,10 @@
+//! Parse things
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() {}
+//! combine::parse("thing");
+//! ```
+
+pub fn parse(_: &str) {}
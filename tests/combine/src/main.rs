@@ -0,0 +1,10 @@
+//! Parse things from the command line
+//!
+//! # Usage
+//!
+//! ```sh
+//! # parse a thing
+//! combine thing
+//! ```
+
+fn main() {}
@@ -0,0 +1,94 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn no_profile() {
+    let args = ["readme", "--project-root", "tests/profiles"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# profiles
+
+Read the [guide](guide.md) or the [docs](https://docs.rs/profiles).
+
+License: MIT
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn profile_with_template() {
+    let args = ["readme", "--project-root", "tests/profiles", "--config-profile", "oss"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# profiles
+
+[![Crates.io](https://img.shields.io/crates/v/profiles.svg)](https://crates.io/crates/profiles)
+
+Read the [guide](guide.md) or the [docs](https://docs.rs/profiles).
+
+License: MIT
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn profile_without_external_links() {
+    let args = ["readme", "--project-root", "tests/profiles", "--config-profile", "internal"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# profiles
+
+Read the [guide](guide.md) or the docs.
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn profile_with_license_section() {
+    let args = [
+        "readme", "--project-root", "tests/profiles", "--config-profile", "license-section",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# profiles
+
+Read the [guide](guide.md) or the [docs](https://docs.rs/profiles).
+
+## License
+
+Licensed under MIT.
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn unknown_profile() {
+    let args = ["readme", "--project-root", "tests/profiles", "--config-profile", "missing"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Config profile 'missing' not found")
+        .unwrap();
+}
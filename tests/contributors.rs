@@ -0,0 +1,54 @@
+extern crate assert_cli;
+
+use std::process::{Command, Stdio};
+
+use assert_cli::Assert;
+
+#[test]
+fn most_active_contributor() {
+    let output = Command::new("git")
+        .args(["shortlog", "-sn", "--no-merges", "HEAD"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let shortlog = String::from_utf8(output.stdout).unwrap();
+    let author = shortlog.lines().next().unwrap().split('\t').nth(1).unwrap();
+
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CONTRIBUTORS.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(format!("Test crate for cargo-readme\n\n## Contributors\n\n- {}", author))
+        .unwrap();
+}
+
+#[test]
+fn no_exec() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CONTRIBUTORS.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-exec",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`{{contributors limit=1}}` needs to run git")
+        .unwrap();
+}
@@ -0,0 +1,26 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+#[cfg(not(feature = "clipboard"))]
+fn copy_without_clipboard_feature() {
+    let args = ["readme", "--project-root", "tests/test-project", "--copy"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`--copy` needs cargo-readme to be built with the `clipboard` feature")
+        .unwrap();
+}
+
+#[test]
+fn copy_conflicts_with_output() {
+    let args = ["readme", "--project-root", "tests/test-project", "--copy", "--output", "-"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("cannot be used with")
+        .unwrap();
+}
@@ -0,0 +1,39 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+#[test]
+fn published_crate_from_cache() {
+    let root = TempDir::copy_of("crates-io");
+    fs::create_dir_all(root.path().join("target/cargo-readme/crates-io")).unwrap();
+    // a fresh cache spares the request to crates.io
+    fs::write(
+        root.path().join("target/cargo-readme/crates-io/cached.json"),
+        r#"{"version":"1.4.2","downloads":1234567,"owners":["alice","github:org:team"]}"#,
+    )
+    .unwrap();
+
+    let root_arg = root.arg();
+    Assert::main_binary()
+        .with_args(&["readme", "--project-root", root_arg, "--crates-io"])
+        .succeeds()
+        .prints_exactly(
+            "Docs\n\nLatest release: 1.4.2, 1.2M downloads, by alice, github:org:team",
+        )
+        .unwrap();
+
+    Assert::main_binary()
+        .with_args(&["readme", "--project-root", root_arg])
+        .fails()
+        .prints_error(
+            "`{{published-version}}` needs the data of the crate on crates.io, fetch it with \
+             `--crates-io`",
+        )
+        .unwrap();
+}
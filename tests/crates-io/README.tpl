@@ -0,0 +1,3 @@
+{{readme}}
+
+Latest release: {{published-version}}, {{total-downloads | humanize}} downloads, by {{owners}}
@@ -0,0 +1,68 @@
+use std::process::Command;
+
+const ARGS: &[&str] = &[
+    "readme",
+    "--project-root",
+    "tests/test-project",
+    "--template",
+    "DATE.tpl",
+    "--input",
+    "src/single_line.rs",
+    "--no-title",
+    "--no-license",
+    "--reproducible",
+];
+
+#[test]
+fn dates_from_source_date_epoch() {
+    // 2024-05-01 08:05:09 UTC
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme"))
+        .args(ARGS)
+        .env("SOURCE_DATE_EPOCH", "1714550709")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        "Test crate for cargo-readme\n\n\
+         Copyright 2024, last updated on May 1, 2024 (2024-05-01).\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn source_date_epoch_without_reproducible() {
+    let args: Vec<&str> = ARGS.iter().cloned().filter(|&arg| arg != "--reproducible").collect();
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme"))
+        .args(&args)
+        .env("SOURCE_DATE_EPOCH", "0")
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Copyright 1970"));
+}
+
+#[test]
+fn reproducible_warns_about_git_history() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme"))
+        .args(["readme", "--project-root", "tests/test-project", "--template", "GIT.tpl"])
+        .args(["--input", "src/single_line.rs", "--no-title", "--no-license", "--reproducible"])
+        .env("SOURCE_DATE_EPOCH", "0")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(
+        "`{{git-sha}}` depends on the git history, the readme cannot be reproduced from the \
+         sources alone"
+    ));
+}
+
+#[test]
+fn reproducible_needs_source_date_epoch() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme"))
+        .args(ARGS)
+        .env_remove("SOURCE_DATE_EPOCH")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("`--reproducible` needs the `SOURCE_DATE_EPOCH` environment variable"));
+}
@@ -0,0 +1,43 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn custom_template_delimiters() {
+    let args = ["readme", "--project-root", "tests/delimiters"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# delimiters
+
+Deploy the crate with Helm
+
+```yaml
+image: {{ .Values.image }}
+```
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn builtin_template_with_custom_delimiters() {
+    let args = ["readme", "--project-root", "tests/delimiters", "--template", "builtin:minimal"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# delimiters
+
+Deploy the crate with Helm
+
+License: MIT
+"#,
+        )
+        .unwrap();
+}
@@ -0,0 +1,7 @@
+# <% crate %>
+
+<% readme %>
+
+```yaml
+image: {{ .Values.image }}
+```
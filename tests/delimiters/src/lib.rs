@@ -0,0 +1 @@
+//! Deploy the crate with Helm
@@ -0,0 +1,39 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# demo
+
+Crate with a demo section
+
+## Demo
+
+![Demo](assets/demo.svg)
+
+![Missing](assets/missing.gif)
+
+[![Recording](https://asciinema.org/a/1234.svg)](https://asciinema.org/a/1234)
+"#;
+
+#[test]
+fn demo_media() {
+    let args = ["readme", "--project-root", "tests/demo", "--no-license"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn warn_missing_media() {
+    let args = ["readme", "--project-root", "tests/demo", "--no-license"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("Warning: demo media 'assets/missing.gif' does not exist")
+        .unwrap();
+}
@@ -0,0 +1,7 @@
+# {{crate}}
+
+{{readme}}
+
+## Demo
+
+{{demo}}
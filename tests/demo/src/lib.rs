@@ -0,0 +1 @@
+//! Crate with a demo section
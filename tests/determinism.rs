@@ -0,0 +1,30 @@
+//! Generating twice gives the same bytes, whatever the order the file system lists things in
+
+use std::process::Command;
+
+const RUNS: &[&[&str]] = &[
+    &["readme", "--project-root", "tests/test-project", "--template", "FILTERS.tpl"],
+    &["readme", "--project-root", "tests/workspace/crates/core", "--no-license"],
+    &["readme", "--project-root", "tests/fixtures/code-blocks"],
+    &["readme", "stats", "--project-root", "tests/recursive", "--recursive", "--json"],
+];
+
+fn run(args: &[&str]) -> (Vec<u8>, Vec<u8>) {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme")).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "`cargo {}` failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (output.stdout, output.stderr)
+}
+
+#[test]
+fn same_output_every_run() {
+    for args in RUNS {
+        let first = run(args);
+        let second = run(args);
+        assert!(first == second, "`cargo {}` gave two different outputs", args.join(" "));
+    }
+}
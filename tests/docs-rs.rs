@@ -0,0 +1,41 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+#[test]
+fn failed_docs_build_from_cache() {
+    let root = TempDir::copy_of("docs-rs");
+    fs::create_dir_all(root.path().join("target/cargo-readme/docs-rs")).unwrap();
+    // a fresh cache spares the request to docs.rs
+    fs::write(
+        root.path().join("target/cargo-readme/docs-rs/broken-docs.json"),
+        r#"{"version":"1.0.0","doc_status":false}"#,
+    )
+    .unwrap();
+
+    let root_arg = root.arg();
+    Assert::main_binary()
+        .with_args(&["readme", "--project-root", root_arg, "--docs-rs"])
+        .succeeds()
+        .prints_exactly("Docs\n\nDocs built: false")
+        .prints_error(
+            "The docs.rs build of broken-docs 1.0.0 failed, links to its docs on docs.rs are \
+             broken",
+        )
+        .unwrap();
+
+    Assert::main_binary()
+        .with_args(&["readme", "--project-root", root_arg])
+        .fails()
+        .prints_error(
+            "`{{docsrs-ok}}` needs the status of the docs build on docs.rs, fetch it with \
+             `--docs-rs`",
+        )
+        .unwrap();
+}
@@ -0,0 +1,3 @@
+{{readme}}
+
+Docs built: {{docsrs-ok}}
@@ -0,0 +1,31 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn loop_over_arrays() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/each",
+        "--no-title",
+        "--no-license",
+        "--stdout-only",
+    ];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "![cli](https://img.shields.io/badge/-cli-blue) ",
+            "![docs](https://img.shields.io/badge/-docs-blue) \n",
+            "\n",
+            "Docs\n",
+            "\n",
+            "## Credits\n",
+            "\n",
+            "- Ada (ada@example.com)\n",
+            "- Grace\n",
+            "- [Ferris](https://rustacean.net)",
+        ))
+        .unwrap();
+}
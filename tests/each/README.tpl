@@ -0,0 +1,12 @@
+{{#each keywords}}![{{this}}](https://img.shields.io/badge/-{{this}}-blue) {{/each}}
+
+{{readme}}
+
+## Credits
+
+{{#each authors}}
+- {{this | replace " <" " (" | replace ">" ")"}}
+{{/each}}
+{{#each metadata.credits}}
+- [{{this.name}}]({{this.url}})
+{{/each}}
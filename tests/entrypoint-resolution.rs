@@ -69,3 +69,38 @@ fn cargo_bin() {
         .prints_exactly("cargo bin")
         .unwrap();
 }
+
+#[test]
+fn prefer_lib_setting() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/entrypoint-resolution/mixed",
+        "--no-title",
+        "--no-license"
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("lib")
+        .unwrap();
+}
+
+#[test]
+fn prefer_bin_flag() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/entrypoint-resolution/mixed",
+        "--no-title",
+        "--no-license",
+        "--prefer-bin"
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("main")
+        .unwrap();
+}
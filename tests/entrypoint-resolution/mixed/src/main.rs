@@ -0,0 +1,5 @@
+//! main
+
+fn main() {
+    println!("hello, world!");
+}
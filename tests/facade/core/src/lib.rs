@@ -0,0 +1,7 @@
+//! Everything the facade re-exports
+//!
+//! # Usage
+//!
+//! Depend on `facade`, not on this crate.
+
+pub fn run() {}
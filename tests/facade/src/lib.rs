@@ -0,0 +1 @@
+pub use facade_core::*;
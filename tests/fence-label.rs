@@ -0,0 +1,34 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn custom_fence_label() {
+    let args = ["readme", "--project-root", "tests/fence-label"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# fence-label
+
+Code blocks with a custom label
+
+```rs
+let answer = 42;
+```
+
+```rs,no_run
+loop {}
+```
+
+```
+plain text
+```
+
+License: MIT
+"#,
+        )
+        .unwrap();
+}
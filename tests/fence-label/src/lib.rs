@@ -0,0 +1,13 @@
+//! Code blocks with a custom label
+//!
+//! ```
+//! let answer = 42;
+//! ```
+//!
+//! ```no_run
+//! loop {}
+//! ```
+//!
+//! ```text
+//! plain text
+//! ```
@@ -0,0 +1,47 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn filter_tag_values() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "FILTERS.tpl",
+        "--input",
+        "src/single_line.rs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("# readme_test 0.1\n\nTest crate for cargo-readme\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn format_values_from_the_command_line() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "VARS.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--var",
+        "downloads=1234567",
+        "--var",
+        "size=1536",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            "# readme-test\n\nTest crate for cargo-readme\n\n1.2M downloads, 1.5 KiB download size",
+        )
+        .unwrap();
+}
@@ -0,0 +1,20 @@
+#!/usr/bin/env -S cargo +nightly -Zscript
+---
+[dependencies]
+clap = { version = "4", features = ["derive"] }
+---
+
+//! Count the lines of the files given.
+//!
+//! Run it directly, cargo builds it on the fly:
+//!
+//! ```sh
+//! ./src/main.rs Cargo.toml
+//! ```
+
+fn main() {
+    for file in std::env::args().skip(1) {
+        let text = std::fs::read_to_string(&file).unwrap();
+        println!("{}: {}", file, text.lines().count());
+    }
+}
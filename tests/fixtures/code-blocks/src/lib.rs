@@ -0,0 +1,43 @@
+//! Code blocks in every place rustdoc accepts them
+//!
+//! # Fences
+//!
+//! ````
+//! ```
+//! # let hidden = true;
+//! ```
+//! ````
+//!
+//! ~~~text
+//! plain text
+//! ~~~
+//!
+//!   ```no_run
+//!   let indented = true;
+//!   	# let hidden_after_a_tab = true;
+//!   ```
+//!
+//! # Quotes and lists
+//!
+//! > ```
+//! > # let hidden = true;
+//! > let quoted = true;
+//! > ```
+//!
+//! 1. Add the dependency:
+//!
+//!    ```toml
+//!    [dependencies]
+//!    ```
+//! 2. Use it:
+//!
+//!    ```
+//!    # use code_blocks::run;
+//!    run();
+//!    ```
+//!
+//! #include <stdio.h> is not a heading
+//!
+//! #hashtag is not one either
+
+pub fn run() {}
@@ -0,0 +1,78 @@
+//! Create a case of the golden tests in `tests/fixtures`
+//!
+//!     cargo run --example golden-fixture -- <name> [<cargo readme arguments>...]
+//!
+//! Creates a crate in `tests/fixtures/<name>` with docs to replace by the ones to test, unless
+//! the directory already exists, saves the arguments in its `args` file and writes the readme
+//! that is generated now to its `expected.md`. Review it before committing it.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+const CARGO_TOML: &str = concat!(
+    "[package]\n",
+    "name = \"{name}\"\n",
+    "version = \"0.1.0\"\n",
+    "license = \"MIT\"\n",
+);
+
+const LIB_RS: &str = concat!(
+    "//! Docs of the case\n",
+    "//!\n",
+    "//! ```\n",
+    "//! # let hidden = true;\n",
+    "//! let shown = true;\n",
+    "//! ```\n",
+);
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let name = match args.next() {
+        Some(name) => name,
+        None => {
+            eprintln!("Usage: golden-fixture <name> [<cargo readme arguments>...]");
+            process::exit(2);
+        }
+    };
+    let readme_args: Vec<String> = args.collect();
+
+    if let Err(e) = generate(&name, &readme_args) {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn generate(name: &str, readme_args: &[String]) -> Result<(), String> {
+    let case = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+    if !case.exists() {
+        fs::create_dir_all(case.join("src")).map_err(|e| format!("{}", e))?;
+        write(&case.join("Cargo.toml"), &CARGO_TOML.replace("{name}", name))?;
+        write(&case.join("src/lib.rs"), LIB_RS)?;
+    }
+
+    let args: String = readme_args.iter().map(|arg| format!("{}\n", arg)).collect();
+    write(&case.join("args"), &args)?;
+
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let output = Command::new(cargo)
+        .args(["run", "--quiet", "--bin", "cargo-readme", "--", "readme", "--project-root"])
+        .arg(&case)
+        .args(readme_args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .map_err(|e| format!("Could not run cargo: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+    write(&case.join("expected.md"), &String::from_utf8_lossy(&output.stdout))?;
+
+    println!("Wrote {}", case.join("expected.md").to_string_lossy());
+    Ok(())
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Could not write '{}': {}", path.to_string_lossy(), e))
+}
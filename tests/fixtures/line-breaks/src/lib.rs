@@ -0,0 +1,18 @@
+//! Parse configuration files. Every file is read once, and the result is cached for later calls.
+//!
+//! # Usage
+//!
+//! - Call [`parse`](fn.parse.html) with the path. It returns an error if the file is invalid.
+//! - Or use `parse_str("key = 1. Value")` to parse text that is already in memory. Both work.
+//!
+//! > Parsing is strict! Unknown keys are rejected, see the [format. Spec](https://example.com).
+//!
+//! | Function | What it does. Briefly, in a single cell |
+//! |----------|-------------------------------------------|
+//! | `parse`  | Reads a file. Then it parses the contents |
+//!
+//! ```
+//! let config = line_breaks::parse_str("a = 1"); // Parsed once. Cached for the next calls.
+//! ```
+//!
+//! A sentence far longer than the limit, which cannot be broken since it has no end before this.
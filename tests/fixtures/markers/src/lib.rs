@@ -0,0 +1,15 @@
+//! [![Crates.io](https://img.shields.io/crates/v/markers.svg)](https://crates.io/crates/markers)
+//!
+//! <!-- readme:title -->
+//!
+//! Place the title and license where the docs say.
+//!
+//! ```text
+//! <!-- readme:license -->
+//! ```
+//!
+//! <!-- readme:license -->
+//!
+//! # Changelog
+//!
+//! See `CHANGELOG.md`.
@@ -0,0 +1,47 @@
+// Copyright 2024 The no-std Authors
+// SPDX-License-Identifier: MIT
+
+#![no_std]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![cfg_attr(
+    not(test),
+    deny(missing_docs, clippy::undocumented_unsafe_blocks)
+)]
+#![doc(html_root_url = "https://docs.rs/no-std/0.1.0")]
+
+//! Fixed capacity collections that never allocate.
+//!
+//! Works on targets without an allocator, like microcontrollers.
+#![doc(html_logo_url = "https://example.com/[logo].svg")]
+//!
+//! # Features
+//!
+//! - `alloc`: conversions to the collections of `alloc`
+/*
+ * The rest of the docs come after the lints, as the crate grew.
+ */
+#![forbid(unsafe_code)]
+#![warn(
+    rust_2018_idioms,
+    missing_debug_implementations,
+)]
+//!
+//! # Example
+//!
+//! ```
+//! let mut stack = no_std::Stack::<4>::new();
+//! stack.push(1);
+//! ```
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// A stack of at most `N` items
+pub struct Stack<const N: usize> {
+    items: [u32; N],
+    len: usize,
+}
+
+mod inner {
+    //! Module docs, which are not the crate docs.
+}
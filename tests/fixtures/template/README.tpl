@@ -0,0 +1,7 @@
+# {{crate}} {{version}}
+
+{{readme}}
+
+## License
+
+{{license}}
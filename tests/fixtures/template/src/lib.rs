@@ -0,0 +1,5 @@
+//! Docs rendered in a template
+//!
+//! # Usage
+//!
+//! Call it.
@@ -0,0 +1,80 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn gitlab_badge_and_links() {
+    let args = ["readme", "--project-root", "tests/forge", "--no-license"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "[![CI](https://gitlab.com/family/tools/badges/main/pipeline.svg)]",
+            "(https://gitlab.com/family/tools/-/pipelines)\n",
+            "\n",
+            "# forge\n",
+            "\n",
+            "Crate hosted on GitLab\n",
+            "\n",
+            "See [the example]",
+            "(https://gitlab.com/family/tools/-/blob/main/tests/forge/examples/basic.rs).",
+        ))
+        .unwrap();
+}
+
+#[test]
+fn override_badge_and_branch() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/forge",
+        "--no-license",
+        "--config-profile",
+        "custom",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints("[![CI](https://ci.example.com/forge.svg)](https://ci.example.com/forge)\n")
+        .prints("(https://gitlab.com/family/tools/-/blob/develop/tests/forge/examples/basic.rs)")
+        .unwrap();
+}
+
+#[test]
+fn no_repository() {
+    let args = ["readme", "--project-root", "tests/test-project", "--template", "FORGE.tpl"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`{{ci-badge}}` was found in template but no repository was provided")
+        .unwrap();
+}
+
+#[test]
+fn absolute_links() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/forge",
+        "--input",
+        "src/links.rs",
+        "--no-template",
+        "--no-title",
+        "--no-license",
+        "--absolute-links",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "![Logo](https://gitlab.com/family/tools/-/raw/main/tests/forge/assets/logo.png)\n",
+            "\n",
+            "Read [the guide](https://gitlab.com/family/tools/-/blob/main/tests/forge/docs/",
+            "guide.md#setup) or [the docs](https://docs.rs/forge).",
+        ))
+        .unwrap();
+}
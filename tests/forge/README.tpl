@@ -0,0 +1,7 @@
+{{ci-badge}}
+
+# {{crate}}
+
+{{readme}}
+
+See [the example]({{source-url "examples/basic.rs"}}).
@@ -0,0 +1 @@
+//! Crate hosted on GitLab
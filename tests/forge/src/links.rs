@@ -0,0 +1,3 @@
+//! ![Logo](assets/logo.png)
+//!
+//! Read [the guide](docs/guide.md#setup) or [the docs](https://docs.rs/forge).
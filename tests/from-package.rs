@@ -0,0 +1,35 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn generate_from_package() {
+    let args = ["readme", "--from-package", "tests/packaged/packaged-0.1.0.crate"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("# packaged\n\nDocs of the published version\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn check_readme_in_package() {
+    let args = ["readme", "check", "--from-package", "tests/packaged/packaged-0.1.0.crate"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .unwrap();
+}
+
+#[test]
+fn not_a_package() {
+    let args = ["readme", "--from-package", "tests/test-project/README.tpl"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Could not unpack 'tests/test-project/README.tpl'")
+        .unwrap();
+}
@@ -0,0 +1,38 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn funding_links() {
+    // the links are found in the directories above the crate, up to the root of its repository
+    let root_arg = "tests/funding/my-crate";
+    let args = ["readme", "--project-root", root_arg, "--stdout-only", "--no-template"];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "# my-crate\n",
+            "\n",
+            "Docs\n",
+            "\n",
+            "License: MIT\n",
+            "\n",
+            "## Support this project\n",
+            "\n",
+            "- [GitHub Sponsors (octocat)](https://github.com/sponsors/octocat)",
+        ))
+        .unwrap();
+
+    // a template with the links does not get them twice
+    let args = ["readme", "--project-root", root_arg, "--stdout-only"];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "Docs\n",
+            "\n",
+            "Sponsors:\n",
+            "- [GitHub Sponsors (octocat)](https://github.com/sponsors/octocat)",
+        ))
+        .unwrap();
+}
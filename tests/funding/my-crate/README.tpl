@@ -0,0 +1,4 @@
+{{readme}}
+
+Sponsors:
+{{funding}}
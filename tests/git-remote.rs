@@ -0,0 +1,54 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn generate_from_git_repository() {
+    let args = [
+        "readme",
+        "--git",
+        env!("CARGO_MANIFEST_DIR"),
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/single_line.rs",
+        "--no-template",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("Test crate for cargo-readme")
+        .unwrap();
+}
+
+#[test]
+fn no_crate_in_repository() {
+    let args = ["readme", "--git", env!("CARGO_MANIFEST_DIR"), "--project-root", "tests"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("No Cargo.toml found in 'tests' of")
+        .unwrap();
+}
+
+#[test]
+fn rev_looking_like_an_option() {
+    let args = [
+        "readme",
+        "--git",
+        env!("CARGO_MANIFEST_DIR"),
+        "--rev=--upload-pack=false",
+        "--project-root",
+        "tests/test-project",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Expected a branch, tag or commit, found '--upload-pack=false'")
+        .unwrap();
+}
@@ -0,0 +1,49 @@
+extern crate assert_cli;
+
+use std::process::Command;
+
+use assert_cli::Assert;
+
+#[test]
+fn git_sha() {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output().unwrap();
+    let sha = String::from_utf8(output.stdout).unwrap();
+
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "GIT.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(format!("Test crate for cargo-readme\n\nGenerated from {}", sha.trim()))
+        .unwrap();
+}
+
+#[test]
+fn no_exec() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "GIT.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-exec",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`{{git-sha}}` needs to run git, but running commands was disabled")
+        .unwrap();
+}
@@ -0,0 +1,139 @@
+//! Golden tests of whole readmes
+//!
+//! Every directory in `tests/fixtures` is a crate whose readme, generated with the arguments in
+//! its `args` file, must be its `expected.md`. Add a case with
+//! `cargo run --example golden-fixture -- <name>`, and after a change that is meant to change the
+//! readmes, update them with `UPDATE_GOLDEN=1 cargo test --test golden` and review the diff.
+
+use std::cmp;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const FIXTURES: &str = "tests/fixtures";
+const CONTEXT: usize = 2;
+
+#[test]
+fn golden_readmes() {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+
+    for case in cases() {
+        let name = case.file_name().unwrap().to_string_lossy().into_owned();
+        let actual = match render(&case) {
+            Ok(actual) => actual,
+            Err(e) => {
+                failures.push(format!("{}: {}", name, e));
+                continue;
+            }
+        };
+
+        let expected_path = case.join("expected.md");
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if actual == expected {
+            continue;
+        }
+        if update {
+            fs::write(&expected_path, &actual).unwrap();
+        } else {
+            failures.push(format!("{}:\n{}", name, diff(&expected, &actual)));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of the readmes are not the expected ones, update them with `UPDATE_GOLDEN=1` if \
+         that is intended\n\n{}",
+        failures.len(),
+        failures.join("\n\n")
+    );
+}
+
+/// Directories of the cases, in order
+fn cases() -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(FIXTURES)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .collect();
+    cases.sort();
+    cases
+}
+
+/// Readme of the case, as printed by `cargo readme`
+fn render(case: &Path) -> Result<String, String> {
+    let args = fs::read_to_string(case.join("args")).unwrap_or_default();
+    let args = args
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme"))
+        .arg("readme")
+        .arg("--project-root")
+        .arg(case)
+        .args(args)
+        .output()
+        .map_err(|e| format!("could not run cargo-readme: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Lines that differ between the readmes, with a few lines around them
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // length of the longest common subsequence of the lines after `i` and `j`
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                cmp::max(common[i + 1][j], common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..lines.len()).filter(|&n| lines[n].0 != ' ').collect();
+    if changed.is_empty() {
+        return "  (the readmes only differ in line endings)".to_owned();
+    }
+
+    let mut result = Vec::new();
+    let mut last_shown = None;
+    for (n, &(mark, line)) in lines.iter().enumerate() {
+        let near_change = changed.iter().any(|&c| c + CONTEXT >= n && n + CONTEXT >= c);
+        if !near_change {
+            continue;
+        }
+        if last_shown.is_some_and(|last| last + 1 < n) {
+            result.push("  ...".to_owned());
+        }
+        result.push(format!("{} {}", mark, line));
+        last_shown = Some(n);
+    }
+
+    result.join("\n")
+}
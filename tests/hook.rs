@@ -0,0 +1,91 @@
+extern crate assert_cli;
+
+mod support;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+/// Run cargo-readme the way cargo-release runs its pre-release hook, with the version being
+/// released and whether it is a dry run in the environment
+#[cfg(unix)]
+fn release_hook(dry_run: &str, args: &[&str]) -> Assert {
+    let mut command = vec![
+        "env",
+        "NEW_VERSION=2.0.0",
+        "PREV_VERSION=1.2.0",
+        "GITHUB_ACTIONS=true",
+        dry_run,
+        "cargo",
+        "run",
+        "--quiet",
+        "--",
+        "readme",
+        "--hook",
+    ];
+    command.extend_from_slice(args);
+    Assert::command(&command)
+}
+
+#[test]
+#[cfg(unix)]
+fn hook_renders_new_version_quietly() {
+    let args = ["--project-root", "tests/unreleased", "--output", "-"];
+
+    release_hook("DRY_RUN=false", &args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# unreleased
+
+A crate whose next version is not released yet
+
+```sh
+cargo install unreleased --version 2.0.0
+```
+"#,
+        )
+        .prints_error_exactly("")
+        .unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn hook_dry_run_writes_nothing() {
+    let args = ["--project-root", "tests/unreleased"];
+
+    release_hook("DRY_RUN=true", &args)
+        .succeeds()
+        .prints_exactly("")
+        .prints_error_exactly("")
+        .unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn hook_dry_run_writes_no_other_files() {
+    let root = TempDir::copy_of("split");
+    let args = [
+        "--project-root",
+        root.arg(),
+        "--no-license",
+        "--split",
+        "DOCUMENTATION.md",
+        "--emit-anchors",
+        "anchors.json",
+    ];
+
+    release_hook("DRY_RUN=true", &args).succeeds().prints_exactly("").unwrap();
+    assert!(!root.path().join("README.md").exists());
+    assert!(!root.path().join("DOCUMENTATION.md").exists());
+    assert!(!root.path().join("anchors.json").exists());
+}
+
+#[test]
+fn hook_exit_codes() {
+    let args = ["readme", "--hook", "--project-root", "tests/does-not-exist"];
+    Assert::main_binary().with_args(&args).fails_with(1).unwrap();
+
+    let args = ["readme", "--hook", "--no-such-flag"];
+    Assert::main_binary().with_args(&args).fails_with(2).unwrap();
+}
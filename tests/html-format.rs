@@ -0,0 +1,16 @@
+extern crate assert_cli;
+
+#[cfg(not(feature = "html"))]
+use assert_cli::Assert;
+
+#[test]
+#[cfg(not(feature = "html"))]
+fn html_without_feature() {
+    let args = ["readme", "--project-root", "tests/test-project", "--format", "html"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`--format html` needs cargo-readme to be built with the `html` feature")
+        .unwrap();
+}
@@ -0,0 +1,35 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+Test crate for cargo-readme
+
+## Performance
+
+| Benchmark | Mean | Median | Std. dev. |
+|-----------|------|--------|-----------|
+| parse/small | 1.25 µs | 1.24 µs | 35.50 ns |
+| render | 2.50 ms | 2.40 ms | 12.00 µs |
+"#;
+
+#[test]
+fn criterion_results() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "BENCH.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
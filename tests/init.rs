@@ -0,0 +1,72 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+#[test]
+fn init_with_defaults() {
+    let root = TempDir::copy_of("init");
+    let args = ["readme", "init", "--yes", "--project-root", root.arg()];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("")
+        .prints_error("Wrote README.tpl")
+        .unwrap();
+
+    let manifest = fs::read_to_string(root.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.contains("[package.metadata.readme]\n"));
+    assert!(!root.path().join(".github").exists());
+
+    let args = ["readme", "--project-root", root.arg()];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("# new-crate\n\nA new crate\n\nLicense: MIT")
+        .unwrap();
+}
+
+#[test]
+fn init_full_with_ci() {
+    let root = TempDir::copy_of("init");
+    let args = [
+        "readme",
+        "init",
+        "--yes",
+        "--style",
+        "full",
+        "--ci",
+        "--project-root",
+        root.arg(),
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("Wrote .github/workflows/readme.yml")
+        .unwrap();
+
+    let args = ["readme", "--project-root", root.arg()];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints("new-crate = \"1.2\"")
+        .unwrap();
+}
+
+#[test]
+fn existing_template() {
+    let args = ["readme", "init", "--yes", "--project-root", "tests/test-project"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("'README.tpl' already exists")
+        .unwrap();
+}
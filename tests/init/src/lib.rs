@@ -0,0 +1 @@
+//! A new crate
@@ -0,0 +1,21 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn link_items_from_list() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/item-links",
+        "--no-title",
+        "--no-license",
+    ];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            "Start with [`Client`](https://docs.rs/item-links/0.2.0/item_links/struct.Client.html).",
+        )
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! Start with `Client`.
@@ -0,0 +1,54 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn item_docs() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/client.rs",
+        "--no-template",
+        "--item",
+        "Client::connect",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# `Client::connect`
+
+Connect to the server
+
+## Errors
+
+Fails if the server is down.
+
+License: MIT
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn item_not_found() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/client.rs",
+        "--item",
+        "Client::disconnect",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Item 'Client::disconnect' not found in the source")
+        .unwrap();
+}
@@ -0,0 +1,49 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# lib-name
+
+Crate whose library has another name
+
+```sh
+cargo add lib-name
+```
+
+```rust
+use renamed::*;
+```
+"#;
+
+#[test]
+fn lib_name_from_manifest() {
+    let args = ["readme", "--project-root", "tests/lib-name", "--no-license"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn lib_name_from_package_name() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "LIB.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("Test crate for cargo-readme\n\n`use readme_test;`")
+        .unwrap();
+}
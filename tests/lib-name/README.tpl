@@ -0,0 +1,11 @@
+# {{crate}}
+
+{{readme}}
+
+```sh
+cargo add {{crate}}
+```
+
+```rust
+use {{lib}}::*;
+```
@@ -0,0 +1 @@
+//! Crate whose library has another name
@@ -0,0 +1,55 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+#[test]
+fn migrate_from_cargo_rdme() {
+    let root = TempDir::copy_of("migrate/rdme");
+    let args = ["readme", "migrate", "--from", "cargo-rdme", "--project-root", root.arg()];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("Wrote README.tpl\nWrote Cargo.toml")
+        .prints_error("`line-terminator` of '.cargo-rdme.toml' has no equivalent and was left out")
+        .unwrap();
+
+    let manifest = fs::read_to_string(root.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.ends_with("template = \"README.tpl\"\nindent-headings = false\n"));
+
+    let args = ["readme", "--project-root", root.arg()];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("# Old crate\n\nNew docs\n\n# Usage")
+        .unwrap();
+}
+
+#[test]
+fn migrate_from_cargo_doc2readme() {
+    let root = TempDir::copy_of("migrate/doc2readme");
+    let args = [
+        "readme", "migrate", "--from", "cargo-doc2readme", "--project-root", root.arg(),
+    ];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("line 1 of 'README.j2' needs to be rewritten by hand: `{{ badges }}`")
+        .prints_error("'README.j2' is no longer needed")
+        .unwrap();
+
+    let template = fs::read_to_string(root.path().join("README.tpl")).unwrap();
+    assert_eq!("# {{crate}} {{ badges }}\n\n{{readme}}\n", template);
+
+    // the template is never overwritten
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("'README.tpl' already exists")
+        .unwrap();
+}
@@ -0,0 +1,3 @@
+//! New docs
+//!
+//! # Usage
@@ -0,0 +1,74 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn too_few_lines() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/single_line.rs",
+        "--no-template",
+        "--min-lines",
+        "5",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Docs are too short: found 1 lines, at least 5 are required")
+        .unwrap();
+}
+
+#[test]
+fn required_sections_found() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/section-order",
+        "--require-sections",
+        "Installation,quick start",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .unwrap();
+}
+
+#[test]
+fn required_sections_missing() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/section-order",
+        "--require-sections",
+        "Installation,Safety,Panics",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Docs are missing required sections: Safety, Panics")
+        .unwrap();
+}
+
+#[test]
+fn readme_too_large() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--max-output-bytes",
+        "100",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("larger than the limit of 100 bytes")
+        .unwrap();
+}
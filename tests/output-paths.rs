@@ -0,0 +1,45 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+use std::path::Path;
+
+use assert_cli::Assert;
+
+use support::{copy_dir, TempDir};
+
+#[test]
+fn outputs_stay_in_project_root() {
+    let dir = TempDir::new("output-paths");
+    let root = dir.path().join("crate");
+    copy_dir(Path::new("tests/output-paths"), &root);
+    fs::create_dir_all(root.join("target")).unwrap();
+
+    let root_arg = root.to_str().unwrap();
+    let readme = |output: &'static str| {
+        vec!["readme", "--project-root", root_arg, "--no-license", "--output", output]
+    };
+
+    Assert::main_binary()
+        .with_args(&readme("../README.md"))
+        .fails()
+        .prints_error("'../README.md' is outside of the project root")
+        .unwrap();
+    assert!(!dir.path().join("README.md").exists());
+
+    let mut args = readme("../README.md");
+    args.push("--allow-outside-root");
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+    assert!(dir.path().join("README.md").is_file());
+
+    // going through `..` is fine as long as the file is in the project root
+    Assert::main_binary().with_args(&readme("src/../README.md")).succeeds().unwrap();
+    assert!(root.join("README.md").is_file());
+
+    Assert::main_binary()
+        .with_args(&readme("target/README.md"))
+        .succeeds()
+        .prints_error("'target/README.md' is in `target/`, which `cargo clean` removes")
+        .unwrap();
+}
@@ -0,0 +1,41 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn render_upcoming_version() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/unreleased",
+        "--override-version",
+        "2.0.0-rc.1",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# unreleased
+
+A crate whose next version is not released yet
+
+```sh
+cargo install unreleased --version 2.0.0-rc.1
+```
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn invalid_version() {
+    let args = ["readme", "--project-root", "tests/unreleased", "--override-version", "next"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("Invalid version 'next', expected a version like 1.2.3")
+        .unwrap();
+}
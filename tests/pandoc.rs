@@ -0,0 +1,14 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn pandoc_needs_output_file() {
+    let args = ["readme", "--project-root", "tests/test-project", "--pandoc-to", "pdf"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`--pandoc-to` needs a file to write to, given with `--output`")
+        .unwrap();
+}
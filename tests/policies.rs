@@ -0,0 +1,46 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+#[test]
+fn policies_from_repository() {
+    // the policies are looked for up to the root of the repository the crate is in
+    let repo = TempDir::copy_of("policies");
+    fs::create_dir_all(repo.path().join(".git")).unwrap();
+    let root = repo.path().join("my-crate");
+
+    let root_arg = root.to_str().unwrap();
+    let args = ["readme", "--project-root", root_arg, "--no-license", "--stdout-only"];
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`{{security}}` was found in template but no SECURITY.md was found")
+        .unwrap();
+
+    fs::write(
+        repo.path().join("SECURITY.md"),
+        "# Security Policy\n\nReport vulnerabilities to security@example.com.\n",
+    )
+    .unwrap();
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(concat!(
+            "Docs\n",
+            "\n",
+            "## Security\n",
+            "\n",
+            "Report vulnerabilities to security@example.com.\n",
+            "\n",
+            "## MSRV\n",
+            "\n",
+            "See [MSRV.md](docs/MSRV.md).",
+        ))
+        .unwrap();
+}
@@ -0,0 +1,9 @@
+{{readme}}
+
+## Security
+
+{{security}}
+
+## MSRV
+
+See {{msrv-policy mode="link"}}.
@@ -0,0 +1,46 @@
+extern crate assert_cli;
+extern crate serde_json;
+
+use std::process::Command;
+
+use assert_cli::Assert;
+use serde_json::Value;
+
+#[test]
+fn print_config_with_sources() {
+    let args = [
+        "readme", "--project-root", "tests/profiles", "--config-profile", "oss", "--no-license",
+        "--print-config",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints("indent-headings = false  # package.metadata.readme\n")
+        .prints("license = false  # command line\n")
+        .prints("template = \"README.oss.tpl\"  # package.metadata.readme.profiles.oss\n")
+        .prints("sembr = false  # default\n")
+        .prints("# not set: ci-badge,")
+        .unwrap();
+}
+
+#[test]
+fn print_config_as_json() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-readme"))
+        .args(["readme", "--project-root", "tests/profiles", "--print-config", "json"])
+        .args(["--max-line-length", "80"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let config: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        serde_json::json!({ "value": 80, "source": "command line" }),
+        config["max-line-length"]
+    );
+    assert_eq!(
+        serde_json::json!({ "value": false, "source": "package.metadata.readme" }),
+        config["indent-headings"]
+    );
+    assert_eq!(serde_json::json!({ "value": null, "source": "default" }), config["forge"]);
+}
@@ -0,0 +1,7 @@
+# {{crate}}
+
+[![Crates.io](https://img.shields.io/crates/v/profiles.svg)](https://crates.io/crates/profiles)
+
+{{readme}}
+
+License: {{license}}
@@ -0,0 +1,3 @@
+//! # Profiles
+//!
+//! Read the [guide](guide.md) or the [docs](https://docs.rs/profiles).
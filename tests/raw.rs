@@ -0,0 +1,50 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn raw_docs() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/raw.rs",
+        "--raw",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+Raw docs
+
+# Usage
+
+```no_run
+# use std::io;
+let input = io::stdin();
+```
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn raw_conflicts_with_template() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--raw",
+        "--template",
+        "README.tpl",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("cannot be used with")
+        .unwrap();
+}
@@ -0,0 +1,51 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+#[test]
+fn write_to_readme_field() {
+    let root = TempDir::copy_of("readme-field");
+    fs::create_dir_all(root.path().join("docs")).unwrap();
+
+    let root_arg = root.arg();
+    let args = ["readme", "--project-root", root_arg, "--no-title", "--no-license"];
+    Assert::main_binary().with_args(&args).succeeds().prints_exactly("").unwrap();
+    assert_eq!(
+        "Docs of the crate.\n",
+        fs::read_to_string(root.path().join("docs/README.md")).unwrap()
+    );
+
+    // `check` looks at the same file
+    let args = ["readme", "check", "--project-root", root_arg, "--no-title", "--no-license"];
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+
+    // writing elsewhere is allowed, with a warning
+    let args = [
+        "readme",
+        "--project-root",
+        root_arg,
+        "--no-title",
+        "--no-license",
+        "--output",
+        "README.md",
+    ];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("but `cargo publish` ships 'docs/README.md'")
+        .unwrap();
+    assert!(root.path().join("README.md").is_file());
+
+    let args = ["readme", "--project-root", root_arg, "--no-title", "--no-license", "--stdout-only"];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("Docs of the crate.")
+        .unwrap();
+}
@@ -0,0 +1,7 @@
+#[macro_use]
+extern crate cargo_readme;
+
+#[test]
+fn readme_is_up_to_date() {
+    assert_readme_up_to_date!("README.md");
+}
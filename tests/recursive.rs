@@ -0,0 +1,51 @@
+extern crate assert_cli;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+#[test]
+fn generate_every_crate_not_ignored() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/recursive",
+        "--recursive",
+        "--output",
+        "README.generated.md",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("")
+        .prints_error(
+            "Generated README.generated.md\n\
+             Generated fixtures/keep/README.generated.md\n\
+             Generated member/README.generated.md",
+        )
+        .unwrap();
+
+    let member = fs::read_to_string("tests/recursive/member/README.generated.md").unwrap();
+    assert_eq!("# recursive-member\n\nMember crate\n\nLicense: MIT\n", member);
+
+    assert!(fs::metadata("tests/recursive/generated/gen/README.generated.md").is_err());
+    assert!(fs::metadata("tests/recursive/fixtures/skip/README.generated.md").is_err());
+}
+
+#[test]
+fn recursive_conflicts_with_input() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/recursive",
+        "--recursive",
+        "--input",
+        "src/lib.rs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! Kept fixture
@@ -0,0 +1 @@
+//! Skipped fixture
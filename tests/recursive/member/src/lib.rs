@@ -0,0 +1 @@
+//! Member crate
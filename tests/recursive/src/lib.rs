@@ -0,0 +1 @@
+//! Root crate
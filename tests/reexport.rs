@@ -0,0 +1,59 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# facade
+
+Everything the facade re-exports
+
+## Usage
+
+Depend on `facade`, not on this crate.
+
+License: MIT
+"#;
+
+#[test]
+fn follow_reexport_docs() {
+    let args = ["readme", "--project-root", "tests/facade", "--follow-reexport-docs"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn input_of_another_crate() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/facade",
+        "--input",
+        "core/src/lib.rs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn no_reexport() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--follow-reexport-docs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("No glob re-export, like `pub use inner::*;`, found in the source")
+        .unwrap();
+}
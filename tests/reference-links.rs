@@ -0,0 +1,25 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn reference_links_at_the_end() {
+    let args = ["readme", "--project-root", "tests/profiles", "--reference-links", "document"];
+
+    let expected = r#"
+# profiles
+
+Read the [guide][1] or the [docs][2].
+
+License: MIT
+
+[1]: guide.md
+[2]: https://docs.rs/profiles
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(expected)
+        .unwrap();
+}
@@ -0,0 +1,24 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn docs_from_rustdoc_json() {
+    // the docs are read from the JSON, not from the source
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/rustdoc-json",
+        "--no-title",
+        "--no-license",
+        "--rustdoc-json",
+        "rustdoc_json.json",
+    ];
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            "Start with [`Client`](https://docs.rs/rustdoc-json/0.3.0/rustdoc_json/struct.Client.html).",
+        )
+        .unwrap();
+}
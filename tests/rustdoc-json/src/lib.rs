@@ -0,0 +1 @@
+//! Outdated docs.
@@ -0,0 +1,73 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn file_outside_project_root() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "SANDBOX.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--sandbox",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("'../../Cargo.toml' is outside of the project root")
+        .unwrap();
+}
+
+#[test]
+fn file_inside_project_root() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CODE.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+        "--sandbox",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .unwrap();
+}
+
+#[test]
+fn no_commands() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CLI.tpl",
+        "--sandbox",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("running commands was disabled with `--no-exec` or `--sandbox`")
+        .unwrap();
+}
+
+#[test]
+fn links_of_workspace_outside_project_root() {
+    let args = ["readme", "--project-root", "tests/workspace/crates/internal", "--sandbox"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("readme-links.toml' is outside of the project root")
+        .unwrap();
+}
@@ -0,0 +1,92 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# section-order
+
+Crate with sections in docs.rs order
+
+## Installation
+
+Add it to `Cargo.toml`.
+
+## Quick start
+
+```rust
+section_order::run();
+```
+
+## Features
+
+None yet.
+
+License: MIT
+"#;
+
+#[test]
+fn reorder_sections() {
+    let args = ["readme", "--project-root", "tests/section-order"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn rename_headings_without_indentation() {
+    let args = ["readme", "--project-root", "tests/section-order", "--no-indent-headings"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints("\n# Quick start\n")
+        .unwrap();
+}
+
+#[test]
+fn numbered_headings() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/section-order",
+        "--no-license",
+        "--number-headings",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints("## 1. Installation")
+        .prints("## 2. Quick start")
+        .unwrap();
+}
+
+#[test]
+fn heading_prefixes() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/section-order",
+        "--no-license",
+        "--config-profile",
+        "emoji",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints(concat!(
+            "## 🚀 Quick start\n",
+            "\n",
+            "```rust\n",
+            "section_order::run();\n",
+            "```\n",
+            "\n",
+            "## Installation\n",
+        ))
+        .prints("\n## ✨ Features\n")
+        .unwrap();
+}
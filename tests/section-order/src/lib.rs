@@ -0,0 +1,19 @@
+//! Crate with sections in docs.rs order
+//!
+//! # Examples
+//!
+//! ```
+//! section_order::run();
+//! ```
+//!
+//! # Features
+//!
+//! None yet.
+//!
+//! # Installation
+//!
+//! Add it to `Cargo.toml`.
+//!
+//! # Rustdoc-only
+//!
+//! The entry point is [`run`](crate::run).
@@ -0,0 +1,39 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn one_sentence_per_line() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/fixtures/line-breaks",
+        "--sembr",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints(concat!(
+            "- Or use `parse_str(\"key = 1. Value\")` to parse text that is already in memory.\n",
+            "  Both work.\n",
+        ))
+        .prints(concat!(
+            "| Function | What it does. Briefly, in a single cell |\n",
+            "|----------|-------------------------------------------|\n",
+        ))
+        .unwrap();
+}
+
+#[test]
+fn sembr_conflicts_with_max_line_length() {
+    let args = ["readme", "--sembr", "--max-line-length", "80"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("cannot be used with")
+        .unwrap();
+}
@@ -0,0 +1,16 @@
+extern crate assert_cli;
+
+#[cfg(not(feature = "serve"))]
+use assert_cli::Assert;
+
+#[test]
+#[cfg(not(feature = "serve"))]
+fn serve_without_feature() {
+    let args = ["readme", "serve", "--project-root", "tests/test-project"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`cargo readme serve` needs cargo-readme to be built with the `serve` feature")
+        .unwrap();
+}
@@ -0,0 +1,24 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn links_shared_by_the_workspace() {
+    let args = ["readme", "--project-root", "tests/workspace/crates/internal"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# family-internal
+
+Internal helpers, following the [guidelines].
+
+License: MIT
+
+[guidelines]: https://example.com/guidelines
+"#,
+        )
+        .unwrap();
+}
@@ -0,0 +1,35 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+Test crate for cargo-readme
+
+## Example
+
+```rust
+let address = "localhost:8080";
+println!("connecting to {}", address);
+```
+"#;
+
+#[test]
+fn include_snippet() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "SNIPPET.tpl",
+        "--input",
+        "src/single_line.rs",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
@@ -0,0 +1,83 @@
+extern crate assert_cli;
+
+mod support;
+
+use std::fs;
+
+use assert_cli::Assert;
+
+use support::TempDir;
+
+const EXPECTED_README: &str = concat!(
+    "# split-crate\n",
+    "\n",
+    "Intro\n",
+    "\n",
+    "## Installation\n",
+    "\n",
+    "Add it to `Cargo.toml`.\n",
+    "\n",
+    "[Read more](DOCUMENTATION.md)\n",
+);
+
+const EXPECTED_DOCUMENTATION: &str = concat!(
+    "## Usage\n",
+    "\n",
+    "Call `run`.\n",
+    "\n",
+    "## Internals\n",
+    "\n",
+    "Many details.\n",
+);
+
+#[test]
+fn split_docs() {
+    let root = TempDir::copy_of("split");
+    let root_arg = root.arg();
+    let args = [
+        "readme",
+        "--project-root",
+        root_arg,
+        "--no-license",
+        "--split",
+        "DOCUMENTATION.md",
+        "--output",
+        "README.md",
+    ];
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+
+    assert_eq!(EXPECTED_README, fs::read_to_string(root.path().join("README.md")).unwrap());
+    assert_eq!(
+        EXPECTED_DOCUMENTATION,
+        fs::read_to_string(root.path().join("DOCUMENTATION.md")).unwrap()
+    );
+
+    let args = [
+        "readme",
+        "check",
+        "--project-root",
+        root_arg,
+        "--no-license",
+        "--split",
+        "DOCUMENTATION.md",
+    ];
+    Assert::main_binary().with_args(&args).succeeds().unwrap();
+
+    // more sections in the readme leave the documentation out of date
+    let args = [
+        "readme",
+        "check",
+        "--project-root",
+        root_arg,
+        "--no-license",
+        "--split",
+        "DOCUMENTATION.md",
+        "--summary-sections",
+        "2",
+    ];
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("DOCUMENTATION.md is out of date")
+        .unwrap();
+}
@@ -0,0 +1,13 @@
+//! Intro
+//!
+//! # Installation
+//!
+//! Add it to `Cargo.toml`.
+//!
+//! # Usage
+//!
+//! Call `run`.
+//!
+//! # Internals
+//!
+//! Many details.
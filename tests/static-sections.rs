@@ -0,0 +1,32 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# static-sections
+
+[![Build](https://example.com/badge.svg)](https://example.com)
+
+Crate documentation
+
+## Contributing
+
+Pull requests are welcome.
+
+## Security
+
+Report vulnerabilities privately.
+
+License: MIT
+"#;
+
+#[test]
+fn prepend_and_append_files() {
+    let args = ["readme", "--project-root", "tests/static-sections"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! Crate documentation
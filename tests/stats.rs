@@ -0,0 +1,50 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn stats_of_crate() {
+    let args = ["readme", "stats", "--project-root", "tests/test-project"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+readme-test
+  doc lines     38
+  sections      3
+  examples      5 (2 ignore, 1 no_run)
+  words         25 (4 in the intro)
+  reading time  1 min
+"#,
+        )
+        .unwrap();
+}
+
+#[test]
+fn stats_as_json() {
+    let args = ["readme", "stats", "--project-root", "tests/workspace/crates/core", "--json"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+[
+  {
+    "crate": "family-core",
+    "examples": 0,
+    "ignored_examples": 0,
+    "intro_words": 5,
+    "lines": 1,
+    "no_run_examples": 0,
+    "reading_minutes": 1,
+    "sections": 0,
+    "words": 5
+  }
+]
+"#,
+        )
+        .unwrap();
+}
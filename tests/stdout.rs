@@ -0,0 +1,87 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED_WARNINGS: &str = r#"
+# readme-test
+
+Test crate for cargo-readme
+
+See [`Item`](crate::Item) for details
+
+```rust
+let unclosed = true;
+
+License: MIT
+"#;
+
+#[test]
+fn warnings_are_not_in_stdout() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--input",
+        "src/warnings.rs",
+        "--stdout-only",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED_WARNINGS)
+        .prints_error("Warning: src/warnings.rs:5: code block is never closed")
+        .unwrap();
+}
+
+#[test]
+fn output_dash_is_stdout() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--input",
+        "src/warnings.rs",
+        "--output",
+        "-",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED_WARNINGS)
+        .unwrap();
+}
+
+#[test]
+fn command_errors_are_not_in_stdout() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--template",
+        "CLI.tpl",
+        "--no-exec",
+        "--stdout-only",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_exactly("")
+        .unwrap();
+}
+
+#[test]
+fn stdout_only_conflicts_with_output() {
+    let args = ["readme", "--stdout-only", "--output", "README.md"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_exactly("")
+        .prints_error("cannot be used with")
+        .unwrap();
+}
@@ -0,0 +1,72 @@
+//! Helpers of the integration tests that write files
+//!
+//! The crates the tests run on are checked in under `tests/`. The tests that write to a crate,
+//! like `readme init` or `--output`, work on a copy of it in a temporary directory instead, which
+//! is removed even when the test fails.
+
+// every test uses only some of the helpers
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory inside the system temporary directory, removed with all its content when dropped
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create a new, empty, temporary directory whose name starts with `prefix`
+    pub fn new(prefix: &str) -> TempDir {
+        let path = env::temp_dir().join(format!(
+            "cargo-readme-{}-{}-{}",
+            prefix,
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        fs::create_dir_all(&path).unwrap();
+        TempDir { path }
+    }
+
+    /// A temporary copy of the checked-in directory `tests/<fixture>`
+    pub fn copy_of(fixture: &str) -> TempDir {
+        let dir = TempDir::new(&fixture.replace('/', "-"));
+        copy_dir(&Path::new("tests").join(fixture), dir.path());
+        dir
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path of the directory, as given to `--project-root`
+    pub fn arg(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Copy the directory `from`, with all its content, to `to`
+pub fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let dest = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &dest);
+        } else {
+            fs::copy(entry.path(), &dest).unwrap();
+        }
+    }
+}
@@ -0,0 +1,85 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn override_block_of_parent() {
+    let args = ["readme", "--project-root", "tests/template-inheritance/my-crate"];
+
+    let expected = r#"
+# my-crate
+
+[![crates.io](https://img.shields.io/crates/v/my-crate.svg)](https://crates.io/crates/my-crate)
+
+Docs of my crate
+
+## Usage
+
+Run `cargo add my-crate`.
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(expected)
+        .unwrap();
+}
+
+#[test]
+fn parent_outside_root_in_sandbox() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/template-inheritance/my-crate",
+        "--sandbox",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("'../base.tpl' is outside of the project root")
+        .unwrap();
+}
+
+#[test]
+fn title_and_license_of_parent() {
+    let args = ["readme", "--project-root", "tests/template-inheritance/titled-crate"];
+
+    let expected = r#"
+# titled-crate
+
+[![crates.io](https://img.shields.io/crates/v/titled-crate.svg)](https://crates.io/crates/titled-crate)
+
+Docs of my crate
+
+## Usage
+
+Run `cargo add titled-crate`.
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(expected)
+        .unwrap();
+}
+
+#[test]
+fn title_of_parent_without_title() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/template-inheritance/titled-crate",
+        "--no-title",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`{{crate}}` was found in template but title should not be rendered")
+        .unwrap();
+}
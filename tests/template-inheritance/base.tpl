@@ -0,0 +1,17 @@
+# {{crate}}
+
+{{block badges}}
+[![crates.io](https://img.shields.io/crates/v/{{crate}}.svg)](https://crates.io/crates/{{crate}})
+{{/block}}
+
+{{readme}}
+
+{{block usage}}
+## Usage
+
+Add `{{crate}}` to your dependencies.
+{{/block}}
+
+{{block footer}}
+License: {{license}}
+{{/block}}
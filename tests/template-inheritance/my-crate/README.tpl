@@ -0,0 +1,7 @@
+{{extends "../base.tpl"}}
+
+{{block usage}}
+## Usage
+
+Run `cargo add {{crate}}`.
+{{/block}}
@@ -0,0 +1 @@
+//! Docs of my crate
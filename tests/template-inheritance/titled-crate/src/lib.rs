@@ -0,0 +1,3 @@
+//! # titled-crate
+//!
+//! Docs of my crate
@@ -0,0 +1,5 @@
+{{readme}}
+
+## Performance
+
+{{include-bench "bench/criterion"}}
@@ -0,0 +1,5 @@
+{{readme}}
+
+## Usage
+
+{{cli-help "cargo --version"}}
@@ -0,0 +1,5 @@
+{{readme}}
+
+## Manifest
+
+{{code "Cargo.toml" lines="1-3"}}
@@ -0,0 +1,5 @@
+{{readme}}
+
+## Contributors
+
+{{contributors limit=1}}
@@ -0,0 +1,3 @@
+{{readme}}
+
+Copyright {{year}}, last updated on {{date "%B %e, %Y"}} ({{date}}).
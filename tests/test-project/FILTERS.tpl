@@ -0,0 +1,5 @@
+# {{crate | replace "-" "_"}} {{version | major-minor}}
+
+{{readme}}
+
+License: {{license | upper}}
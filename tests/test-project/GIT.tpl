@@ -0,0 +1,3 @@
+{{readme}}
+
+Generated from {{git-sha}}
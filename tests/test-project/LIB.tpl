@@ -0,0 +1,3 @@
+{{readme}}
+
+`use {{lib}};`
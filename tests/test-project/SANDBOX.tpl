@@ -0,0 +1,3 @@
+{{readme}}
+
+{{code "../../Cargo.toml"}}
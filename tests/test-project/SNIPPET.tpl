@@ -0,0 +1,5 @@
+{{readme}}
+
+## Example
+
+{{snippet "connect"}}
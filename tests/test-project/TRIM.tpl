@@ -0,0 +1,10 @@
+{{! no blank lines are left when the crate has no keywords }}
+# {{crate}}
+
+{{readme}}
+
+{{#each keywords}}
+- {{this}}
+{{/each -}}
+
+License: {{license}}
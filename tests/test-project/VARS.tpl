@@ -0,0 +1,5 @@
+# {{crate}}
+
+{{readme}}
+
+{{downloads | humanize}} downloads, {{size | bytes}} download size
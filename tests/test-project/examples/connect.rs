@@ -0,0 +1,6 @@
+fn main() {
+    // readme:snippet-begin connect
+    let address = "localhost:8080";
+    println!("connecting to {}", address);
+    // readme:snippet-end
+}
@@ -0,0 +1,15 @@
+//! Crate docs
+
+/// A client
+pub struct Client;
+
+impl Client {
+    /// Connect to the server
+    ///
+    /// # Errors
+    ///
+    /// Fails if the server is down.
+    pub fn connect(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+//! Raw docs
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io;
+//! let input = io::stdin();
+//! ```
@@ -0,0 +1,7 @@
+//! # My great crate
+//!
+//! Does great things.
+//!
+//! # Usage
+//!
+//! Call it.
@@ -0,0 +1,6 @@
+//! Test crate for cargo-readme
+//!
+//! See [`Item`](crate::Item) for details
+//!
+//! ```
+//! let unclosed = true;
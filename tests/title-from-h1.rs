@@ -0,0 +1,111 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn title_from_h1() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/titled.rs",
+        "--no-template",
+        "--set-title-from-h1",
+    ];
+
+    let expected = r#"
+# My great crate
+
+Does great things.
+
+## Usage
+
+Call it.
+
+License: MIT
+"#;
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(expected)
+        .unwrap();
+}
+
+#[test]
+fn warn_about_two_titles() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/titled.rs",
+        "--no-template",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error(
+            "Warning: src/titled.rs:1: The docs start with the title 'My great crate' and the \
+             crate name is added as title too, use `--set-title-from-h1` to only keep the one of \
+             the docs",
+        )
+        .unwrap();
+}
+
+#[test]
+fn no_title_in_docs() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--input",
+        "src/single_line.rs",
+        "--no-template",
+        "--set-title-from-h1",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly("# readme-test\n\nTest crate for cargo-readme\n\nLicense: MIT")
+        .prints_error("do not start with a `# Title` heading, the crate name is used instead")
+        .unwrap();
+}
+
+#[test]
+fn title_same_as_crate_name() {
+    let args = ["readme", "--project-root", "tests/profiles"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            "# profiles\n\nRead the [guide](guide.md) or the [docs](https://docs.rs/profiles).\n\n\
+             License: MIT",
+        )
+        .unwrap();
+}
+
+#[test]
+fn keep_title_same_as_crate_name() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/profiles",
+        "--config-profile",
+        "duplicate-title",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            "# profiles\n\n# Profiles\n\n\
+             Read the [guide](guide.md) or the [docs](https://docs.rs/profiles).\n\nLicense: MIT",
+        )
+        .prints_error("Warning: src/lib.rs:1: The docs start with the title 'Profiles'")
+        .unwrap();
+}
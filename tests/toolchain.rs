@@ -0,0 +1,40 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# toolchain
+
+Crate pinning its toolchain
+
+Written in Rust 2021, built with Rust 1.70.0.
+"#;
+
+#[test]
+fn edition_and_toolchain() {
+    let args = ["readme", "--project-root", "tests/toolchain", "--no-license"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+#[test]
+fn no_toolchain_file() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/lib-name",
+        "--template",
+        "../toolchain/README.tpl",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .fails()
+        .prints_error("`{{toolchain}}` was found in template but no rust-toolchain file was found")
+        .unwrap();
+}
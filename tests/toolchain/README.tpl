@@ -0,0 +1,5 @@
+# {{crate}}
+
+{{readme}}
+
+Written in Rust {{edition}}, built with Rust {{toolchain}}.
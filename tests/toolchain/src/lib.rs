@@ -0,0 +1 @@
+//! Crate pinning its toolchain
@@ -0,0 +1,28 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn warn_about_unreleased_version() {
+    let args = ["readme", "--project-root", "tests/unreleased"];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(
+            r#"
+# unreleased
+
+A crate whose next version is not released yet
+
+```sh
+cargo install unreleased --version 1.3.0
+```
+"#,
+        )
+        .prints_error(
+            "Warning: The readme mentions version 1.3.0, which is not released yet according to \
+             CHANGELOG.md, where the latest release is 1.2.0",
+        )
+        .unwrap();
+}
@@ -0,0 +1,7 @@
+# {{crate}}
+
+{{readme}}
+
+```sh
+cargo install {{crate}} --version {{version}}
+```
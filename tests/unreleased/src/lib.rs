@@ -0,0 +1 @@
+//! A crate whose next version is not released yet
@@ -0,0 +1,59 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+#[test]
+fn unclosed_code_block() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--input",
+        "src/warnings.rs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("Warning: src/warnings.rs:5: code block is never closed")
+        .unwrap();
+}
+
+#[test]
+fn intra_doc_link() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--input",
+        "src/warnings.rs",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("Warning: src/warnings.rs:3: intra-doc links cannot be resolved outside of rustdoc")
+        .unwrap();
+}
+
+#[test]
+fn github_annotations() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/test-project",
+        "--no-template",
+        "--input",
+        "src/warnings.rs",
+        "--message-format",
+        "github",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_error("::warning file=tests/test-project/src/warnings.rs,line=5::code block is never closed")
+        .unwrap();
+}
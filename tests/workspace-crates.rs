@@ -0,0 +1,56 @@
+extern crate assert_cli;
+
+use assert_cli::Assert;
+
+const EXPECTED: &str = r#"
+# family-core
+
+Core types of the family
+
+## Other crates
+
+- [family-cli](../cli): Command line interface of the family
+- [family-internal](../internal)
+"#;
+
+#[test]
+fn link_to_directories() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/workspace/crates/core",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED)
+        .unwrap();
+}
+
+const EXPECTED_CRATES_IO: &str = r#"
+Core types of the family
+
+- [family-cli](https://crates.io/crates/family-cli): Command line interface of the family
+- [family-internal](../internal)
+"#;
+
+#[test]
+fn link_to_crates_io() {
+    let args = [
+        "readme",
+        "--project-root",
+        "tests/workspace/crates/core",
+        "--template",
+        "CRATES_IO.tpl",
+        "--no-title",
+        "--no-license",
+    ];
+
+    Assert::main_binary()
+        .with_args(&args)
+        .succeeds()
+        .prints_exactly(EXPECTED_CRATES_IO)
+        .unwrap();
+}
@@ -0,0 +1 @@
+//! Command line interface of the family
@@ -0,0 +1,3 @@
+{{readme}}
+
+{{workspace-crates links="crates.io"}}
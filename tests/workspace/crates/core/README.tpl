@@ -0,0 +1,7 @@
+# {{crate}}
+
+{{readme}}
+
+## Other crates
+
+{{workspace-crates}}
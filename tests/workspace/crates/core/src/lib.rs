@@ -0,0 +1 @@
+//! Core types of the family
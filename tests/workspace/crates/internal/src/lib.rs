@@ -0,0 +1 @@
+//! Internal helpers, following the [guidelines].